@@ -54,8 +54,56 @@ pub struct ApiOutputField {
     pub description: String,
 }
 
-/// API 注册表类型
-type ApiRegistry = HashMap<String, Vec<ApiDefinition>>;
+impl ApiDefinition {
+    /// 为本地生成的结果（如 diff、portfolio）构造定义，字段依次为 (名称, 类型, 说明)
+    pub fn synthetic(name: &str, description: &str, fields: &[(&str, &str, &str)]) -> Self {
+        ApiDefinition {
+            name: name.to_string(),
+            description: description.to_string(),
+            category: String::new(),
+            doc_id: 0,
+            parameters: Vec::new(),
+            output_fields: fields
+                .iter()
+                .map(|(name, field_type, description)| ApiOutputField::new(name, field_type, description))
+                .collect(),
+            requires_points: None,
+        }
+    }
+
+    /// 为在接口结果上加工得到的数据（如聚合、指标）构造定义，沿用接口的名称和分类
+    pub fn derived(api: Option<&ApiDefinition>, output_fields: Vec<ApiOutputField>) -> Self {
+        ApiDefinition {
+            name: api.map(|a| a.name.clone()).unwrap_or_default(),
+            description: api.map(|a| a.description.clone()).unwrap_or_default(),
+            category: api.map(|a| a.category.clone()).unwrap_or_default(),
+            doc_id: api.map(|a| a.doc_id).unwrap_or_default(),
+            parameters: Vec::new(),
+            output_fields,
+            requires_points: None,
+        }
+    }
+}
+
+#[cfg(test)]
+impl ApiDefinition {
+    /// 测试用的接口定义，字段依次为 (名称, 类型, 说明)
+    pub fn for_test(name: &str, fields: &[(&str, &str, &str)]) -> Self {
+        Self::synthetic(name, name, fields)
+    }
+}
+
+impl ApiOutputField {
+    /// 构造默认显示的输出字段
+    pub fn new(name: &str, field_type: &str, description: &str) -> Self {
+        ApiOutputField {
+            name: name.to_string(),
+            field_type: field_type.to_string(),
+            default_show: true,
+            description: description.to_string(),
+        }
+    }
+}
 
 /// 全局 API 定义缓存
 static mut API_DEFINITIONS_CACHE: Option<HashMap<String, ApiDefinition>> = None;
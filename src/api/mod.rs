@@ -6,4 +6,4 @@ pub mod definitions;
 pub mod search;
 
 pub use definitions::{ApiDefinition, ApiParameter, ApiOutputField, load_api_definitions, get_categories};
-pub use search::{find_api_by_name, search_apis};
+pub use search::{find_api_by_name, search_apis, get_apis_by_category};
@@ -0,0 +1,186 @@
+//! 交易日历本地缓存
+//!
+//! 每个交易所一个 JSON 文件，按整年拉取；请求的区间超出缓存范围时，连同已缓存的
+//! 年份一起重新拉取并覆盖缓存。只是结束日期超出（接口尚未发布的年份）时，一天内
+//! 不重复拉取。
+
+use crate::calendar::{format_date, parse_date, TradeCalendar};
+use crate::client::TushareClient;
+use crate::config::get_cache_dir;
+use crate::error::TResult;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 港交所（使用 hk_tradecal 接口）
+pub const HK_EXCHANGE: &str = "HKEX";
+
+/// 缓存更新时间的格式
+const UPDATED_AT_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// 缓存文件内容
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheFile {
+    exchange: String,
+    start: String,
+    end: String,
+    open_days: Vec<String>,
+    updated_at: String,
+}
+
+/// 交易所日历的缓存文件路径
+pub fn cache_path(exchange: &str) -> PathBuf {
+    get_cache_dir().join("calendar").join(format!("{}.json", exchange.to_uppercase()))
+}
+
+/// 读取缓存，文件不存在或无法解析时为 None
+pub fn read_cache(path: &Path) -> Option<TradeCalendar> {
+    read_cache_file(path).map(|(calendar, _)| calendar)
+}
+
+/// 读取缓存及其更新时间
+fn read_cache_file(path: &Path) -> Option<(TradeCalendar, NaiveDateTime)> {
+    let file: CacheFile = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+    let calendar = TradeCalendar {
+        exchange: file.exchange,
+        start: parse_date(&file.start).ok()?,
+        end: parse_date(&file.end).ok()?,
+        open_days: file.open_days.iter().map(|d| parse_date(d)).collect::<TResult<_>>().ok()?,
+    };
+    Some((calendar, NaiveDateTime::parse_from_str(&file.updated_at, UPDATED_AT_FORMAT).ok()?))
+}
+
+/// 缓存能否用于查询区间：覆盖整个区间，或一天内刚更新过且只是结束日期超出
+/// （超出部分视为接口尚未发布，重新拉取也不会有）
+fn cache_usable(
+    calendar: &TradeCalendar,
+    updated_at: NaiveDateTime,
+    now: NaiveDateTime,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> bool {
+    calendar.covers(start, end) || (calendar.start <= start && now - updated_at < Duration::days(1))
+}
+
+/// 写入缓存
+pub fn write_cache(path: &Path, calendar: &TradeCalendar) -> TResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = CacheFile {
+        exchange: calendar.exchange.clone(),
+        start: format_date(calendar.start),
+        end: format_date(calendar.end),
+        open_days: calendar.open_days.iter().map(|d| format_date(*d)).collect(),
+        updated_at: chrono::Local::now().format(UPDATED_AT_FORMAT).to_string(),
+    };
+    fs::write(path, serde_json::to_string_pretty(&file)?)?;
+    Ok(())
+}
+
+/// 加载覆盖指定区间的交易日历，优先使用本地缓存
+///
+/// 接口尚未发布的日期（如明年）不在返回的日历范围内，此时返回已有的部分，
+/// 调用方可用 [`TradeCalendar::covers`] 判断。
+pub async fn load_calendar(
+    client: &TushareClient,
+    exchange: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> TResult<TradeCalendar> {
+    let path = cache_path(exchange);
+    let cached = read_cache_file(&path);
+    if let Some((calendar, updated_at)) = &cached {
+        if cache_usable(calendar, *updated_at, chrono::Local::now().naive_local(), start, end) {
+            return Ok(calendar.clone());
+        }
+    }
+
+    // 连同已缓存的范围一起按整年拉取
+    let (start, end) = match &cached {
+        Some((c, _)) => (start.min(c.start), end.max(c.end)),
+        None => (start, end),
+    };
+    refresh_calendar(client, exchange, start, end).await
+}
+
+/// 忽略缓存，重新拉取区间所在年份的交易日历并写入缓存
+pub async fn refresh_calendar(
+    client: &TushareClient,
+    exchange: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> TResult<TradeCalendar> {
+    let exchange = exchange.to_uppercase();
+    let first = NaiveDate::from_ymd_opt(start.year(), 1, 1).unwrap_or(start);
+    let last = NaiveDate::from_ymd_opt(end.year(), 12, 31).unwrap_or(end);
+
+    let mut params = serde_json::json!({
+        "start_date": format_date(first),
+        "end_date": format_date(last),
+    });
+    let api_name = if exchange == HK_EXCHANGE {
+        "hk_tradecal"
+    } else {
+        params["exchange"] = serde_json::json!(exchange);
+        "trade_cal"
+    };
+
+    let response = client.call(api_name, Some(params), None).await?;
+    let calendar = TradeCalendar::from_rows(&exchange, &response.to_objects())?;
+
+    // 缓存写入失败（如目录只读）不影响本次查询
+    let _ = write_cache(&cache_path(&exchange), &calendar);
+    Ok(calendar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("tushare-calendar-{}", std::process::id()))
+            .join("SSE.json");
+        let calendar = TradeCalendar {
+            exchange: "SSE".to_string(),
+            start: parse_date("20240101").unwrap(),
+            end: parse_date("20241231").unwrap(),
+            open_days: vec![parse_date("20240102").unwrap(), parse_date("20240103").unwrap()],
+        };
+
+        write_cache(&path, &calendar).unwrap();
+        assert_eq!(read_cache(&path), Some(calendar));
+
+        fs::write(&path, "not json").unwrap();
+        assert_eq!(read_cache(&path), None);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_cache_usable() {
+        let date = |s: &str| parse_date(s).unwrap();
+        let time = |s: &str| NaiveDateTime::parse_from_str(s, UPDATED_AT_FORMAT).unwrap();
+        let calendar = TradeCalendar {
+            exchange: "SSE".to_string(),
+            start: date("20240101"),
+            end: date("20241231"),
+            open_days: vec![date("20241230"), date("20241231")],
+        };
+        let updated_at = time("2024-12-30 09:00:00");
+
+        assert!(cache_usable(&calendar, updated_at, time("2025-03-01 09:00:00"), date("20240601"), date("20240630")));
+        // 明年的日历尚未发布：一天内使用缓存，之后重新拉取
+        assert!(cache_usable(&calendar, updated_at, time("2024-12-30 18:00:00"), date("20241230"), date("20250210")));
+        assert!(!cache_usable(&calendar, updated_at, time("2024-12-31 10:00:00"), date("20241230"), date("20250210")));
+        // 起始日期早于缓存时总是重新拉取
+        assert!(!cache_usable(&calendar, updated_at, time("2024-12-30 18:00:00"), date("20231201"), date("20240110")));
+    }
+
+    #[test]
+    fn test_cache_path() {
+        assert!(cache_path("sse").ends_with("calendar/SSE.json"));
+    }
+}
@@ -0,0 +1,202 @@
+//! 交易日历
+//!
+//! 基于 `trade_cal`（港交所为 `hk_tradecal`）的交易日历，按交易所缓存在本地
+//! （`~/.tushare/cache/calendar/<交易所>.json`），提供是否开市、前后第 N 个交易日
+//! 和区间交易日查询。
+
+pub mod cache;
+
+use crate::error::{TushareError, TResult};
+use crate::output::format_value;
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
+pub use cache::load_calendar;
+
+/// 日期格式（YYYYMMDD）
+pub const DATE_FORMAT: &str = "%Y%m%d";
+
+/// 默认交易所
+pub const DEFAULT_EXCHANGE: &str = "SSE";
+
+/// 解析 YYYYMMDD 格式的日期
+pub fn parse_date(date: &str) -> TResult<NaiveDate> {
+    NaiveDate::parse_from_str(date.trim(), DATE_FORMAT)
+        .map_err(|_| TushareError::ValidationError(format!("无效的日期: {} (格式应为 YYYYMMDD)", date)))
+}
+
+/// 格式化为 YYYYMMDD
+pub fn format_date(date: NaiveDate) -> String {
+    date.format(DATE_FORMAT).to_string()
+}
+
+/// 期货代码后缀对应的交易所
+pub fn futures_exchange(ts_code: &str) -> Option<&'static str> {
+    match ts_code.rsplit_once('.')?.1.to_uppercase().as_str() {
+        "SHF" => Some("SHFE"),
+        "DCE" => Some("DCE"),
+        "ZCE" => Some("CZCE"),
+        "CFX" => Some("CFFEX"),
+        "INE" => Some("INE"),
+        "GFE" => Some("GFEX"),
+        _ => None,
+    }
+}
+
+/// 代码后缀对应的交易日历（港股为港交所，期货为所属交易所，其余为默认交易所）
+pub fn exchange_of(ts_code: &str) -> &'static str {
+    match ts_code.rsplit_once('.') {
+        Some((_, suffix)) if suffix.eq_ignore_ascii_case("HK") => cache::HK_EXCHANGE,
+        _ => futures_exchange(ts_code).unwrap_or(DEFAULT_EXCHANGE),
+    }
+}
+
+/// 单个交易所的交易日历
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeCalendar {
+    /// 交易所
+    pub exchange: String,
+    /// 日历覆盖的起始日期
+    pub start: NaiveDate,
+    /// 日历覆盖的结束日期
+    pub end: NaiveDate,
+    /// 开市日期（升序）
+    pub open_days: Vec<NaiveDate>,
+}
+
+impl TradeCalendar {
+    /// 从 `trade_cal`/`hk_tradecal` 的结果构建（需包含休市日，以确定覆盖区间）
+    pub fn from_rows(exchange: &str, rows: &[HashMap<String, serde_json::Value>]) -> TResult<Self> {
+        let mut days = Vec::new();
+        let mut open_days = Vec::new();
+        for row in rows {
+            let Some(date) = row.get("cal_date") else { continue };
+            let date = parse_date(&format_value(date))?;
+            days.push(date);
+            if row.get("is_open").is_some_and(|v| v == 1 || v == "1") {
+                open_days.push(date);
+            }
+        }
+
+        let (Some(&start), Some(&end)) = (days.iter().min(), days.iter().max()) else {
+            return Err(TushareError::ValidationError(format!("未获取到 {} 的交易日历", exchange)));
+        };
+        open_days.sort();
+        open_days.dedup();
+
+        Ok(Self {
+            exchange: exchange.to_string(),
+            start,
+            end,
+            open_days,
+        })
+    }
+
+    /// 是否覆盖整个区间
+    pub fn covers(&self, start: NaiveDate, end: NaiveDate) -> bool {
+        self.start <= start && end <= self.end
+    }
+
+    /// 是否开市，日期不在日历范围内时为 None
+    pub fn is_open(&self, date: NaiveDate) -> Option<bool> {
+        self.covers(date, date).then(|| self.open_days.binary_search(&date).is_ok())
+    }
+
+    /// 之前的第 N 个交易日（不含当日），N 从 1 开始
+    pub fn prev(&self, date: NaiveDate, n: usize) -> Option<NaiveDate> {
+        let index = self.open_days.partition_point(|d| *d < date);
+        index.checked_sub(n.max(1)).map(|i| self.open_days[i])
+    }
+
+    /// 之后的第 N 个交易日（不含当日），日历范围内不足 N 个时为 None
+    pub fn next(&self, date: NaiveDate, n: usize) -> Option<NaiveDate> {
+        let index = self.open_days.partition_point(|d| *d <= date);
+        index.checked_add(n.max(1) - 1).and_then(|i| self.open_days.get(i)).copied()
+    }
+
+    /// 区间内的交易日（含首尾）
+    pub fn range(&self, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+        self.open_days
+            .iter()
+            .copied()
+            .filter(|d| start <= *d && *d <= end)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn date(s: &str) -> NaiveDate {
+        parse_date(s).unwrap()
+    }
+
+    /// 2024 年元旦前后的上交所日历
+    fn rows() -> Vec<HashMap<String, serde_json::Value>> {
+        [
+            ("20231228", 1),
+            ("20231229", 1),
+            ("20231230", 0),
+            ("20231231", 0),
+            ("20240101", 0),
+            ("20240102", 1),
+            ("20240103", 1),
+            ("20240104", 1),
+        ]
+        .iter()
+        .map(|(d, open)| {
+            [("exchange", json!("SSE")), ("cal_date", json!(d)), ("is_open", json!(open))]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect()
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_is_open() {
+        let calendar = TradeCalendar::from_rows("SSE", &rows()).unwrap();
+        assert_eq!(calendar.start, date("20231228"));
+        assert_eq!(calendar.end, date("20240104"));
+        assert_eq!(calendar.is_open(date("20240102")), Some(true));
+        assert_eq!(calendar.is_open(date("20240101")), Some(false));
+        assert_eq!(calendar.is_open(date("20240105")), None);
+    }
+
+    #[test]
+    fn test_prev_and_next() {
+        let calendar = TradeCalendar::from_rows("SSE", &rows()).unwrap();
+        // 休市日的前后交易日
+        assert_eq!(calendar.prev(date("20240101"), 1), Some(date("20231229")));
+        assert_eq!(calendar.next(date("20240101"), 1), Some(date("20240102")));
+        // 不包含当日
+        assert_eq!(calendar.prev(date("20240103"), 1), Some(date("20240102")));
+        assert_eq!(calendar.next(date("20231229"), 2), Some(date("20240103")));
+        assert_eq!(calendar.prev(date("20240104"), 4), Some(date("20231228")));
+        assert_eq!(calendar.prev(date("20240104"), 5), None);
+        assert_eq!(calendar.next(date("20240103"), 2), None);
+        assert_eq!(calendar.next(date("20231228"), usize::MAX), None);
+    }
+
+    #[test]
+    fn test_range() {
+        let calendar = TradeCalendar::from_rows("SSE", &rows()).unwrap();
+        assert_eq!(
+            calendar.range(date("20231229"), date("20240102")),
+            vec![date("20231229"), date("20240102")]
+        );
+        assert!(TradeCalendar::from_rows("SSE", &[]).is_err());
+    }
+
+    #[test]
+    fn test_exchange_of() {
+        assert_eq!(exchange_of("000001.SZ"), "SSE");
+        assert_eq!(exchange_of("00700.HK"), "HKEX");
+        assert_eq!(exchange_of("CU2409.SHF"), "SHFE");
+        assert_eq!(exchange_of(""), "SSE");
+        assert_eq!(futures_exchange("IF2409.CFX"), Some("CFFEX"));
+        assert_eq!(futures_exchange("600519.SH"), None);
+    }
+}
@@ -0,0 +1,565 @@
+//! 数据质量检查
+//!
+//! `--check` 对接口结果做以下检查（不指定规则时执行全部规则，`gaps` 只用于日线行情接口）：
+//!
+//! - `gaps`：对照交易日历，每个代码在首尾日期之间缺少的交易日（`suspend_d` 中的停牌日除外）
+//! - `dups`：主键（`ts_code`、`trade_date` 等标识字段）重复
+//! - `nulls`：主键和开高低收价格为空
+//! - `ohlc`：不满足 最低价 ≤ 开盘价/收盘价 ≤ 最高价
+//! - `limit`：A 股相对 `pre_close` 的涨跌超出涨跌幅限制（新股上市初期不设限制的交易日除外）
+//!
+//! 发现问题时退出码为 3。
+
+use crate::calendar::{exchange_of, format_date, load_calendar, parse_date, TradeCalendar, DEFAULT_EXCHANGE};
+use crate::client::{Row, TushareClient};
+use chrono::NaiveDate;
+use crate::error::TResult;
+use crate::output::format_value;
+use crate::query::{field_text, value_as_f64};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// 标识一行数据的字段（存在于结果中的部分组成主键）
+const KEY_FIELDS: [&str; 6] = ["ts_code", "exchange", "trade_date", "trade_time", "cal_date", "end_date"];
+
+/// 开高低收价格字段
+const OHLC_FIELDS: [&str; 4] = ["open", "high", "low", "close"];
+
+/// 创业板 20% 涨跌幅限制的起始日期
+const CHINEXT_REFORM_DATE: &str = "20200824";
+
+/// 主板注册制首批新股上市日期，此后主板新股前 5 个交易日不设涨跌幅限制
+const MAIN_BOARD_REGISTRATION_DATE: &str = "20230410";
+
+/// 上市后多少个自然日内检查是否处于不设涨跌幅限制的期间
+const IPO_WINDOW_DAYS: i64 = 30;
+
+/// 默认检查缺少交易日的日线行情接口，其它接口（如龙虎榜、大宗交易）只在某些交易日有数据
+const DAILY_BAR_APIS: [&str; 7] = ["daily", "index_daily", "fund_daily", "hk_daily", "fut_daily", "daily_basic", "adj_factor"];
+
+/// 价格比较的容差
+const EPSILON: f64 = 1e-6;
+
+/// 检查规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheckRule {
+    /// 缺少交易日
+    Gaps,
+    /// 主键重复
+    Duplicates,
+    /// 空值
+    Nulls,
+    /// 开高低收不一致
+    Ohlc,
+    /// 超出涨跌幅限制
+    Limit,
+}
+
+impl CheckRule {
+    /// 全部规则
+    pub const ALL: [CheckRule; 5] = [
+        CheckRule::Gaps,
+        CheckRule::Duplicates,
+        CheckRule::Nulls,
+        CheckRule::Ohlc,
+        CheckRule::Limit,
+    ];
+
+    /// 未指定规则时对接口执行的规则
+    pub fn defaults(api_name: &str) -> Vec<Self> {
+        Self::ALL
+            .into_iter()
+            .filter(|r| *r != CheckRule::Gaps || DAILY_BAR_APIS.contains(&api_name))
+            .collect()
+    }
+
+    /// 从字符串解析规则
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "gaps" | "gap" => Some(CheckRule::Gaps),
+            "dups" | "duplicates" => Some(CheckRule::Duplicates),
+            "nulls" | "null" => Some(CheckRule::Nulls),
+            "ohlc" => Some(CheckRule::Ohlc),
+            "limit" => Some(CheckRule::Limit),
+            _ => None,
+        }
+    }
+
+    /// 解析逗号分隔的规则列表，`all` 为全部规则
+    pub fn parse_list(s: &str) -> Option<Vec<Self>> {
+        if s.trim().eq_ignore_ascii_case("all") {
+            return Some(Self::ALL.to_vec());
+        }
+        let mut rules: Vec<Self> = s.split(',').map(Self::from_str).collect::<Option<_>>()?;
+        rules.sort();
+        rules.dedup();
+        (!rules.is_empty()).then_some(rules)
+    }
+
+    /// 规则名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckRule::Gaps => "gaps",
+            CheckRule::Duplicates => "dups",
+            CheckRule::Nulls => "nulls",
+            CheckRule::Ohlc => "ohlc",
+            CheckRule::Limit => "limit",
+        }
+    }
+}
+
+/// 检查发现的问题
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    /// 规则
+    pub rule: CheckRule,
+    /// 代码
+    pub ts_code: String,
+    /// 日期
+    pub date: String,
+    /// 说明
+    pub message: String,
+}
+
+/// 加载需要的交易日历后执行检查
+///
+/// `rules` 为空时使用 [`CheckRule::defaults`]；周线、月线等非日频接口不检查缺少的交易日。缺少的交易日再对照 `suspend_d`
+/// 排除停牌日，超出涨跌幅限制的行对照 `stock_basic` 的上市日期排除新股上市初期；
+/// 无法获取时保留并提示。
+pub async fn check_data(
+    client: &TushareClient,
+    api_name: &str,
+    data: &[Row],
+    rules: &[CheckRule],
+) -> TResult<Vec<Issue>> {
+    let mut rules = if rules.is_empty() { CheckRule::defaults(api_name) } else { rules.to_vec() };
+    if api_name.contains("week") || api_name.contains("month") {
+        rules.retain(|r| *r != CheckRule::Gaps);
+    }
+
+    let mut calendars = HashMap::new();
+    if rules.contains(&CheckRule::Gaps) {
+        let mut spans: HashMap<&'static str, (String, String)> = HashMap::new();
+        for row in data {
+            let Some(date) = row.get("trade_date").map(format_value).filter(|d| !d.is_empty()) else {
+                continue;
+            };
+            let span = spans.entry(exchange_of(&field_text(row, "ts_code"))).or_insert_with(|| (date.clone(), date.clone()));
+            if date < span.0 {
+                span.0 = date.clone();
+            }
+            if date > span.1 {
+                span.1 = date;
+            }
+        }
+        for (exchange, (start, end)) in spans {
+            let calendar = load_calendar(client, exchange, parse_date(&start)?, parse_date(&end)?).await?;
+            calendars.insert(exchange.to_string(), calendar);
+        }
+    }
+
+    let mut issues = run_checks(data, &rules, &calendars);
+
+    // 按代码查询缺少交易日区间内的停牌（同一代码的缺少交易日按日期排列）
+    let mut spans: BTreeMap<String, (String, String)> = BTreeMap::new();
+    for issue in issues.iter().filter(|i| i.rule == CheckRule::Gaps) {
+        spans
+            .entry(issue.ts_code.clone())
+            .and_modify(|span| span.1 = issue.date.clone())
+            .or_insert_with(|| (issue.date.clone(), issue.date.clone()));
+    }
+    for (code, (start, end)) in spans {
+        let params = serde_json::json!({ "ts_code": code, "start_date": start, "end_date": end, "suspend_type": "S" });
+        match client.call("suspend_d", Some(params), Some("ts_code,trade_date".to_string())).await {
+            Ok(response) => issues = drop_suspended(issues, &response.to_objects()),
+            Err(e) => eprintln!("{}: 获取停牌信息失败，缺少的交易日可能包含停牌: {}", code, e),
+        }
+    }
+
+    // 查询超出涨跌幅限制的代码的上市日期，只关心上市不久的行
+    let limited: BTreeSet<String> =
+        issues.iter().filter(|i| i.rule == CheckRule::Limit).map(|i| i.ts_code.clone()).collect();
+    let mut list_dates = HashMap::new();
+    for code in limited {
+        let params = serde_json::json!({ "ts_code": code });
+        match client.call("stock_basic", Some(params), Some("ts_code,list_date".to_string())).await {
+            Ok(response) => list_dates.extend(
+                response.to_objects().iter().map(|row| (field_text(row, "ts_code"), field_text(row, "list_date"))),
+            ),
+            Err(e) => eprintln!("{}: 获取上市日期失败，新股上市初期可能被误报: {}", code, e),
+        }
+    }
+    let recent: Vec<(NaiveDate, NaiveDate)> = issues
+        .iter()
+        .filter(|i| i.rule == CheckRule::Limit)
+        .filter_map(|i| Some((parse_date(list_dates.get(&i.ts_code)?).ok()?, parse_date(&i.date).ok()?)))
+        .filter(|(listed, date)| (*date - *listed).num_days() <= IPO_WINDOW_DAYS)
+        .collect();
+    if let (Some(start), Some(end)) = (recent.iter().map(|r| r.0).min(), recent.iter().map(|r| r.1).max()) {
+        let calendar = load_calendar(client, DEFAULT_EXCHANGE, start, end).await?;
+        issues = drop_ipo_days(issues, &list_dates, &calendar);
+    }
+
+    Ok(issues)
+}
+
+/// 新股上市后不设涨跌幅限制的交易日数
+///
+/// 科创板、创业板（改革后）和主板注册制新股为前 5 个交易日，其余为上市首日。
+pub fn ipo_free_days(ts_code: &str, list_date: &str) -> usize {
+    let (symbol, suffix) = ts_code.rsplit_once('.').unwrap_or((ts_code, ""));
+    let starts = |prefixes: &[&str]| prefixes.iter().any(|p| symbol.starts_with(p));
+    match suffix.to_uppercase().as_str() {
+        "SH" if starts(&["688", "689"]) => 5,
+        "SZ" if starts(&["300", "301"]) && list_date >= CHINEXT_REFORM_DATE => 5,
+        "SH" | "SZ" if list_date >= MAIN_BOARD_REGISTRATION_DATE => 5,
+        _ => 1,
+    }
+}
+
+/// 去掉新股上市初期（不设涨跌幅限制）的超出涨跌幅限制
+pub fn drop_ipo_days(issues: Vec<Issue>, list_dates: &HashMap<String, String>, calendar: &TradeCalendar) -> Vec<Issue> {
+    let exempt = |issue: &Issue| {
+        let Some(list_date) = list_dates.get(&issue.ts_code) else { return false };
+        let (Ok(listed), Ok(date)) = (parse_date(list_date), parse_date(&issue.date)) else { return false };
+        // 上市日至当日（含）的交易日数
+        let days = calendar.range(listed, date).len();
+        calendar.covers(listed, date) && days > 0 && days <= ipo_free_days(&issue.ts_code, list_date)
+    };
+    issues.into_iter().filter(|i| i.rule != CheckRule::Limit || !exempt(i)).collect()
+}
+
+/// 去掉停牌日（`suspend_d` 的结果）对应的缺少交易日
+pub fn drop_suspended(issues: Vec<Issue>, suspensions: &[Row]) -> Vec<Issue> {
+    let suspended: HashSet<(String, String)> = suspensions
+        .iter()
+        .map(|row| (field_text(row, "ts_code"), field_text(row, "trade_date")))
+        .collect();
+    issues
+        .into_iter()
+        .filter(|i| i.rule != CheckRule::Gaps || !suspended.contains(&(i.ts_code.clone(), i.date.clone())))
+        .collect()
+}
+
+/// 执行检查，`calendars` 为按交易所的交易日历（缺少时跳过缺失交易日检查）
+pub fn run_checks(data: &[Row], rules: &[CheckRule], calendars: &HashMap<String, TradeCalendar>) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    for rule in rules {
+        issues.extend(match rule {
+            CheckRule::Gaps => check_gaps(data, calendars),
+            CheckRule::Duplicates => check_duplicates(data),
+            CheckRule::Nulls => check_nulls(data),
+            CheckRule::Ohlc => check_ohlc(data),
+            CheckRule::Limit => check_limit(data),
+        });
+    }
+    issues
+}
+
+/// 输出检查报告到标准错误
+pub fn print_report(issues: &[Issue], rows: usize) {
+    if issues.is_empty() {
+        eprintln!("数据检查: {} 行，未发现问题", rows);
+        return;
+    }
+    eprintln!("数据检查: {} 行，发现 {} 个问题", rows, issues.len());
+    for issue in issues {
+        eprintln!("  [{}] {} {}: {}", issue.rule.as_str(), issue.ts_code, issue.date, issue.message);
+    }
+}
+
+/// A 股的涨跌幅限制比例，指数、港股、期货等为 None
+pub fn limit_band(ts_code: &str, trade_date: &str) -> Option<f64> {
+    let (symbol, suffix) = ts_code.rsplit_once('.')?;
+    let starts = |prefixes: &[&str]| prefixes.iter().any(|p| symbol.starts_with(p));
+    match suffix.to_uppercase().as_str() {
+        "BJ" => Some(0.3),
+        "SH" if starts(&["688", "689"]) => Some(0.2),
+        "SH" if starts(&["5", "6"]) => Some(0.1),
+        "SZ" if starts(&["300", "301"]) => Some(if trade_date >= CHINEXT_REFORM_DATE { 0.2 } else { 0.1 }),
+        "SZ" if starts(&["00", "15", "16", "18"]) => Some(0.1),
+        _ => None,
+    }
+}
+
+fn issue(rule: CheckRule, row: &Row, message: String) -> Issue {
+    let date = ["trade_date", "trade_time", "cal_date", "end_date"]
+        .iter()
+        .map(|f| field_text(row, f))
+        .find(|d| !d.is_empty())
+        .unwrap_or_default();
+    Issue { rule, ts_code: field_text(row, "ts_code"), date, message }
+}
+
+/// 结果中存在的主键字段
+fn key_fields(data: &[Row]) -> Vec<&'static str> {
+    KEY_FIELDS
+        .iter()
+        .copied()
+        .filter(|f| data.iter().any(|r| r.contains_key(*f)))
+        .collect()
+}
+
+fn check_gaps(data: &[Row], calendars: &HashMap<String, TradeCalendar>) -> Vec<Issue> {
+    let mut groups: HashMap<String, BTreeSet<String>> = HashMap::new();
+    for row in data {
+        let date = field_text(row, "trade_date");
+        if !date.is_empty() {
+            groups.entry(field_text(row, "ts_code")).or_default().insert(date);
+        }
+    }
+
+    let mut codes: Vec<&String> = groups.keys().collect();
+    codes.sort();
+    let mut issues = Vec::new();
+    for code in codes {
+        let dates = &groups[code];
+        let Some(calendar) = calendars.get(exchange_of(code)) else { continue };
+        let (Some(first), Some(last)) = (dates.first(), dates.last()) else { continue };
+        let (Ok(start), Ok(end)) = (parse_date(first), parse_date(last)) else { continue };
+        for day in calendar.range(start, end) {
+            let day = format_date(day);
+            if !dates.contains(&day) {
+                issues.push(Issue {
+                    rule: CheckRule::Gaps,
+                    ts_code: code.clone(),
+                    date: day,
+                    message: format!("{} 交易日缺少数据", calendar.exchange),
+                });
+            }
+        }
+    }
+    issues
+}
+
+fn check_duplicates(data: &[Row]) -> Vec<Issue> {
+    let keys = key_fields(data);
+    if keys.is_empty() {
+        return Vec::new();
+    }
+    let mut counts: HashMap<Vec<String>, usize> = HashMap::new();
+    let mut issues = Vec::new();
+    for row in data {
+        let key: Vec<String> = keys.iter().map(|k| field_text(row, k)).collect();
+        let count = counts.entry(key).or_default();
+        *count += 1;
+        // 每个重复的主键只报告一次
+        if *count == 2 {
+            issues.push(issue(CheckRule::Duplicates, row, format!("主键 ({}) 重复", keys.join(", "))));
+        }
+    }
+    issues
+}
+
+fn check_nulls(data: &[Row]) -> Vec<Issue> {
+    let fields: Vec<&str> = key_fields(data)
+        .into_iter()
+        .chain(OHLC_FIELDS.iter().copied().filter(|f| data.iter().any(|r| r.contains_key(*f))))
+        .collect();
+    let mut issues = Vec::new();
+    for row in data {
+        let empty: Vec<&str> = fields
+            .iter()
+            .copied()
+            .filter(|f| row.get(*f).is_none_or(|v| v.is_null() || v.as_str().is_some_and(|s| s.trim().is_empty())))
+            .collect();
+        if !empty.is_empty() {
+            issues.push(issue(CheckRule::Nulls, row, format!("{} 为空", empty.join(", "))));
+        }
+    }
+    issues
+}
+
+fn check_ohlc(data: &[Row]) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    for row in data {
+        let prices: Option<Vec<f64>> = OHLC_FIELDS.iter().map(|f| row.get(*f).and_then(value_as_f64)).collect();
+        let Some([open, high, low, close]) = prices.as_deref().and_then(|p| <[f64; 4]>::try_from(p).ok()) else {
+            continue;
+        };
+        if low > open.min(close) + EPSILON || high < open.max(close) - EPSILON || low > high + EPSILON {
+            issues.push(issue(
+                CheckRule::Ohlc,
+                row,
+                format!("开 {} 高 {} 低 {} 收 {} 不一致", open, high, low, close),
+            ));
+        }
+    }
+    issues
+}
+
+fn check_limit(data: &[Row]) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    for row in data {
+        let Some(band) = limit_band(&field_text(row, "ts_code"), &field_text(row, "trade_date")) else { continue };
+        let Some(pre_close) = row.get("pre_close").and_then(value_as_f64).filter(|p| *p > 0.0) else {
+            continue;
+        };
+        // 涨跌停价按分四舍五入
+        let limit = (pre_close * band * 100.0).round() / 100.0 + 0.01;
+        let worst = ["close", "high", "low"]
+            .iter()
+            .filter_map(|f| row.get(*f).and_then(value_as_f64))
+            .map(|p| p - pre_close)
+            .max_by(|a, b| a.abs().total_cmp(&b.abs()));
+        if let Some(change) = worst.filter(|c| c.abs() > limit + EPSILON) {
+            issues.push(issue(
+                CheckRule::Limit,
+                row,
+                format!(
+                    "相对昨收 {} 涨跌 {:.2}%，超过 ±{}% 限制",
+                    pre_close,
+                    change / pre_close * 100.0,
+                    band * 100.0
+                ),
+            ));
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::rows_from_json;
+    use serde_json::json;
+
+    fn rules(issues: &[Issue]) -> Vec<(&str, &str, &str)> {
+        issues.iter().map(|i| (i.rule.as_str(), i.ts_code.as_str(), i.date.as_str())).collect()
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        assert_eq!(CheckRule::parse_list("all"), Some(CheckRule::ALL.to_vec()));
+        assert_eq!(
+            CheckRule::parse_list("ohlc, dups,ohlc"),
+            Some(vec![CheckRule::Duplicates, CheckRule::Ohlc])
+        );
+        assert_eq!(CheckRule::parse_list("ohlc,price"), None);
+        assert_eq!(CheckRule::parse_list("daily"), None);
+
+        // 缺少交易日只默认检查日线行情
+        assert_eq!(CheckRule::defaults("daily"), CheckRule::ALL.to_vec());
+        assert!(!CheckRule::defaults("top_list").contains(&CheckRule::Gaps));
+        assert_eq!(CheckRule::defaults("top_list").len(), 4);
+    }
+
+    #[test]
+    fn test_check_gaps() {
+        let calendar = TradeCalendar {
+            exchange: "SSE".to_string(),
+            start: parse_date("20240101").unwrap(),
+            end: parse_date("20240131").unwrap(),
+            open_days: ["20240102", "20240103", "20240104", "20240105"]
+                .iter()
+                .map(|d| parse_date(d).unwrap())
+                .collect(),
+        };
+        let calendars = HashMap::from([("SSE".to_string(), calendar)]);
+        let data = rows_from_json(json!([
+            {"ts_code": "000001.SZ", "trade_date": "20240105"},
+            {"ts_code": "000001.SZ", "trade_date": "20240102"},
+            {"ts_code": "600519.SH", "trade_date": "20240103"},
+            {"ts_code": "600519.SH", "trade_date": "20240104"},
+        ]));
+        let issues = run_checks(&data, &[CheckRule::Gaps], &calendars);
+        assert_eq!(rules(&issues), [("gaps", "000001.SZ", "20240103"), ("gaps", "000001.SZ", "20240104")]);
+
+        // 没有日历时跳过
+        assert!(run_checks(&data, &[CheckRule::Gaps], &HashMap::new()).is_empty());
+
+        // 停牌日不算缺少数据
+        let suspensions = rows_from_json(json!([
+            {"ts_code": "000001.SZ", "trade_date": "20240103"},
+            {"ts_code": "600519.SH", "trade_date": "20240104"},
+        ]));
+        assert_eq!(rules(&drop_suspended(issues, &suspensions)), [("gaps", "000001.SZ", "20240104")]);
+    }
+
+    #[test]
+    fn test_check_duplicates_and_nulls() {
+        let data = rows_from_json(json!([
+            {"ts_code": "000001.SZ", "trade_date": "20240102", "open": 9.0, "high": 9.5, "low": 8.9, "close": 9.2},
+            {"ts_code": "000001.SZ", "trade_date": "20240102", "open": 9.0, "high": 9.5, "low": 8.9, "close": 9.2},
+            {"ts_code": "000001.SZ", "trade_date": "20240102", "open": 9.0, "high": 9.5, "low": 8.9, "close": 9.2},
+            {"ts_code": "000001.SZ", "trade_date": "", "open": null, "high": 9.5, "low": 8.9, "close": 9.2},
+        ]));
+        let issues = run_checks(&data, &[CheckRule::Duplicates, CheckRule::Nulls], &HashMap::new());
+        assert_eq!(rules(&issues), [("dups", "000001.SZ", "20240102"), ("nulls", "000001.SZ", "")]);
+        assert_eq!(issues[1].message, "trade_date, open 为空");
+    }
+
+    #[test]
+    fn test_check_ohlc() {
+        let data = rows_from_json(json!([
+            {"ts_code": "A", "trade_date": "1", "open": 9.0, "high": 9.5, "low": 8.9, "close": 9.5},
+            {"ts_code": "B", "trade_date": "1", "open": 9.0, "high": 9.4, "low": 8.9, "close": 9.5},
+            {"ts_code": "C", "trade_date": "1", "open": 8.8, "high": 9.4, "low": 8.9, "close": 9.0},
+        ]));
+        let issues = run_checks(&data, &[CheckRule::Ohlc], &HashMap::new());
+        assert_eq!(rules(&issues), [("ohlc", "B", "1"), ("ohlc", "C", "1")]);
+    }
+
+    #[test]
+    fn test_check_limit() {
+        assert_eq!(limit_band("300750.SZ", "20200821"), Some(0.1));
+        assert_eq!(limit_band("300750.SZ", "20240102"), Some(0.2));
+        assert_eq!(limit_band("688981.SH", "20240102"), Some(0.2));
+        assert_eq!(limit_band("830799.BJ", "20240102"), Some(0.3));
+        assert_eq!(limit_band("000300.SH", "20240102"), None);
+        assert_eq!(limit_band("00700.HK", "20240102"), None);
+
+        let data = rows_from_json(json!([
+            // 涨停价 1.155 四舍五入为 1.16，涨幅 10.48% 仍在限制内
+            {"ts_code": "600000.SH", "trade_date": "20240102", "pre_close": 1.05, "close": 1.16, "high": 1.16, "low": 1.05},
+            {"ts_code": "600001.SH", "trade_date": "20240102", "pre_close": 10.0, "close": 10.5, "high": 11.2, "low": 10.0},
+            {"ts_code": "300750.SZ", "trade_date": "20240102", "pre_close": 100.0, "close": 82.0, "high": 100.0, "low": 80.0},
+            {"ts_code": "000300.SH", "trade_date": "20240102", "pre_close": 100.0, "close": 150.0, "high": 150.0, "low": 100.0},
+        ]));
+        let issues = run_checks(&data, &[CheckRule::Limit], &HashMap::new());
+        assert_eq!(rules(&issues), [("limit", "600001.SH", "20240102")]);
+        assert!(issues[0].message.contains("12.00%"));
+    }
+
+    #[test]
+    fn test_ipo_exemption() {
+        assert_eq!(ipo_free_days("688981.SH", "20200716"), 5);
+        assert_eq!(ipo_free_days("300999.SZ", "20201015"), 5);
+        assert_eq!(ipo_free_days("300750.SZ", "20180611"), 1);
+        assert_eq!(ipo_free_days("601088.SH", "20071009"), 1);
+        assert_eq!(ipo_free_days("001286.SZ", "20230410"), 5);
+        assert_eq!(ipo_free_days("830799.BJ", "20240102"), 1);
+
+        let calendar = TradeCalendar {
+            exchange: "SSE".to_string(),
+            start: parse_date("20240101").unwrap(),
+            end: parse_date("20240131").unwrap(),
+            open_days: ["20240102", "20240103", "20240104", "20240105", "20240108", "20240109"]
+                .iter()
+                .map(|d| parse_date(d).unwrap())
+                .collect(),
+        };
+        let issue = |code: &str, date: &str| Issue {
+            rule: CheckRule::Limit,
+            ts_code: code.to_string(),
+            date: date.to_string(),
+            message: String::new(),
+        };
+        let list_dates = HashMap::from([
+            ("688001.SH".to_string(), "20240102".to_string()),
+            ("830001.BJ".to_string(), "20240102".to_string()),
+        ]);
+        let issues = vec![
+            issue("688001.SH", "20240108"),
+            issue("688001.SH", "20240109"),
+            issue("830001.BJ", "20240102"),
+            issue("830001.BJ", "20240103"),
+            issue("600000.SH", "20240102"),
+        ];
+        // 科创板前 5 个交易日、北交所首日不设涨跌幅限制
+        assert_eq!(
+            rules(&drop_ipo_days(issues, &list_dates, &calendar)),
+            [("limit", "688001.SH", "20240109"), ("limit", "830001.BJ", "20240103"), ("limit", "600000.SH", "20240102")]
+        );
+    }
+}
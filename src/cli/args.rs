@@ -1,29 +1,46 @@
 //! CLI 参数类型定义
 
+use crate::check::CheckRule;
+use crate::resample::Frequency;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// 输出格式
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum OutputFormat {
     /// JSON 格式
     Json,
     /// 表格格式
+    #[default]
     Table,
     /// CSV 格式
     Csv,
     /// Markdown 格式
     Markdown,
+    /// 纵向记录格式（每个字段一行）
+    Record,
+    /// YAML 格式
+    Yaml,
+    /// TOML 格式（表数组）
+    Toml,
+    /// 独立 HTML 报告
+    Html,
 }
 
 impl OutputFormat {
     /// 从字符串解析输出格式
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "json" => Some(OutputFormat::Json),
             "table" => Some(OutputFormat::Table),
             "csv" => Some(OutputFormat::Csv),
             "markdown" => Some(OutputFormat::Markdown),
+            "record" => Some(OutputFormat::Record),
+            "yaml" | "yml" => Some(OutputFormat::Yaml),
+            "toml" => Some(OutputFormat::Toml),
+            "html" => Some(OutputFormat::Html),
             _ => None,
         }
     }
@@ -35,13 +52,127 @@ impl OutputFormat {
             OutputFormat::Table => "table",
             OutputFormat::Csv => "csv",
             OutputFormat::Markdown => "markdown",
+            OutputFormat::Record => "record",
+            OutputFormat::Yaml => "yaml",
+            OutputFormat::Toml => "toml",
+            OutputFormat::Html => "html",
         }
     }
 }
 
-impl Default for OutputFormat {
-    fn default() -> Self {
-        OutputFormat::Table
+/// 数值显示风格（仅 table/markdown）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberStyle {
+    /// 原始数值
+    #[default]
+    Raw,
+    /// 中文单位（万/亿）
+    Chinese,
+    /// 英文单位（K/M/B）
+    English,
+}
+
+impl NumberStyle {
+    /// 从字符串解析数值显示风格
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "raw" => Some(NumberStyle::Raw),
+            "cn" => Some(NumberStyle::Chinese),
+            "en" => Some(NumberStyle::English),
+            _ => None,
+        }
+    }
+}
+
+/// 终端图表类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartKind {
+    /// 折线图
+    Line,
+    /// K 线图
+    Candle,
+    /// 柱状图
+    Bar,
+}
+
+impl ChartKind {
+    /// 从字符串解析图表类型
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "line" => Some(ChartKind::Line),
+            "candle" | "kline" => Some(ChartKind::Candle),
+            "bar" => Some(ChartKind::Bar),
+            _ => None,
+        }
+    }
+
+    /// 转换为字符串
+    pub fn as_str(&self) -> &str {
+        match self {
+            ChartKind::Line => "line",
+            ChartKind::Candle => "candle",
+            ChartKind::Bar => "bar",
+        }
+    }
+}
+
+/// 表头显示方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HeaderMode {
+    /// 字段名
+    #[default]
+    Name,
+    /// 字段说明
+    Desc,
+    /// 字段说明和字段名
+    Both,
+}
+
+impl HeaderMode {
+    /// 从字符串解析表头显示方式
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "name" => Some(HeaderMode::Name),
+            "desc" => Some(HeaderMode::Desc),
+            "both" => Some(HeaderMode::Both),
+            _ => None,
+        }
+    }
+}
+
+/// 小数位数设置
+///
+/// 由 `--decimals 2` 或 `--decimals close=2,vol=0` 指定，
+/// 按列设置优先于全局设置。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DecimalSpec {
+    /// 全局小数位数
+    pub default: Option<usize>,
+    /// 按列设置的小数位数
+    pub columns: HashMap<String, usize>,
+}
+
+impl DecimalSpec {
+    /// 解析小数位数设置
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut spec = DecimalSpec::default();
+        for part in s.split(',').map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            match part.split_once('=') {
+                Some((column, n)) => {
+                    spec.columns.insert(column.trim().to_string(), n.trim().parse().ok()?);
+                }
+                None => spec.default = Some(part.parse().ok()?),
+            }
+        }
+        Some(spec)
+    }
+
+    /// 获取某列的小数位数
+    pub fn for_column(&self, column: &str) -> Option<usize> {
+        self.columns.get(column).copied().or(self.default)
     }
 }
 
@@ -59,6 +190,7 @@ pub enum ParamValue {
 
 impl ParamValue {
     /// 从字符串解析参数值
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: String) -> Self {
         // 尝试解析为数字
         if let Ok(n) = s.parse::<f64>() {
@@ -77,6 +209,22 @@ impl ParamValue {
     }
 }
 
+impl std::fmt::Display for ParamValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParamValue::String(s) => write!(f, "{}", s),
+            ParamValue::Number(n) => {
+                if n.fract() == 0.0 {
+                    write!(f, "{}", *n as i64)
+                } else {
+                    write!(f, "{}", n)
+                }
+            }
+            ParamValue::Boolean(b) => write!(f, "{}", b),
+        }
+    }
+}
+
 impl From<String> for ParamValue {
     fn from(s: String) -> Self {
         ParamValue::from_str(s)
@@ -101,8 +249,11 @@ impl From<bool> for ParamValue {
     }
 }
 
+/// 默认单元格最大显示宽度
+pub const DEFAULT_MAX_WIDTH: usize = 30;
+
 /// CLI 选项
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct Options {
     /// 输出格式
     pub format: OutputFormat,
@@ -114,6 +265,81 @@ pub struct Options {
     pub help: bool,
     /// 显示版本
     pub version: bool,
+    /// 单元格最大显示宽度（仅 table/markdown，0 表示不截断）
+    pub max_width: usize,
+    /// 最大显示行数（仅 table/markdown，0 表示不限制；未指定时仅在终端中限制）
+    pub max_rows: Option<usize>,
+    /// 只保留前 N 行
+    pub head: Option<usize>,
+    /// 只保留后 N 行
+    pub tail: Option<usize>,
+    /// 禁用分页程序
+    pub no_pager: bool,
+    /// 数值显示风格
+    pub number_style: NumberStyle,
+    /// 小数位数设置
+    pub decimals: DecimalSpec,
+    /// 禁用涨跌着色
+    pub no_color: bool,
+    /// 表头显示方式（table/markdown/csv）
+    pub header: HeaderMode,
+    /// 输出文件（仅 html）
+    pub output: Option<String>,
+    /// 终端图表类型（替代表格输出）
+    pub chart: Option<ChartKind>,
+    /// 图表横轴字段
+    pub chart_x: Option<String>,
+    /// 图表纵轴字段
+    pub chart_y: Option<String>,
+    /// 过滤表达式（--where）
+    pub where_clause: Option<String>,
+    /// 分组字段（--group-by）
+    pub group_by: Option<Vec<String>>,
+    /// 聚合设置（--agg，如 `sum(amount),count()`）
+    pub agg: Option<String>,
+    /// 排序设置（--sort，`-` 前缀表示降序）
+    pub sort: Option<String>,
+    /// 输出字段及顺序（--select）
+    pub select: Option<Vec<String>>,
+    /// 技术指标（--indicators，如 `ma5,macd,rsi14`）
+    pub indicators: Option<String>,
+    /// K 线重采样周期（--resample）
+    pub resample: Option<Frequency>,
+    /// 数据质量检查规则（--check），空列表表示按接口选择默认规则
+    pub check: Option<Vec<CheckRule>>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            format: OutputFormat::default(),
+            pretty: false,
+            token: None,
+            help: false,
+            version: false,
+            max_width: DEFAULT_MAX_WIDTH,
+            max_rows: None,
+            head: None,
+            tail: None,
+            no_pager: false,
+            number_style: NumberStyle::default(),
+            decimals: DecimalSpec::default(),
+            no_color: false,
+            header: HeaderMode::default(),
+            output: None,
+            chart: None,
+            chart_x: None,
+            chart_y: None,
+            where_clause: None,
+            group_by: None,
+            agg: None,
+            sort: None,
+            select: None,
+            indicators: None,
+            resample: None,
+            check: None,
+        }
+    }
 }
 
 /// 解析后的参数
@@ -189,9 +415,43 @@ mod tests {
         assert_eq!(OutputFormat::from_str("table"), Some(OutputFormat::Table));
         assert_eq!(OutputFormat::from_str("csv"), Some(OutputFormat::Csv));
         assert_eq!(OutputFormat::from_str("markdown"), Some(OutputFormat::Markdown));
+        assert_eq!(OutputFormat::from_str("record"), Some(OutputFormat::Record));
+        assert_eq!(OutputFormat::from_str("yml"), Some(OutputFormat::Yaml));
+        assert_eq!(OutputFormat::from_str("toml"), Some(OutputFormat::Toml));
+        assert_eq!(OutputFormat::from_str("html"), Some(OutputFormat::Html));
         assert_eq!(OutputFormat::from_str("invalid"), None);
     }
 
+    #[test]
+    fn test_number_style_from_str() {
+        assert_eq!(NumberStyle::from_str("cn"), Some(NumberStyle::Chinese));
+        assert_eq!(NumberStyle::from_str("EN"), Some(NumberStyle::English));
+        assert_eq!(NumberStyle::from_str("raw"), Some(NumberStyle::Raw));
+        assert_eq!(NumberStyle::from_str("fr"), None);
+    }
+
+    #[test]
+    fn test_header_mode_from_str() {
+        assert_eq!(HeaderMode::from_str("desc"), Some(HeaderMode::Desc));
+        assert_eq!(HeaderMode::from_str("Both"), Some(HeaderMode::Both));
+        assert_eq!(HeaderMode::from_str("name"), Some(HeaderMode::Name));
+        assert_eq!(HeaderMode::from_str("zh"), None);
+    }
+
+    #[test]
+    fn test_decimal_spec_parse() {
+        let spec = DecimalSpec::parse("2,vol=0,amount=1").unwrap();
+        assert_eq!(spec.for_column("close"), Some(2));
+        assert_eq!(spec.for_column("vol"), Some(0));
+        assert_eq!(spec.for_column("amount"), Some(1));
+
+        let spec = DecimalSpec::parse("close=3").unwrap();
+        assert_eq!(spec.for_column("close"), Some(3));
+        assert_eq!(spec.for_column("open"), None);
+
+        assert!(DecimalSpec::parse("close=x").is_none());
+    }
+
     #[test]
     fn test_param_value_from_str() {
         assert_eq!(ParamValue::from_str("123".to_string()), ParamValue::Number(123.0));
@@ -203,6 +463,13 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_param_value_display() {
+        assert_eq!(ParamValue::Number(20240101.0).to_string(), "20240101");
+        assert_eq!(ParamValue::Number(1.5).to_string(), "1.5");
+        assert_eq!(ParamValue::from("market.db").to_string(), "market.db");
+    }
+
     #[test]
     fn test_parsed_args() {
         let mut args = ParsedArgs::new("stock_basic".to_string());
@@ -0,0 +1,334 @@
+//! 复权行情命令
+//!
+//! `tushare bar --ts-code 600519.SH --adj qfq --freq D` 调用 `daily`（`W`/`M` 为
+//! `weekly`/`monthly`）和 `adj_factor`，按 Python SDK `pro_bar` 的方式在本地计算
+//! 前复权/后复权价格：
+//!
+//! - 后复权：价格 × 当日复权因子
+//! - 前复权：价格 × 当日复权因子 / 最新复权因子
+//!
+//! 价格保留两位小数，`change` 和 `pct_chg` 按复权后的价格重新计算。
+
+use crate::api::{find_api_by_name, load_api_definitions};
+use crate::cli::args::ParsedArgs;
+use crate::cli::commands::{create_client, request_api};
+use crate::client::TushareClient;
+use crate::error::{TushareError, TResult};
+use crate::output::output_data;
+use crate::query::{field_text, number_value, value_as_f64};
+use std::collections::HashMap;
+
+/// 需要复权的价格字段
+const PRICE_FIELDS: [&str; 5] = ["open", "close", "high", "low", "pre_close"];
+
+/// 传给行情和复权因子接口的参数
+const BAR_PARAMS: [&str; 3] = ["ts_code", "start_date", "end_date"];
+
+/// 复权方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdjustKind {
+    /// 前复权
+    Qfq,
+    /// 后复权
+    Hfq,
+}
+
+impl AdjustKind {
+    /// 从字符串解析复权方式
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "qfq" => Some(AdjustKind::Qfq),
+            "hfq" => Some(AdjustKind::Hfq),
+            _ => None,
+        }
+    }
+}
+
+/// K 线周期
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BarFreq {
+    /// 日线
+    #[default]
+    Daily,
+    /// 周线
+    Weekly,
+    /// 月线
+    Monthly,
+}
+
+impl BarFreq {
+    /// 从字符串解析周期（D/W/M）
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_uppercase().as_str() {
+            "D" => Some(BarFreq::Daily),
+            "W" => Some(BarFreq::Weekly),
+            "M" => Some(BarFreq::Monthly),
+            _ => None,
+        }
+    }
+
+    /// 对应的行情接口
+    pub fn api_name(&self) -> &'static str {
+        match self {
+            BarFreq::Daily => "daily",
+            BarFreq::Weekly => "weekly",
+            BarFreq::Monthly => "monthly",
+        }
+    }
+}
+
+/// 处理 bar 命令
+///
+/// `codes` 为 `--ts-code @名称` 展开后的代码，多个代码时逐个请求后合并。
+pub async fn run_bar(args: &ParsedArgs, codes: Option<Vec<String>>) -> TResult<()> {
+    if args.get_param("ts_code").is_none() {
+        return Err(TushareError::ParseError(
+            "请指定 --ts-code，例如: tushare bar --ts-code 600519.SH --adj qfq".to_string(),
+        ));
+    }
+    let freq = match args.get_param("freq") {
+        Some(v) => BarFreq::from_str(&v.to_string())
+            .ok_or_else(|| TushareError::ParseError(format!("无效的周期: {} (可选 D|W|M)", v)))?,
+        None => BarFreq::default(),
+    };
+    let adj = match args.get_param("adj") {
+        Some(v) => Some(
+            AdjustKind::from_str(&v.to_string())
+                .ok_or_else(|| TushareError::ParseError(format!("无效的复权方式: {} (可选 qfq|hfq)", v)))?,
+        ),
+        None => None,
+    };
+
+    // 参数值保留为字符串，避免代码的前导零丢失
+    let params: serde_json::Map<String, serde_json::Value> = BAR_PARAMS
+        .iter()
+        .filter_map(|&key| {
+            args.get_param(key)
+                .map(|v| (key.to_string(), serde_json::Value::String(v.to_string())))
+        })
+        .collect();
+    let params = serde_json::Value::Object(params);
+
+    let client = create_client(args)?;
+    let rows = match codes {
+        Some(codes) if codes.len() > 1 => {
+            let mut rows = Vec::new();
+            for (i, code) in codes.iter().enumerate() {
+                let mut params = params.clone();
+                params["ts_code"] = serde_json::json!(code);
+                let bars = fetch_bars(&client, freq, adj, params).await?;
+                eprintln!("[{}/{}] {}: {} 行", i + 1, codes.len(), code, bars.len());
+                rows.extend(bars);
+            }
+            rows
+        }
+        _ => fetch_bars(&client, freq, adj, params).await?,
+    };
+
+    let definitions = load_api_definitions();
+    let api = find_api_by_name(&definitions, freq.api_name());
+    output_data(&rows, &args.options, api, Some(&args.params_to_json()))
+}
+
+/// 获取行情，指定复权方式时按复权因子调整
+async fn fetch_bars(
+    client: &TushareClient,
+    freq: BarFreq,
+    adj: Option<AdjustKind>,
+    params: serde_json::Value,
+) -> TResult<Vec<HashMap<String, serde_json::Value>>> {
+    let rows = request_api(client, freq.api_name(), params.clone()).await?.to_objects();
+    let Some(adj) = adj else { return Ok(rows) };
+
+    let factors = request_api(client, "adj_factor", params).await?.to_objects();
+    if factors.is_empty() {
+        eprintln!("未获取到复权因子");
+    }
+    Ok(adjust_prices(&rows, &factors, adj))
+}
+
+/// 按复权因子计算复权价格
+///
+/// `rows` 与 `factors` 均按接口返回的顺序（交易日期倒序），可包含多个 `ts_code`，
+/// 按代码分别计算。缺少因子的行取同一代码之后（更早）最近一个交易日的因子；
+/// 前复权以该代码在 `factors` 中第一行（最新）的因子为基准。没有复权因子时返回空数据。
+pub fn adjust_prices(
+    rows: &[HashMap<String, serde_json::Value>],
+    factors: &[HashMap<String, serde_json::Value>],
+    adj: AdjustKind,
+) -> Vec<HashMap<String, serde_json::Value>> {
+    if factors.is_empty() {
+        return Vec::new();
+    }
+
+    let factor_of = |row: &HashMap<String, serde_json::Value>| row.get("adj_factor").and_then(value_as_f64);
+    let mut base: HashMap<String, f64> = HashMap::new();
+    let mut by_date: HashMap<(String, String), f64> = HashMap::new();
+    for f in factors {
+        let Some(factor) = factor_of(f) else { continue };
+        let code = field_text(f, "ts_code");
+        base.entry(code.clone()).or_insert(factor);
+        by_date.insert((code, field_text(f, "trade_date")), factor);
+    }
+
+    // 从后向前按代码填充缺失的因子
+    let mut row_factors: Vec<Option<f64>> = rows
+        .iter()
+        .map(|r| by_date.get(&(field_text(r, "ts_code"), field_text(r, "trade_date"))).copied())
+        .collect();
+    let mut next: HashMap<String, f64> = HashMap::new();
+    for (row, factor) in rows.iter().zip(row_factors.iter_mut()).rev() {
+        let code = field_text(row, "ts_code");
+        match factor {
+            Some(f) => {
+                next.insert(code, *f);
+            }
+            None => *factor = next.get(&code).copied(),
+        }
+    }
+
+    rows.iter()
+        .zip(row_factors)
+        .map(|(row, factor)| {
+            let base = base.get(&field_text(row, "ts_code")).copied();
+            let mut row = row.clone();
+            for field in PRICE_FIELDS {
+                let price = row.get(field).and_then(value_as_f64);
+                let adjusted = match (price, factor, adj) {
+                    (Some(price), Some(factor), AdjustKind::Hfq) => Some(round2(price * factor)),
+                    (Some(price), Some(factor), AdjustKind::Qfq) => base.map(|base| round2(price * factor / base)),
+                    _ => None,
+                };
+                row.insert(field.to_string(), number_value(adjusted));
+            }
+
+            let close = row.get("close").and_then(value_as_f64);
+            let pre_close = row.get("pre_close").and_then(value_as_f64);
+            let change = close.zip(pre_close).map(|(c, p)| c - p);
+            let pct_chg = change.zip(pre_close).map(|(c, p)| round2(c / p * 100.0));
+            row.insert("change".to_string(), number_value(change));
+            row.insert("pct_chg".to_string(), number_value(pct_chg));
+            row
+        })
+        .collect()
+}
+
+/// 保留两位小数（与 SDK 的 `'%.2f' % x` 一致）
+fn round2(value: f64) -> f64 {
+    format!("{:.2}", value).parse().unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::TushareResponse;
+
+    fn fixture(json: &str) -> Vec<HashMap<String, serde_json::Value>> {
+        serde_json::from_str::<TushareResponse>(json).unwrap().to_objects()
+    }
+
+    fn daily() -> Vec<HashMap<String, serde_json::Value>> {
+        fixture(include_str!("../../tests/fixtures/bar_600519_daily.json"))
+    }
+
+    fn factors() -> Vec<HashMap<String, serde_json::Value>> {
+        fixture(include_str!("../../tests/fixtures/bar_600519_adj_factor.json"))
+    }
+
+    /// 期望值由 SDK 的复权算法对同一份数据计算得到
+    fn assert_matches_expected(adj: AdjustKind, key: &str) {
+        let expected: serde_json::Value =
+            serde_json::from_str(include_str!("../../tests/fixtures/bar_600519_expected.json")).unwrap();
+        let expected = expected[key].as_array().unwrap();
+        let rows = adjust_prices(&daily(), &factors(), adj);
+        assert_eq!(rows.len(), expected.len());
+
+        for (row, want) in rows.iter().zip(expected) {
+            assert_eq!(row["trade_date"], want["trade_date"]);
+            for field in ["open", "high", "low", "close", "pre_close", "change", "pct_chg"] {
+                let got = row[field].as_f64().unwrap();
+                let want = want[field].as_f64().unwrap();
+                assert!((got - want).abs() < 1e-9, "{} {}: {} != {}", key, field, got, want);
+            }
+            assert!(!row.contains_key("adj_factor"));
+        }
+    }
+
+    #[test]
+    fn test_qfq_matches_sdk() {
+        assert_matches_expected(AdjustKind::Qfq, "qfq");
+        // 最新的交易日价格不变
+        let rows = adjust_prices(&daily(), &factors(), AdjustKind::Qfq);
+        assert_eq!(rows[0]["close"], serde_json::json!(1705.0));
+    }
+
+    #[test]
+    fn test_hfq_matches_sdk() {
+        assert_matches_expected(AdjustKind::Hfq, "hfq");
+    }
+
+    #[test]
+    fn test_missing_factor_is_filled_from_earlier_day() {
+        // 20230703 没有复权因子，取 20230630 的因子
+        let rows = adjust_prices(&daily(), &factors(), AdjustKind::Hfq);
+        assert_eq!(rows[1]["close"], serde_json::json!(13859.33));
+    }
+
+    #[test]
+    fn test_multiple_codes() {
+        let row = |code: &str, date: &str, close: f64| -> HashMap<String, serde_json::Value> {
+            vec![
+                ("ts_code".to_string(), serde_json::json!(code)),
+                ("trade_date".to_string(), serde_json::json!(date)),
+                ("close".to_string(), serde_json::json!(close)),
+            ]
+            .into_iter()
+            .collect()
+        };
+        let factor = |code: &str, date: &str, factor: f64| -> HashMap<String, serde_json::Value> {
+            vec![
+                ("ts_code".to_string(), serde_json::json!(code)),
+                ("trade_date".to_string(), serde_json::json!(date)),
+                ("adj_factor".to_string(), serde_json::json!(factor)),
+            ]
+            .into_iter()
+            .collect()
+        };
+        let rows = vec![
+            row("000001.SZ", "20240103", 10.0),
+            row("600519.SH", "20240103", 1700.0),
+            row("000001.SZ", "20240102", 10.0),
+            row("600519.SH", "20240102", 1700.0),
+        ];
+        // 000001.SZ 的 20240103 缺少因子，只能取自己更早的因子
+        let factors = vec![
+            factor("600519.SH", "20240103", 8.0),
+            factor("000001.SZ", "20240102", 2.0),
+            factor("600519.SH", "20240102", 4.0),
+        ];
+
+        let hfq = adjust_prices(&rows, &factors, AdjustKind::Hfq);
+        let closes: Vec<f64> = hfq.iter().map(|r| r["close"].as_f64().unwrap()).collect();
+        assert_eq!(closes, vec![20.0, 13600.0, 20.0, 6800.0]);
+
+        // 前复权各自以本代码最新的因子为基准
+        let qfq = adjust_prices(&rows, &factors, AdjustKind::Qfq);
+        let closes: Vec<f64> = qfq.iter().map(|r| r["close"].as_f64().unwrap()).collect();
+        assert_eq!(closes, vec![10.0, 1700.0, 10.0, 850.0]);
+    }
+
+    #[test]
+    fn test_without_factors() {
+        assert!(adjust_prices(&daily(), &[], AdjustKind::Qfq).is_empty());
+    }
+
+    #[test]
+    fn test_parse_options() {
+        assert_eq!(AdjustKind::from_str("QFQ"), Some(AdjustKind::Qfq));
+        assert_eq!(AdjustKind::from_str("none"), None);
+        assert_eq!(BarFreq::from_str("w").map(|f| f.api_name()), Some("weekly"));
+        assert_eq!(BarFreq::from_str("Y"), None);
+    }
+}
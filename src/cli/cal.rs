@@ -0,0 +1,150 @@
+//! 交易日历命令
+//!
+//! ```text
+//! tushare cal next [N] [--date 日期]     之后的 N 个交易日（默认从今天开始）
+//! tushare cal prev [N] [--date 日期]     之前的 N 个交易日
+//! tushare cal is-open [日期]             是否开市
+//! tushare cal range <开始日期> <结束日期> 区间内的交易日
+//! ```
+//!
+//! `--exchange` 指定交易所（默认 SSE，港股为 HKEX），`--refresh` 忽略本地缓存重新拉取。
+
+use crate::api::{find_api_by_name, load_api_definitions};
+use crate::calendar::cache::refresh_calendar;
+use crate::calendar::{format_date, load_calendar, parse_date, TradeCalendar, DEFAULT_EXCHANGE};
+use crate::cli::args::ParsedArgs;
+use crate::cli::commands::create_client;
+use crate::error::{TushareError, TResult};
+use crate::output::output_data;
+use chrono::{Duration, NaiveDate};
+use std::collections::HashMap;
+
+/// 处理 cal 命令
+pub async fn run_cal(args: &ParsedArgs) -> TResult<()> {
+    let usage = || {
+        TushareError::ParseError(
+            "用法: tushare cal next|prev [N] [--date 日期] | is-open [日期] | range <开始日期> <结束日期> [--exchange SSE]"
+                .to_string(),
+        )
+    };
+    let action = args.positional.first().ok_or_else(usage)?;
+    let exchange = args
+        .get_param("exchange")
+        .map(|v| v.to_string().to_uppercase())
+        .unwrap_or_else(|| DEFAULT_EXCHANGE.to_string());
+    let date = match args.get_param("date") {
+        Some(v) => parse_date(&v.to_string())?,
+        None => chrono::Local::now().date_naive(),
+    };
+    let count = |index: usize| -> TResult<usize> {
+        match args.positional.get(index) {
+            Some(n) => n
+                .parse()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or_else(|| TushareError::ParseError(format!("交易日数量需要正整数: {}", n))),
+            None => Ok(1),
+        }
+    };
+
+    let client = create_client(args)?;
+    let load = |start: NaiveDate, end: NaiveDate| {
+        let client = &client;
+        let exchange = &exchange;
+        async move {
+            if args.get_param("refresh").is_some() {
+                refresh_calendar(client, exchange, start, end).await
+            } else {
+                load_calendar(client, exchange, start, end).await
+            }
+        }
+    };
+
+    let rows = match action.as_str() {
+        "next" | "prev" => {
+            let n = count(1)?;
+            // 按最稀疏的情况（长假）预留足够的自然日
+            let span = Duration::days(n as i64 * 2 + 30);
+            let calendar = load(date - span, date + span).await?;
+            let days = if action == "next" {
+                (1..=n).map(|i| calendar.next(date, i)).collect::<Option<Vec<_>>>()
+            } else {
+                (1..=n).map(|i| calendar.prev(date, i)).collect::<Option<Vec<_>>>()
+            };
+            let days = days.ok_or_else(|| {
+                TushareError::ValidationError(format!(
+                    "{} 的交易日历中 {} {}不足 {} 个交易日 (日历范围 {} - {})",
+                    exchange,
+                    format_date(date),
+                    if action == "next" { "之后" } else { "之前" },
+                    n,
+                    format_date(calendar.start),
+                    format_date(calendar.end)
+                ))
+            })?;
+            days.into_iter().map(|d| day_row(&calendar, d, None)).collect()
+        }
+        "is-open" | "open" => {
+            let date = match args.positional.get(1) {
+                Some(d) => parse_date(d)?,
+                None => date,
+            };
+            let calendar = load(date - Duration::days(30), date).await?;
+            let open = calendar.is_open(date).ok_or_else(|| {
+                TushareError::ValidationError(format!("{} 不在 {} 的交易日历范围内", format_date(date), exchange))
+            })?;
+            vec![day_row(&calendar, date, Some(open))]
+        }
+        "range" => {
+            let (Some(start), Some(end)) = (args.positional.get(1), args.positional.get(2)) else {
+                return Err(usage());
+            };
+            let (start, end) = (parse_date(start)?, parse_date(end)?);
+            let calendar = load(start, end).await?;
+            calendar.range(start, end).into_iter().map(|d| day_row(&calendar, d, None)).collect()
+        }
+        _ => return Err(usage()),
+    };
+
+    let definitions = load_api_definitions();
+    let api = find_api_by_name(&definitions, "trade_cal");
+    output_data(&rows, &args.options, api, Some(&args.params_to_json()))
+}
+
+/// 生成输出行，`is_open` 仅在查询是否开市时输出
+fn day_row(calendar: &TradeCalendar, date: NaiveDate, is_open: Option<bool>) -> HashMap<String, serde_json::Value> {
+    let mut row = HashMap::new();
+    row.insert("exchange".to_string(), serde_json::json!(calendar.exchange));
+    row.insert("cal_date".to_string(), serde_json::json!(format_date(date)));
+    if let Some(open) = is_open {
+        row.insert("is_open".to_string(), serde_json::json!(open as i32));
+        row.insert(
+            "pretrade_date".to_string(),
+            calendar.prev(date, 1).map(|d| serde_json::json!(format_date(d))).unwrap_or_default(),
+        );
+    }
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_day_row() {
+        let calendar = TradeCalendar {
+            exchange: "SSE".to_string(),
+            start: parse_date("20231229").unwrap(),
+            end: parse_date("20240102").unwrap(),
+            open_days: vec![parse_date("20231229").unwrap(), parse_date("20240102").unwrap()],
+        };
+        let row = day_row(&calendar, parse_date("20240101").unwrap(), Some(false));
+        assert_eq!(row["cal_date"], json!("20240101"));
+        assert_eq!(row["is_open"], json!(0));
+        assert_eq!(row["pretrade_date"], json!("20231229"));
+
+        let row = day_row(&calendar, parse_date("20240102").unwrap(), None);
+        assert!(!row.contains_key("is_open"));
+    }
+}
@@ -1,13 +1,37 @@
 //! 命令处理器
 
 use crate::cli::args::ParsedArgs;
-use crate::client::TushareClient;
-use crate::error::TResult;
+use crate::cli::bar::run_bar;
+use crate::cli::cal::run_cal;
+use crate::cli::join::run_join;
+use crate::cli::sql::run_query;
+use crate::cli::statement::run_statement;
+use crate::cli::screen::run_screen;
+use crate::cli::index_diff::run_index_diff;
+use crate::cli::watchlist::{expand_ts_code, run_watchlist};
+use crate::cli::portfolio::run_portfolio;
+use crate::cli::diff::run_diff;
+use crate::cli::mirror::run_mirror;
+use crate::cli::sync::run_sync;
+use crate::client::{TushareClient, TushareResponse};
+use crate::error::{TushareError, TResult};
 use crate::output::output_data;
-use std::collections::HashMap;
+
+/// 内置命令（其余命令名按接口名调用）
+const BUILTIN_COMMANDS: [&str; 19] = [
+    "help", "version", "--version", "-v", "list", "ls", "sync", "mirror", "join", "query", "bar", "cal",
+    "statement", "screen", "index-diff", "watchlist", "portfolio", "diff", "search",
+];
+
+/// 逐个代码请求后合并结果的内置命令（其余内置命令只接受单个代码）
+const FAN_OUT_COMMANDS: [&str; 2] = ["bar", "statement"];
 
 /// 处理命令
-pub async fn handle_command(args: ParsedArgs) -> TResult<()> {
+pub async fn handle_command(mut args: ParsedArgs) -> TResult<()> {
+    // --ts-code @名称 展开为自选列表中的代码
+    let watchlist = expand_ts_code(&mut args)?;
+    check_builtin(&args, watchlist.as_deref())?;
+
     match args.command.as_str() {
         "help" => {
             show_help(&args);
@@ -22,6 +46,18 @@ pub async fn handle_command(args: ParsedArgs) -> TResult<()> {
             list_apis(category);
             Ok(())
         }
+        "sync" => run_sync(&args).await,
+        "mirror" => run_mirror(&args).await,
+        "join" => run_join(&args).await,
+        "query" => run_query(&args).await,
+        "bar" => run_bar(&args, watchlist).await,
+        "cal" => run_cal(&args).await,
+        "statement" => run_statement(&args, watchlist).await,
+        "screen" => run_screen(&args).await,
+        "index-diff" => run_index_diff(&args).await,
+        "watchlist" => run_watchlist(&args).await,
+        "portfolio" => run_portfolio(&args).await,
+        "diff" => run_diff(&args).await,
         "search" => {
             if let Some(keyword) = args.positional.first() {
                 search_apis(keyword);
@@ -32,11 +68,33 @@ pub async fn handle_command(args: ParsedArgs) -> TResult<()> {
         }
         _ => {
             // 调用 API
-            call_api(args).await
+            call_api(args, watchlist).await
         }
     }
 }
 
+/// 检查内置命令是否支持 `@名称` 展开的多个代码和 `--check`
+fn check_builtin(args: &ParsedArgs, codes: Option<&[String]>) -> TResult<()> {
+    let command = args.command.as_str();
+    if !BUILTIN_COMMANDS.contains(&command) {
+        return Ok(());
+    }
+    if let Some(codes) = codes.filter(|codes| codes.len() > 1 && !FAN_OUT_COMMANDS.contains(&command)) {
+        return Err(TushareError::ValidationError(format!(
+            "{} 命令不支持多个代码（--ts-code 展开为 {} 个代码），请直接调用接口逐个请求，如 tushare daily --ts-code @名称",
+            command,
+            codes.len()
+        )));
+    }
+    if args.options.check.is_some() {
+        return Err(TushareError::ValidationError(format!(
+            "{} 命令不支持 --check，数据检查只用于直接调用接口，如 tushare daily --ts-code 000001.SZ --check",
+            command
+        )));
+    }
+    Ok(())
+}
+
 /// 显示帮助信息
 fn show_help(args: &ParsedArgs) {
     if let Some(api_name) = args.positional.first() {
@@ -61,10 +119,42 @@ fn show_general_help() {
     println!("  version, --version, -v  显示版本信息");
     println!("  list, ls [类别]         列出所有 API 接口");
     println!("  search <关键词>         搜索 API 接口");
+    println!("  sync <接口名>           增量同步数据到本地存储 (需 sqlite 特性)");
+    println!("  mirror --category <类别> 按日期分区镜像整个类别的数据到本地目录");
+    println!("  join <接口名> <接口名>... 调用多个接口并按 --on 字段关联 (--how inner|left)");
+    println!("  query <SQL> --from <源>   将接口结果载入内存表后执行 SQL (需 sql 特性)");
+    println!("  bar --ts-code <代码>      复权行情 (--adj qfq|hfq, --freq D|W|M)");
+    println!("  cal next|prev [N]        交易日历: 之后/之前 N 个交易日，也支持 is-open [日期]、range <开始> <结束>");
+    println!("  statement --ts-code <代码> 财务报表按 科目 × 报告期 展示 (--type income|balance|cashflow|indicator, --periods 8)");
+    println!("  screen --where <表达式>  全市场选股 (daily_basic + 最新 fina_indicator + stock_basic，--date last-trade-day)");
+    println!("  watchlist ls|show|add|rm|import 自选列表，可在参数中以 --ts-code @名称 引用");
+    println!("  portfolio value <持仓.csv> 持仓估值: 市值、浮动盈亏、权重和当日盈亏 (--date, --base CNY)");
+    println!("  diff <旧快照> <新快照>    按 --key 比较两份数据的新增、删除和变更，有差异时退出码为 2 (也可 --api 与实时结果比较)");
+    println!("  index-diff --index <代码> 指数成分变动: 新增、剔除和权重变化 (--from 202312 --to 202406)");
     println!();
     println!("选项:");
-    println!("  -f, --format <格式>     输出格式 (json|table|csv|markdown)");
+    println!("  -f, --format <格式>     输出格式 (json|table|csv|markdown|record|yaml|toml|html)");
+    println!("  -o, --output <文件>     写入文件 (html)");
+    println!("  --chart <类型>          终端图表: line|candle|bar (替代表格输出)");
+    println!("  --x <字段> / --y <字段> 图表横轴/纵轴字段，默认 trade_date / close");
     println!("  -p, --pretty            美化 JSON 输出");
+    println!("  --max-width <宽度>      单元格最大显示宽度，默认 30，0 表示不截断 (table|markdown)");
+    println!("  --max-rows <行数>       终端最大显示行数，0 表示不限制 (table|markdown)");
+    println!("  --where <表达式>        过滤结果，如 \"pct_chg > 9.5 and industry == '银行'\"");
+    println!("  --group-by <字段>       分组字段，如 industry 或 ts_code,trade_date");
+    println!("  --agg <聚合>            聚合，如 \"sum(amount),avg(pct_chg),count()\" (sum|avg|min|max|count|first|last|median)");
+    println!("  --sort <字段>           排序，如 -amount,ts_code (- 表示降序)");
+    println!("  --select <字段>         只输出指定字段并按该顺序排列，如 ts_code,close");
+    println!("  --resample <周期>       K 线重采样: 30min|2h (按交易时段)|D|W|M|Q|Y");
+    println!("  --check [规则]          数据质量检查: gaps,dups,nulls,ohlc,limit (默认全部，发现问题时退出码为 3)");
+    println!("  --indicators <指标>     追加技术指标列，如 ma5,ma20,macd,rsi14 (ma|ema|macd|rsi|kdj|boll|atr)");
+    println!("  --head <行数>           只输出前 N 行");
+    println!("  --tail <行数>           只输出后 N 行");
+    println!("  --no-pager              超过一屏时不使用分页程序 ($PAGER，默认 less -RS)");
+    println!("  --number-format <风格>  数值显示: raw|cn (万/亿)|en (K/M/B) (table|markdown)");
+    println!("  --decimals <设置>       小数位数，如 2 或 close=2,vol=0 (table|markdown)");
+    println!("  --no-color              终端表格中不对涨跌着色");
+    println!("  --header <方式>         表头: name (字段名)|desc (中文说明)|both (table|markdown|csv)");
     println!("  -t, --token <token>     API Token");
     println!();
     println!("参数:");
@@ -87,9 +177,50 @@ fn show_general_help() {
     println!("  # 搜索接口");
     println!("  tushare search 龙虎榜");
     println!();
+    println!("  # 增量同步日线到本地存储");
+    println!("  tushare sync daily --store market.db --since 20240101 --dry-run");
+    println!();
+    println!("  # 关联日线、每日指标和股票名称");
+    println!("  tushare join daily daily_basic stock_basic --trade-date 20240102 --how left");
+    println!();
+    println!("  # 对接口结果执行 SQL");
+    println!("  tushare query \"SELECT ts_code, avg(close) FROM daily GROUP BY ts_code\" --from daily:trade_date=20240102");
+    println!();
+    println!("  # 前复权日线");
+    println!("  tushare bar --ts-code 600519.SH --adj qfq --start-date 20230101 --indicators ma5,macd");
+    println!();
+    println!("  # 最近 8 期利润表");
+    println!("  tushare statement --ts-code 600519.SH --type income --periods 8");
+    println!();
+    println!("  # 低估值高 ROE 选股");
+    println!("  tushare screen --where \"pe_ttm < 15 and roe > 15 and total_mv > 1e6\"");
+    println!();
+    println!("  # 自选列表");
+    println!("  tushare watchlist add banks 000001.SZ 600036.SH");
+    println!("  tushare daily --ts-code @banks --start-date 20240101");
+    println!();
+    println!("  # 持仓估值");
+    println!("  tushare portfolio value holdings.csv --date 20240630");
+    println!();
+    println!("  # 检查缺失交易日和异常价格");
+    println!("  tushare daily --ts-code 000001.SZ --start-date 20240101 --check");
+    println!();
+    println!("  # 检查数据是否被修订");
+    println!("  tushare diff daily_20240102.json --api daily --trade-date 20240102 --key ts_code");
+    println!();
+    println!("  # 沪深300 半年调样");
+    println!("  tushare index-diff --index 000300.SH --from 202312 --to 202406");
+    println!();
+    println!("  # 之后 5 个交易日");
+    println!("  tushare cal next 5 --exchange SSE");
+    println!();
     println!("  # 使用不同输出格式");
     println!("  tushare stock_basic --ts-code 000001.SZ --format json --pretty");
     println!("  tushare stock_basic --ts-code 000001.SZ --format csv");
+    println!("  tushare stock_company --ts-code 000001.SZ --format record");
+    println!("  tushare daily --trade-date 20240102 --format html --output report.html");
+    println!("  tushare daily --ts-code 000001.SZ --start-date 20240101 --chart candle");
+    println!("  tushare daily --trade-date 20240102 --where \"pct_chg > 9.5\" --sort -amount --select ts_code,pct_chg,amount");
     println!();
     println!("环境变量:");
     println!("  TUSHARE_TOKEN             API Token（推荐设置）");
@@ -237,7 +368,7 @@ fn list_apis(category: Option<&str>) {
 
         for api in definitions.values() {
             *category_counts.entry(&api.category).or_insert(0) += 1;
-            category_apis.entry(&api.category).or_insert_with(Vec::new).push(api);
+            category_apis.entry(&api.category).or_default().push(api);
         }
 
         println!("所有 API 接口 (共 {} 个)", definitions.len());
@@ -293,16 +424,49 @@ fn search_apis(keyword: &str) {
 }
 
 /// 调用 API
-async fn call_api(args: ParsedArgs) -> TResult<()> {
+async fn call_api(args: ParsedArgs, watchlist: Option<Vec<String>>) -> TResult<()> {
     use crate::api::{find_api_by_name, load_api_definitions};
 
-    // 检查 API 是否存在
+    // 查找 API 定义（未收录的接口也允许直接调用）
     let definitions = load_api_definitions();
-    if let Some(_api_def) = find_api_by_name(&definitions, &args.command) {
-        // API 存在，继续调用
-    }
+    let api_def = find_api_by_name(&definitions, &args.command);
 
     // 创建客户端
+    let client = create_client(&args)?;
+
+    // 调用 API，引用自选列表时逐个代码请求后合并
+    let objects = match watchlist {
+        Some(codes) if codes.len() > 1 => {
+            let mut objects = Vec::new();
+            for (i, code) in codes.iter().enumerate() {
+                let mut params = args.params_to_json();
+                params["ts_code"] = serde_json::json!(code);
+                let rows = request_api(&client, &args.command, params).await?.to_objects();
+                eprintln!("[{}/{}] {}: {} 行", i + 1, codes.len(), code, rows.len());
+                objects.extend(rows);
+            }
+            objects
+        }
+        _ => request_api(&client, &args.command, args.params_to_json()).await?.to_objects(),
+    };
+
+    // 输出结果
+    output_data(&objects, &args.options, api_def, Some(&args.params_to_json()))?;
+
+    // 数据质量检查，报告输出到标准错误
+    if let Some(rules) = &args.options.check {
+        let issues = crate::check::check_data(&client, &args.command, &objects, rules).await?;
+        crate::check::print_report(&issues, objects.len());
+        if !issues.is_empty() {
+            return Err(crate::error::TushareError::CheckFailed(issues.len()));
+        }
+    }
+
+    Ok(())
+}
+
+/// 创建客户端并设置 Token
+pub(crate) fn create_client(args: &ParsedArgs) -> TResult<TushareClient> {
     let mut client = TushareClient::new()?;
 
     // 设置 Token
@@ -324,12 +488,17 @@ async fn call_api(args: ParsedArgs) -> TResult<()> {
         }
     }
 
-    // 调用 API
-    let response = match client
-        .call(&args.command, Some(args.params_to_json()), None)
-        .await
-    {
-        Ok(r) => r,
+    Ok(client)
+}
+
+/// 调用 API，失败时输出可能的原因
+pub(crate) async fn request_api(
+    client: &TushareClient,
+    api_name: &str,
+    params: serde_json::Value,
+) -> TResult<TushareResponse> {
+    match client.call(api_name, Some(params), None).await {
+        Ok(r) => Ok(r),
         Err(crate::error::TushareError::ApiError { code, msg }) => {
             eprintln!("API 调用失败 (错误码: {}): {}", code, msg);
             if code == -10000 {
@@ -348,22 +517,15 @@ async fn call_api(args: ParsedArgs) -> TResult<()> {
                 eprintln!();
                 eprintln!("使用 'tushare list' 查看所有可用接口");
             }
-            return Err(crate::error::TushareError::ApiError { code, msg });
+            Err(crate::error::TushareError::ApiError { code, msg })
         }
-        Err(e) => return Err(e),
-    };
-
-    // 输出结果
-    let objects = response.to_objects();
-    output_data(&objects, args.options.format, args.options.pretty)?;
-
-    Ok(())
+        Err(e) => Err(e),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::cli::args::{OutputFormat, Options};
 
     #[test]
     fn test_show_general_help() {
@@ -389,4 +551,26 @@ mod tests {
         search_apis("龙虎榜");
         // 如果没有 panic，测试通过
     }
+
+    #[test]
+    fn test_builtin_rejects_multiple_codes() {
+        let codes = vec!["000001.SZ".to_string(), "600036.SH".to_string()];
+        for command in ["diff", "sync", "mirror", "join"] {
+            let err = check_builtin(&ParsedArgs::new(command.to_string()), Some(&codes)).unwrap_err();
+            assert!(err.to_string().contains("展开为 2 个代码"), "{}: {}", command, err);
+        }
+        // bar、statement 和直接调用接口时逐个代码请求
+        for command in ["bar", "statement", "daily"] {
+            assert!(check_builtin(&ParsedArgs::new(command.to_string()), Some(&codes)).is_ok());
+        }
+        assert!(check_builtin(&ParsedArgs::new("sync".to_string()), Some(&codes[..1])).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_builtin_rejects_check() {
+        let mut args = ParsedArgs::new("bar".to_string());
+        args.options.check = Some(crate::check::CheckRule::ALL.to_vec());
+        let err = handle_command(args).await.unwrap_err();
+        assert!(err.to_string().contains("不支持 --check"), "{}", err);
+    }
 }
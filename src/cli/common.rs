@@ -0,0 +1,23 @@
+//! 命令间共用的类型
+
+/// 比较结果中的变动类型（index-diff、diff）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeKind {
+    /// 新增
+    Added,
+    /// 删除
+    Removed,
+    /// 变更
+    Changed,
+}
+
+impl ChangeKind {
+    /// 输出中的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "added",
+            ChangeKind::Removed => "removed",
+            ChangeKind::Changed => "changed",
+        }
+    }
+}
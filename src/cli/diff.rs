@@ -0,0 +1,311 @@
+//! 数据快照比较命令
+//!
+//! ```text
+//! tushare diff before.json after.json --key ts_code,trade_date
+//! tushare diff snapshot.json --api daily --trade-date 20240102 --key ts_code
+//! ```
+//!
+//! 按 `--key` 匹配两份数据的行，输出新增、删除的行和变更字段的新旧值。快照可以是
+//! `--format json` 的输出、Tushare 原始响应或带表头的 CSV，`-` 表示标准输入；只给一份
+//! 快照时与 `--api` 的实时结果比较。有差异时退出码为 2，便于监控脚本判断。
+
+use crate::api::{ApiDefinition, ApiOutputField};
+use crate::cli::args::ParsedArgs;
+use crate::cli::commands::{create_client, request_api};
+use crate::cli::common::ChangeKind;
+use crate::cli::parser::kebab_to_snake;
+use crate::cli::watchlist::parse_csv;
+use crate::client::{ResponseData, Row, TushareResponse};
+use crate::error::{TushareError, TResult};
+use crate::output::{format_value, output_data};
+use crate::query::value_as_f64;
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+
+/// diff 自身的选项，不传给接口
+const DIFF_OPTIONS: [&str; 4] = ["key", "api", "ignore", "tolerance"];
+
+/// 处理 diff 命令
+pub async fn run_diff(args: &ParsedArgs) -> TResult<()> {
+    let list = |name: &str| args.get_param(name).map(|v| field_list(&v.to_string())).unwrap_or_default();
+    let keys = list("key");
+    let ignore = list("ignore");
+    let tolerance = match args.get_param("tolerance") {
+        Some(v) => v
+            .to_string()
+            .parse()
+            .ok()
+            .filter(|t: &f64| *t >= 0.0)
+            .ok_or_else(|| TushareError::ParseError(format!("--tolerance 需要非负数: {}", v)))?,
+        None => 0.0,
+    };
+    if keys.is_empty() {
+        return Err(TushareError::ParseError(
+            "用法: tushare diff <旧快照> <新快照>|--api <接口> --key ts_code,trade_date [--ignore 字段] [--tolerance 0.01]"
+                .to_string(),
+        ));
+    }
+
+    let (before, after) = match (args.positional.first(), args.positional.get(1), args.get_param("api")) {
+        (Some(before), Some(after), None) => (load_snapshot(before)?, load_snapshot(after)?),
+        (Some(before), None, Some(api)) => {
+            let mut params = args.params_to_json();
+            if let Some(map) = params.as_object_mut() {
+                map.retain(|key, _| !DIFF_OPTIONS.contains(&key.as_str()));
+            }
+            let client = create_client(args)?;
+            let after = request_api(&client, &api.to_string(), params).await?.to_objects();
+            (load_snapshot(before)?, after)
+        }
+        _ => {
+            return Err(TushareError::ParseError(
+                "请指定两份快照，或一份快照和 --api <接口名>".to_string(),
+            ))
+        }
+    };
+
+    let rows = diff_rows(&before, &after, &keys, &ignore, tolerance)?;
+    let count = |kind: ChangeKind| {
+        let mut seen = HashSet::new();
+        rows.iter()
+            .filter(|r| r["change"] == kind.as_str())
+            .filter(|r| seen.insert(keys.iter().map(|k| format_value(&r[k])).collect::<Vec<_>>()))
+            .count()
+    };
+    let (added, removed, changed) = (count(ChangeKind::Added), count(ChangeKind::Removed), count(ChangeKind::Changed));
+    eprintln!(
+        "{} → {} 行：新增 {}，删除 {}，变更 {}",
+        before.len(),
+        after.len(),
+        added,
+        removed,
+        changed
+    );
+
+    if rows.is_empty() {
+        return Ok(());
+    }
+    output_data(&rows, &args.options, Some(&diff_definition(&keys)), None)?;
+    Err(TushareError::DiffFound(added + removed + changed))
+}
+
+/// 解析逗号分隔的字段名，与接口参数一样接受 kebab-case（`trade-date`）
+pub fn field_list(value: &str) -> Vec<String> {
+    value.split(',').map(str::trim).filter(|s| !s.is_empty()).map(kebab_to_snake).collect()
+}
+
+/// 读取快照文件，`-` 为标准输入
+pub fn load_snapshot(path: &str) -> TResult<Vec<Row>> {
+    let content = if path == "-" {
+        let mut content = String::new();
+        std::io::stdin().read_to_string(&mut content)?;
+        content
+    } else {
+        std::fs::read_to_string(path)?
+    };
+    if path.to_lowercase().ends_with(".csv") {
+        return Ok(parse_csv(&content));
+    }
+    parse_snapshot(&content)
+}
+
+/// 解析 JSON 快照：对象数组、Tushare 原始响应或 `{fields, items}`
+pub fn parse_snapshot(content: &str) -> TResult<Vec<Row>> {
+    let value: serde_json::Value = serde_json::from_str(content)?;
+    let data = match value {
+        serde_json::Value::Array(_) => return Ok(serde_json::from_value(value)?),
+        serde_json::Value::Object(mut map) if map.contains_key("data") => map.remove("data").unwrap_or_default(),
+        serde_json::Value::Object(map) if map.contains_key("items") => serde_json::Value::Object(map),
+        _ => {
+            return Err(TushareError::ValidationError(
+                "无法识别的快照格式 (需要对象数组或 Tushare 响应)".to_string(),
+            ))
+        }
+    };
+    let response = TushareResponse {
+        request_id: String::new(),
+        code: 0,
+        msg: String::new(),
+        data: Some(serde_json::from_value::<ResponseData>(data)?),
+    };
+    Ok(response.to_objects())
+}
+
+/// 比较两份数据
+///
+/// 每个新增或删除的行输出一行，变更的行每个字段输出一行（`field`、`old`、`new`）。
+/// 删除和变更按旧数据的顺序，新增按新数据的顺序排在最后。
+pub fn diff_rows(before: &[Row], after: &[Row], keys: &[String], ignore: &[String], tolerance: f64) -> TResult<Vec<Row>> {
+    let old = index_rows(before, keys)?;
+    let new = index_rows(after, keys)?;
+
+    let mut rows = Vec::new();
+    let output = |kind: ChangeKind, key_row: &Row, field: Option<&str>, old: Option<&serde_json::Value>, new: Option<&serde_json::Value>| {
+        let mut row: Row = keys.iter().map(|k| (k.clone(), key_row.get(k).cloned().unwrap_or_default())).collect();
+        row.insert("change".to_string(), serde_json::json!(kind.as_str()));
+        row.insert("field".to_string(), serde_json::json!(field));
+        row.insert("old".to_string(), old.cloned().unwrap_or_default());
+        row.insert("new".to_string(), new.cloned().unwrap_or_default());
+        row
+    };
+
+    for row in before {
+        let Some(other) = new.get(&key_of(row, keys)) else {
+            rows.push(output(ChangeKind::Removed, row, None, None, None));
+            continue;
+        };
+        let mut fields: Vec<&String> = row.keys().chain(other.keys()).collect();
+        fields.sort();
+        fields.dedup();
+        for field in fields.into_iter().filter(|f| !keys.contains(f) && !ignore.contains(f)) {
+            let (a, b) = (row.get(field), other.get(field));
+            if !values_equal(a, b, tolerance) {
+                rows.push(output(ChangeKind::Changed, row, Some(field), a, b));
+            }
+        }
+    }
+    for row in after {
+        if !old.contains_key(&key_of(row, keys)) {
+            rows.push(output(ChangeKind::Added, row, None, None, None));
+        }
+    }
+    Ok(rows)
+}
+
+/// 按主键建立索引，缺少主键字段或主键重复时报错
+fn index_rows<'a>(rows: &'a [Row], keys: &[String]) -> TResult<HashMap<Vec<String>, &'a Row>> {
+    let mut index = HashMap::new();
+    for row in rows {
+        if let Some(missing) = keys.iter().find(|k| !row.contains_key(*k)) {
+            return Err(TushareError::ValidationError(format!("数据中没有主键字段 {}", missing)));
+        }
+        let key = key_of(row, keys);
+        if index.insert(key.clone(), row).is_some() {
+            return Err(TushareError::ValidationError(format!(
+                "主键 ({}) 重复: {}",
+                keys.join(", "),
+                key.join(", ")
+            )));
+        }
+    }
+    Ok(index)
+}
+
+fn key_of(row: &Row, keys: &[String]) -> Vec<String> {
+    keys.iter().map(|k| row.get(k).map(format_value).unwrap_or_default()).collect()
+}
+
+/// 数值按容差比较（CSV 中的数字字符串也按数值比较），其它按文本比较，缺失视为 null
+fn values_equal(a: Option<&serde_json::Value>, b: Option<&serde_json::Value>, tolerance: f64) -> bool {
+    let null = serde_json::Value::Null;
+    let (a, b) = (a.unwrap_or(&null), b.unwrap_or(&null));
+    if a.is_null() || b.is_null() {
+        return a.is_null() && b.is_null();
+    }
+    match (value_as_f64(a), value_as_f64(b)) {
+        (Some(x), Some(y)) => (x - y).abs() <= tolerance,
+        _ => format_value(a) == format_value(b),
+    }
+}
+
+/// 输出字段定义
+fn diff_definition(keys: &[String]) -> ApiDefinition {
+    let mut definition = ApiDefinition::synthetic(
+        "diff",
+        "数据差异",
+        &[("change", "str", "变动"), ("field", "str", "字段"), ("old", "str", "旧值"), ("new", "str", "新值")],
+    );
+    let key_fields = keys.iter().map(|k| ApiOutputField::new(k, "str", k));
+    definition.output_fields.splice(0..0, key_fields);
+    definition
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::rows_from_json;
+    use serde_json::json;
+
+    fn keys() -> Vec<String> {
+        vec!["ts_code".to_string(), "trade_date".to_string()]
+    }
+
+    #[test]
+    fn test_parse_snapshot() {
+        let expected = rows_from_json(json!([{"ts_code": "000001.SZ", "close": 9.21}]));
+        assert_eq!(parse_snapshot(r#"[{"ts_code": "000001.SZ", "close": 9.21}]"#).unwrap(), expected);
+        assert_eq!(
+            parse_snapshot(r#"{"code": 0, "msg": "", "data": {"fields": ["ts_code", "close"], "items": [["000001.SZ", 9.21]]}}"#)
+                .unwrap(),
+            expected
+        );
+        assert_eq!(parse_snapshot(r#"{"fields": "ts_code,close", "items": [["000001.SZ", 9.21]]}"#).unwrap(), expected);
+        assert!(parse_snapshot(r#"{"rows": []}"#).is_err());
+    }
+
+    #[test]
+    fn test_diff_rows() {
+        let before = rows_from_json(json!([
+            {"ts_code": "000001.SZ", "trade_date": "20240102", "close": 9.21, "vol": 100.0},
+            {"ts_code": "000002.SZ", "trade_date": "20240102", "close": 10.5, "vol": 200.0},
+            {"ts_code": "000004.SZ", "trade_date": "20240102", "close": 12.0, "vol": 300.0},
+        ]));
+        let after = rows_from_json(json!([
+            {"ts_code": "000004.SZ", "trade_date": "20240102", "close": 12.0, "vol": 300.0},
+            {"ts_code": "000003.SZ", "trade_date": "20240102", "close": 8.0, "vol": 50.0},
+            {"ts_code": "000001.SZ", "trade_date": "20240102", "close": 9.25, "vol": 120.0},
+        ]));
+
+        let diff = diff_rows(&before, &after, &keys(), &[], 0.0).unwrap();
+        let summary: Vec<(String, String, String)> = diff
+            .iter()
+            .map(|r| (format_value(&r["ts_code"]), format_value(&r["change"]), format_value(&r["field"])))
+            .collect();
+        let expected = [
+            ("000001.SZ", "changed", "close"),
+            ("000001.SZ", "changed", "vol"),
+            ("000002.SZ", "removed", ""),
+            ("000003.SZ", "added", ""),
+        ];
+        assert_eq!(summary.len(), expected.len());
+        for (actual, expected) in summary.iter().zip(expected) {
+            assert_eq!((actual.0.as_str(), actual.1.as_str(), actual.2.as_str()), expected);
+        }
+        assert_eq!(diff[0]["old"], json!(9.21));
+        assert_eq!(diff[0]["new"], json!(9.25));
+
+        // 忽略字段和容差
+        let diff = diff_rows(&before[..1], &after[2..], &keys(), &["vol".to_string()], 0.05).unwrap();
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn test_values_equal() {
+        assert!(values_equal(Some(&json!(1)), Some(&json!(1.0)), 0.0));
+        assert!(values_equal(Some(&json!("9.21")), Some(&json!(9.21)), 0.0));
+        assert!(values_equal(None, Some(&json!(null)), 0.0));
+        assert!(!values_equal(Some(&json!(0)), None, 0.0));
+        assert!(!values_equal(Some(&json!("a")), Some(&json!("b")), 0.0));
+    }
+
+    #[test]
+    fn test_duplicate_keys() {
+        let data = rows_from_json(json!([
+            {"ts_code": "000001.SZ", "trade_date": "20240102"},
+            {"ts_code": "000001.SZ", "trade_date": "20240102"},
+        ]));
+        assert!(diff_rows(&data, &[], &keys(), &[], 0.0).is_err());
+    }
+
+    #[test]
+    fn test_field_list() {
+        assert_eq!(field_list("ts-code, trade_date,"), keys());
+    }
+
+    #[test]
+    fn test_missing_key_field() {
+        let data = rows_from_json(json!([{"ts_code": "000001.SZ", "trade_date": "20240102"}]));
+        let err = diff_rows(&data, &data, &["trade-date".to_string()], &[], 0.0).unwrap_err();
+        assert!(err.to_string().contains("trade-date"));
+    }
+}
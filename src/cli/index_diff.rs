@@ -0,0 +1,228 @@
+//! 指数成分变动命令
+//!
+//! `tushare index-diff --index 000300.SH --from 202312 --to 202406` 获取两个时点的
+//! `index_weight` 快照（月度数据，取当月最后一次公布的权重），列出新增、剔除的成分
+//! 以及权重的变化。
+
+use crate::api::ApiDefinition;
+use crate::calendar::{format_date, parse_date};
+use crate::cli::args::ParsedArgs;
+use crate::cli::commands::{create_client, request_api};
+use crate::cli::common::ChangeKind;
+use crate::client::{Row, TushareClient};
+use crate::error::{TushareError, TResult};
+use crate::output::output_data;
+use crate::query::{field_text, round_to, value_as_f64};
+use chrono::{Datelike, Months, NaiveDate};
+use std::collections::HashMap;
+
+/// 权重变化保留的小数位数
+const WEIGHT_DECIMALS: i32 = 4;
+
+/// 某一时点的成分权重
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    /// 权重公布日期
+    pub trade_date: String,
+    /// 成分代码 → 权重
+    pub weights: HashMap<String, f64>,
+}
+
+/// 处理 index-diff 命令
+pub async fn run_index_diff(args: &ParsedArgs) -> TResult<()> {
+    let param = |name: &str| args.get_param(name).map(|v| v.to_string());
+    let (Some(index), Some(from), Some(to)) = (
+        param("index").or_else(|| param("index_code")),
+        param("from"),
+        param("to"),
+    ) else {
+        return Err(TushareError::ParseError(
+            "用法: tushare index-diff --index 000300.SH --from 202312 --to 202406".to_string(),
+        ));
+    };
+
+    let client = create_client(args)?;
+    let before = fetch_snapshot(&client, &index, &from).await?;
+    let after = fetch_snapshot(&client, &index, &to).await?;
+
+    let rows = diff_snapshots(&before, &after);
+    let count = |kind: ChangeKind| rows.iter().filter(|r| r["change"] == kind.as_str()).count();
+    eprintln!(
+        "{}: {} → {}，新增 {}，剔除 {}，成分 {} → {}",
+        index,
+        before.trade_date,
+        after.trade_date,
+        count(ChangeKind::Added),
+        count(ChangeKind::Removed),
+        before.weights.len(),
+        after.weights.len()
+    );
+
+    output_data(&rows, &args.options, Some(&diff_definition()), Some(&args.params_to_json()))
+}
+
+/// 获取区间内最后一次公布的成分权重
+async fn fetch_snapshot(client: &TushareClient, index: &str, period: &str) -> TResult<Snapshot> {
+    let (start, end) = period_range(period)?;
+    let response = request_api(
+        client,
+        "index_weight",
+        serde_json::json!({
+            "index_code": index,
+            "start_date": format_date(start),
+            "end_date": format_date(end),
+        }),
+    )
+    .await?;
+    latest_snapshot(&response.to_objects()).ok_or_else(|| {
+        TushareError::ValidationError(format!(
+            "{} 在 {} - {} 没有成分权重数据",
+            index,
+            format_date(start),
+            format_date(end)
+        ))
+    })
+}
+
+/// 时点对应的查询区间：YYYYMM 为整月，YYYYMMDD 为当月 1 日至该日
+pub fn period_range(period: &str) -> TResult<(NaiveDate, NaiveDate)> {
+    let period = period.trim();
+    match period.len() {
+        6 => {
+            let start = parse_date(&format!("{}01", period))?;
+            let end = start
+                .checked_add_months(Months::new(1))
+                .and_then(|d| d.pred_opt())
+                .unwrap_or(start);
+            Ok((start, end))
+        }
+        8 => {
+            let end = parse_date(period)?;
+            Ok((end.with_day(1).unwrap_or(end), end))
+        }
+        _ => Err(TushareError::ValidationError(format!(
+            "无效的时点: {} (格式应为 YYYYMM 或 YYYYMMDD)",
+            period
+        ))),
+    }
+}
+
+/// 取最新公布日期的权重，没有数据时为 None
+pub fn latest_snapshot(rows: &[Row]) -> Option<Snapshot> {
+    let trade_date = rows.iter().map(|r| field_text(r, "trade_date")).max()?;
+    let weights = rows
+        .iter()
+        .filter(|r| field_text(r, "trade_date") == trade_date)
+        .map(|r| (field_text(r, "con_code"), r.get("weight").and_then(value_as_f64).unwrap_or(0.0)))
+        .collect();
+    Some(Snapshot { trade_date, weights })
+}
+
+/// 比较两个快照
+///
+/// 依次输出新增（按期末权重降序）、剔除（按期初权重降序）和权重有变化的成分
+/// （按变化幅度降序）。
+pub fn diff_snapshots(before: &Snapshot, after: &Snapshot) -> Vec<Row> {
+    let mut changes: Vec<(ChangeKind, &str, Option<f64>, Option<f64>)> = Vec::new();
+    for (code, weight) in &after.weights {
+        match before.weights.get(code) {
+            None => changes.push((ChangeKind::Added, code, None, Some(*weight))),
+            Some(old) if round_to(weight - old, WEIGHT_DECIMALS) != 0.0 => {
+                changes.push((ChangeKind::Changed, code, Some(*old), Some(*weight)))
+            }
+            Some(_) => {}
+        }
+    }
+    for (code, weight) in &before.weights {
+        if !after.weights.contains_key(code) {
+            changes.push((ChangeKind::Removed, code, Some(*weight), None));
+        }
+    }
+
+    let delta = |from: Option<f64>, to: Option<f64>| to.unwrap_or(0.0) - from.unwrap_or(0.0);
+    changes.sort_by(|a, b| {
+        a.0.cmp(&b.0)
+            .then_with(|| delta(b.2, b.3).abs().total_cmp(&delta(a.2, a.3).abs()))
+            .then_with(|| a.1.cmp(b.1))
+    });
+
+    changes
+        .into_iter()
+        .map(|(kind, code, from, to)| {
+            let mut row = HashMap::new();
+            row.insert("con_code".to_string(), serde_json::json!(code));
+            row.insert("change".to_string(), serde_json::json!(kind.as_str()));
+            row.insert("from_weight".to_string(), serde_json::json!(from));
+            row.insert("to_weight".to_string(), serde_json::json!(to));
+            row.insert("weight_chg".to_string(), serde_json::json!(round_to(delta(from, to), WEIGHT_DECIMALS)));
+            row
+        })
+        .collect()
+}
+
+/// 输出字段定义
+fn diff_definition() -> ApiDefinition {
+    ApiDefinition::synthetic(
+        "index_diff",
+        "指数成分变动",
+        &[
+            ("con_code", "str", "成分代码"),
+            ("change", "str", "变动"),
+            ("from_weight", "float", "期初权重"),
+            ("to_weight", "float", "期末权重"),
+            ("weight_chg", "float", "权重变化"),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::rows_from_json;
+    use serde_json::json;
+
+    fn snapshot(trade_date: &str, weights: &[(&str, f64)]) -> Snapshot {
+        Snapshot {
+            trade_date: trade_date.to_string(),
+            weights: weights.iter().map(|(c, w)| (c.to_string(), *w)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_period_range() {
+        let range = period_range("202402").unwrap();
+        assert_eq!((format_date(range.0), format_date(range.1)), ("20240201".to_string(), "20240229".to_string()));
+        let range = period_range("20231215").unwrap();
+        assert_eq!(format_date(range.0), "20231201");
+        assert!(period_range("2024").is_err());
+    }
+
+    #[test]
+    fn test_latest_snapshot() {
+        let rows = rows_from_json(json!([
+            {"con_code": "600519.SH", "trade_date": "20231201", "weight": 5.1},
+            {"con_code": "600519.SH", "trade_date": "20231229", "weight": 5.3},
+            {"con_code": "601318.SH", "trade_date": "20231229", "weight": 2.7},
+        ]));
+        let snapshot = latest_snapshot(&rows).unwrap();
+        assert_eq!(snapshot.trade_date, "20231229");
+        assert_eq!(snapshot.weights.len(), 2);
+        assert_eq!(snapshot.weights["600519.SH"], 5.3);
+        assert_eq!(latest_snapshot(&[]), None);
+    }
+
+    #[test]
+    fn test_diff_snapshots() {
+        let before = snapshot("20231229", &[("A", 5.0), ("B", 2.0), ("C", 1.0), ("D", 0.5)]);
+        let after = snapshot("20240628", &[("A", 4.5), ("B", 2.0), ("C", 1.2), ("E", 0.8), ("F", 0.3)]);
+        let rows = diff_snapshots(&before, &after);
+
+        let codes: Vec<String> = rows.iter().map(|r| field_text(r, "con_code")).collect();
+        assert_eq!(codes, ["E", "F", "D", "A", "C"]);
+        assert_eq!(rows[0]["change"], json!("added"));
+        assert_eq!(rows[0]["from_weight"], json!(null));
+        assert_eq!(rows[2]["change"], json!("removed"));
+        assert_eq!(rows[2]["weight_chg"], json!(-0.5));
+        assert_eq!(rows[4]["weight_chg"], json!(0.2));
+    }
+}
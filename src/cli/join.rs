@@ -0,0 +1,303 @@
+//! 多接口关联命令
+//!
+//! `tushare join daily daily_basic stock_basic --trade-date 20240102` 依次调用多个接口，
+//! 按共有的关联字段（默认 `ts_code,trade_date`）将结果合并为一张表后正常输出。
+//! 公共参数只传给定义中接受该参数的接口；非关联字段重名时，后面接口的字段加上
+//! `<接口名>_` 前缀。
+
+use crate::api::{find_api_by_name, load_api_definitions, ApiDefinition, ApiOutputField};
+use crate::cli::args::ParsedArgs;
+use crate::cli::commands::{create_client, request_api};
+use crate::error::{TushareError, TResult};
+use crate::output::{format_value, get_fields, output_data};
+use std::collections::{HashMap, HashSet};
+
+/// 默认关联字段
+const DEFAULT_JOIN_KEYS: &str = "ts_code,trade_date";
+
+/// join 命令自身的选项，不传给接口
+const JOIN_OPTIONS: [&str; 2] = ["on", "how"];
+
+/// 关联方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JoinKind {
+    /// 只保留两边都有的行
+    #[default]
+    Inner,
+    /// 保留左表所有行，右表缺失的字段为空
+    Left,
+}
+
+impl JoinKind {
+    /// 从字符串解析关联方式
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "inner" => Some(JoinKind::Inner),
+            "left" => Some(JoinKind::Left),
+            _ => None,
+        }
+    }
+}
+
+/// 重命名的字段：（表序号, 原字段名）→ 新字段名
+pub type Renames = HashMap<(usize, String), String>;
+
+/// 参与关联的一张表
+#[derive(Debug, Clone)]
+pub struct JoinTable {
+    /// 接口名（用作重名字段的前缀）
+    pub name: String,
+    /// 数据
+    pub rows: Vec<HashMap<String, serde_json::Value>>,
+    /// 字段（有数据时取数据中的字段，否则取定义中的字段）
+    pub fields: Vec<String>,
+}
+
+/// 处理 join 命令
+pub async fn run_join(args: &ParsedArgs) -> TResult<()> {
+    if args.positional.len() < 2 {
+        return Err(TushareError::ParseError(
+            "请至少指定两个接口，例如: tushare join daily daily_basic --trade-date 20240102".to_string(),
+        ));
+    }
+
+    let keys: Vec<String> = args
+        .get_param("on")
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| DEFAULT_JOIN_KEYS.to_string())
+        .split(',')
+        .map(|k| k.trim().replace('-', "_"))
+        .filter(|k| !k.is_empty())
+        .collect();
+    let how = match args.get_param("how") {
+        Some(v) => JoinKind::from_str(&v.to_string()).ok_or_else(|| {
+            TushareError::ParseError(format!("无效的关联方式: {} (可选 inner|left)", v))
+        })?,
+        None => JoinKind::default(),
+    };
+
+    let definitions = load_api_definitions();
+    let client = create_client(args)?;
+    let mut tables = Vec::new();
+    let mut apis: Vec<Option<&ApiDefinition>> = Vec::new();
+
+    for name in &args.positional {
+        let api = find_api_by_name(&definitions, name);
+        let params = api_params(args, api);
+        let response = request_api(&client, name, params).await?;
+        let rows = response.to_objects();
+        eprintln!("{}: {} 行", name, rows.len());
+
+        let mut fields = get_fields(&rows);
+        if fields.is_empty() {
+            fields = api.map(|a| a.output_fields.iter().map(|f| f.name.clone()).collect()).unwrap_or_default();
+        }
+        tables.push(JoinTable { name: name.clone(), rows, fields });
+        apis.push(api);
+    }
+
+    let (rows, renames) = join_tables(&tables, &keys, how)?;
+    let definition = joined_definition(&tables, &apis, &renames);
+    output_data(&rows, &args.options, Some(&definition), Some(&args.params_to_json()))
+}
+
+/// 生成单个接口的请求参数：只保留定义中接受的参数（未收录的接口保留全部参数）
+fn api_params(args: &ParsedArgs, api: Option<&ApiDefinition>) -> serde_json::Value {
+    let mut params = args.params_to_json();
+    if let Some(map) = params.as_object_mut() {
+        map.retain(|key, _| {
+            !JOIN_OPTIONS.contains(&key.as_str())
+                && api.map(|a| a.parameters.iter().any(|p| &p.name == key)).unwrap_or(true)
+        });
+    }
+    params
+}
+
+/// 依次关联所有表，返回合并后的数据和重命名的字段
+pub fn join_tables(
+    tables: &[JoinTable],
+    keys: &[String],
+    how: JoinKind,
+) -> TResult<(Vec<HashMap<String, serde_json::Value>>, Renames)> {
+    let mut renames = HashMap::new();
+    let Some(first) = tables.first() else {
+        return Ok((Vec::new(), renames));
+    };
+
+    let mut rows = first.rows.clone();
+    let mut columns: Vec<String> = first.fields.clone();
+
+    for (index, table) in tables.iter().enumerate().skip(1) {
+        // 只使用两边都有的关联字段
+        let on: Vec<String> = keys
+            .iter()
+            .filter(|k| columns.contains(k) && table.fields.contains(k))
+            .cloned()
+            .collect();
+        if on.is_empty() {
+            return Err(TushareError::ValidationError(format!(
+                "{} 与之前的结果没有共同的关联字段 (关联字段: {})",
+                table.name,
+                keys.join(", ")
+            )));
+        }
+
+        // 非关联字段重名时加前缀
+        let mut mapping: Vec<(String, String)> = Vec::new();
+        for field in table.fields.iter().filter(|f| !on.contains(f)) {
+            let target = if columns.contains(field) {
+                let renamed = format!("{}_{}", table.name, field);
+                renames.insert((index, field.clone()), renamed.clone());
+                renamed
+            } else {
+                field.clone()
+            };
+            columns.push(target.clone());
+            mapping.push((field.clone(), target));
+        }
+
+        rows = join_rows(&rows, &table.rows, &on, &mapping, how);
+    }
+
+    Ok((rows, renames))
+}
+
+/// 关联两张表，`mapping` 为右表字段（原名, 合并后名称）
+fn join_rows(
+    left: &[HashMap<String, serde_json::Value>],
+    right: &[HashMap<String, serde_json::Value>],
+    on: &[String],
+    mapping: &[(String, String)],
+    how: JoinKind,
+) -> Vec<HashMap<String, serde_json::Value>> {
+    let key_of = |row: &HashMap<String, serde_json::Value>| -> Vec<String> {
+        on.iter().map(|k| row.get(k).map(format_value).unwrap_or_default()).collect()
+    };
+
+    let mut index: HashMap<Vec<String>, Vec<&HashMap<String, serde_json::Value>>> = HashMap::new();
+    for row in right {
+        index.entry(key_of(row)).or_default().push(row);
+    }
+
+    let mut joined = Vec::new();
+    for row in left {
+        match index.get(&key_of(row)) {
+            Some(matches) => {
+                for other in matches {
+                    let mut merged = row.clone();
+                    for (source, target) in mapping {
+                        merged.insert(target.clone(), other.get(source).cloned().unwrap_or_default());
+                    }
+                    joined.push(merged);
+                }
+            }
+            None if how == JoinKind::Left => {
+                let mut merged = row.clone();
+                for (_, target) in mapping {
+                    merged.insert(target.clone(), serde_json::Value::Null);
+                }
+                joined.push(merged);
+            }
+            None => {}
+        }
+    }
+    joined
+}
+
+/// 合并各接口的字段定义，用于表头说明和数值显示
+pub(crate) fn joined_definition(
+    tables: &[JoinTable],
+    apis: &[Option<&ApiDefinition>],
+    renames: &Renames,
+) -> ApiDefinition {
+    let mut seen = HashSet::new();
+    let mut output_fields = Vec::new();
+
+    for (index, api) in apis.iter().enumerate() {
+        let Some(api) = api else { continue };
+        for field in &api.output_fields {
+            let name = renames.get(&(index, field.name.clone())).cloned().unwrap_or_else(|| field.name.clone());
+            if seen.insert(name.clone()) {
+                output_fields.push(ApiOutputField { name, ..field.clone() });
+            }
+        }
+    }
+
+    let names: Vec<&str> = tables.iter().map(|t| t.name.as_str()).collect();
+    ApiDefinition {
+        name: names.join("+"),
+        description: format!("关联 {}", names.join("、")),
+        category: String::new(),
+        doc_id: 0,
+        parameters: Vec::new(),
+        output_fields,
+        requires_points: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn table(name: &str, rows: Vec<serde_json::Value>) -> JoinTable {
+        let rows = crate::client::rows_from_json(serde_json::Value::Array(rows));
+        JoinTable { name: name.to_string(), fields: get_fields(&rows), rows }
+    }
+
+    fn tables() -> Vec<JoinTable> {
+        vec![
+            table("daily", vec![
+                json!({"ts_code": "000001.SZ", "trade_date": "20240102", "close": 9.21}),
+                json!({"ts_code": "000002.SZ", "trade_date": "20240102", "close": 10.5}),
+            ]),
+            table("daily_basic", vec![
+                json!({"ts_code": "000001.SZ", "trade_date": "20240102", "close": 9.21, "pe": 4.5}),
+            ]),
+            table("stock_basic", vec![
+                json!({"ts_code": "000001.SZ", "name": "平安银行"}),
+                json!({"ts_code": "000002.SZ", "name": "万科A"}),
+            ]),
+        ]
+    }
+
+    fn keys() -> Vec<String> {
+        vec!["ts_code".to_string(), "trade_date".to_string()]
+    }
+
+    #[test]
+    fn test_inner_join() {
+        let (rows, renames) = join_tables(&tables(), &keys(), JoinKind::Inner).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["pe"], json!(4.5));
+        assert_eq!(rows[0]["daily_basic_close"], json!(9.21));
+        assert_eq!(rows[0]["name"], json!("平安银行"));
+        assert_eq!(renames[&(1, "close".to_string())], "daily_basic_close");
+    }
+
+    #[test]
+    fn test_left_join() {
+        let (rows, _) = join_tables(&tables(), &keys(), JoinKind::Left).unwrap();
+        assert_eq!(rows.len(), 2);
+        let vanke = rows.iter().find(|r| r["ts_code"] == json!("000002.SZ")).unwrap();
+        assert_eq!(vanke["pe"], json!(null));
+        // stock_basic 只按 ts_code 关联
+        assert_eq!(vanke["name"], json!("万科A"));
+    }
+
+    #[test]
+    fn test_join_without_common_keys() {
+        let tables = vec![
+            table("a", vec![json!({"ts_code": "000001.SZ"})]),
+            table("b", vec![json!({"cal_date": "20240102"})]),
+        ];
+        assert!(join_tables(&tables, &keys(), JoinKind::Inner).is_err());
+    }
+
+    #[test]
+    fn test_join_kind() {
+        assert_eq!(JoinKind::from_str("LEFT"), Some(JoinKind::Left));
+        assert_eq!(JoinKind::from_str("outer"), None);
+    }
+}
@@ -0,0 +1,587 @@
+//! 数据镜像命令
+//!
+//! `tushare mirror --category <类别> --since <日期> --dir <目录>` 遍历类别下的
+//! 所有接口，按日期参数将数据分区下载到本地目录：
+//!
+//! ```text
+//! <dir>/<api>/date=YYYYMM/part.csv   按日期分区的接口
+//! <dir>/<api>/part.csv               无日期参数的接口（整表快照）
+//! <dir>/manifest.json                本次镜像的结果清单
+//! ```
+
+use crate::api::{get_apis_by_category, load_api_definitions, get_categories, ApiDefinition};
+use crate::calendar::{format_date, load_calendar, parse_date, DEFAULT_EXCHANGE};
+use crate::cli::args::ParsedArgs;
+use crate::cli::commands::create_client;
+use crate::client::TushareClient;
+use crate::error::{TushareError, TResult};
+use crate::output::{escape_csv_value, format_value};
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// 日期相关的参数名
+const DATE_PARAMS: [&str; 4] = ["trade_date", "start_date", "end_date", "period"];
+
+/// 接口单次行数上限的取整单位
+const PAGE_ROUNDING: usize = 100;
+
+/// 单个分区最多翻页次数
+const MAX_PAGES: usize = 1000;
+
+/// 接口的分区方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PartitionKind {
+    /// 按交易日逐日拉取（trade_date），按月归档
+    TradeDate,
+    /// 按月区间拉取（start_date/end_date）
+    DateRange,
+    /// 按报告期拉取（period）
+    Period,
+    /// 无日期参数，整表快照
+    Snapshot,
+}
+
+/// 月份区间
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthRange {
+    /// 月份（YYYYMM）
+    pub month: String,
+    /// 起始日期（YYYYMMDD）
+    pub start: String,
+    /// 结束日期（YYYYMMDD）
+    pub end: String,
+}
+
+/// 镜像状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MirrorStatus {
+    /// 下载成功
+    Ok,
+    /// 已跳过（积分不足或缺少必选参数）
+    Skipped,
+    /// 下载失败
+    Failed,
+}
+
+/// 清单中的单个接口条目
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestEntry {
+    /// API 名称
+    pub api: String,
+    /// 状态
+    pub status: MirrorStatus,
+    /// 分区方式
+    pub partition: Option<PartitionKind>,
+    /// 写入的文件（相对镜像目录）
+    pub files: Vec<String>,
+    /// 写入的行数
+    pub rows: usize,
+    /// 可能被截断的分区（交易日、月份或报告期）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub truncated: Vec<String>,
+    /// 跳过、失败或截断的原因
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// 镜像清单
+#[derive(Debug, Clone, Serialize)]
+pub struct MirrorManifest {
+    /// 类别
+    pub category: String,
+    /// 起始日期
+    pub since: String,
+    /// 结束日期
+    pub until: String,
+    /// 生成时间
+    pub generated_at: String,
+    /// 各接口结果
+    pub apis: Vec<ManifestEntry>,
+}
+
+/// 处理 mirror 命令
+pub async fn run_mirror(args: &ParsedArgs) -> TResult<()> {
+    let category = args
+        .get_param("category")
+        .map(|v| v.to_string())
+        .ok_or_else(|| TushareError::ParseError("请使用 --category <类别> 指定要镜像的接口类别".to_string()))?;
+    if !get_categories().contains(&category.as_str()) {
+        return Err(TushareError::ValidationError(format!(
+            "未知类别: {} (可用类别: {})",
+            category,
+            get_categories().join("、")
+        )));
+    }
+
+    let since = args
+        .get_param("since")
+        .map(|v| v.to_string())
+        .ok_or_else(|| TushareError::ParseError("请使用 --since <YYYYMMDD> 指定起始日期".to_string()))?;
+    parse_date(&since)?;
+    let until = match args.get_param("until") {
+        Some(v) => v.to_string(),
+        None => format_date(chrono::Local::now().date_naive()),
+    };
+    parse_date(&until)?;
+
+    let dir = args
+        .get_param("dir")
+        .map(|v| PathBuf::from(v.to_string()))
+        .ok_or_else(|| TushareError::ParseError("请使用 --dir <目录> 指定镜像目录".to_string()))?;
+    let points = match args.get_param("points") {
+        Some(v) => Some(v.to_string().parse::<i32>().map_err(|_| {
+            TushareError::ValidationError(format!("无效的积分: {}", v))
+        })?),
+        None => None,
+    };
+    let exchange = args
+        .get_param("exchange")
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| DEFAULT_EXCHANGE.to_string());
+
+    let definitions = load_api_definitions();
+    let mut apis = get_apis_by_category(&definitions, &category);
+    apis.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let client = create_client(args)?;
+    // 交易日（或获取交易日历失败的原因），首个按交易日分区的接口用到时获取
+    let mut trading_days: Option<Result<Vec<String>, String>> = None;
+    let mut entries = Vec::new();
+
+    for (i, api) in apis.iter().enumerate() {
+        eprintln!("[{}/{}] {}", i + 1, apis.len(), api.name);
+
+        let mut entry = ManifestEntry {
+            api: api.name.clone(),
+            status: MirrorStatus::Skipped,
+            partition: None,
+            files: Vec::new(),
+            rows: 0,
+            truncated: Vec::new(),
+            message: None,
+        };
+
+        if let (Some(have), Some(need)) = (points, api.requires_points) {
+            if need > have {
+                entry.message = Some(format!("需要 {} 积分", need));
+                entries.push(entry);
+                continue;
+            }
+        }
+
+        let kind = match partition_kind(api) {
+            Ok(kind) => kind,
+            Err(reason) => {
+                entry.message = Some(reason);
+                entries.push(entry);
+                continue;
+            }
+        };
+        entry.partition = Some(kind);
+
+        if kind == PartitionKind::TradeDate && trading_days.is_none() {
+            let days = load_trading_days(&client, &exchange, &since, &until).await;
+            trading_days = Some(days.map_err(|e| e.to_string()));
+        }
+
+        let result = match (&trading_days, kind) {
+            (Some(Err(msg)), PartitionKind::TradeDate) => {
+                Err(TushareError::ValidationError(format!("获取交易日历失败: {}", msg)))
+            }
+            (days, _) => {
+                let days = days.as_ref().and_then(|d| d.as_deref().ok()).unwrap_or_default();
+                mirror_api(&client, api, kind, &since, &until, days, &dir, &mut entry).await
+            }
+        };
+
+        match result {
+            Ok(()) => entry.status = MirrorStatus::Ok,
+            Err(TushareError::ApiError { code, msg }) if is_permission_error(&msg) => {
+                entry.status = MirrorStatus::Skipped;
+                entry.message = Some(format!("权限不足 (code={}): {}", code, msg));
+            }
+            Err(e) => {
+                entry.status = MirrorStatus::Failed;
+                entry.message = Some(e.to_string());
+            }
+        }
+
+        if let Some(msg) = &entry.message {
+            eprintln!("  {}", msg);
+        }
+        entries.push(entry);
+    }
+
+    let manifest = MirrorManifest {
+        category,
+        since,
+        until,
+        generated_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        apis: entries,
+    };
+    let manifest_path = write_manifest(&dir, &manifest)?;
+
+    let count = |status| manifest.apis.iter().filter(|e| e.status == status).count();
+    println!(
+        "镜像完成: 成功 {}，跳过 {}，失败 {}",
+        count(MirrorStatus::Ok),
+        count(MirrorStatus::Skipped),
+        count(MirrorStatus::Failed)
+    );
+    println!("清单: {}", manifest_path.display());
+
+    Ok(())
+}
+
+/// 获取 [since, until] 内的交易日
+async fn load_trading_days(client: &TushareClient, exchange: &str, since: &str, until: &str) -> TResult<Vec<String>> {
+    let (start, end) = (parse_date(since)?, parse_date(until)?);
+    let calendar = load_calendar(client, exchange, start, end).await?;
+    Ok(calendar.range(start, end).into_iter().map(format_date).collect())
+}
+
+/// 下载单个接口的所有分区
+#[allow(clippy::too_many_arguments)]
+async fn mirror_api(
+    client: &TushareClient,
+    api: &ApiDefinition,
+    kind: PartitionKind,
+    since: &str,
+    until: &str,
+    trading_days: &[String],
+    dir: &Path,
+    entry: &mut ManifestEntry,
+) -> TResult<()> {
+    let mut record = |month: Option<&str>, fields: &[String], items: &[Vec<serde_json::Value>]| -> TResult<()> {
+        if items.is_empty() {
+            return Ok(());
+        }
+        let path = write_partition(dir, &api.name, month, fields, items)?;
+        let relative = path.strip_prefix(dir).unwrap_or(&path);
+        entry.files.push(relative.display().to_string());
+        entry.rows += items.len();
+        Ok(())
+    };
+
+    // 可能被截断的分区
+    let mut truncated = Vec::new();
+
+    match kind {
+        PartitionKind::TradeDate => {
+            for (month, days) in group_by_month(trading_days) {
+                let mut fields = Vec::new();
+                let mut items = Vec::new();
+                for day in days {
+                    let page = fetch(client, &api.name, serde_json::json!({ "trade_date": day })).await?;
+                    if page.truncated {
+                        truncated.push(day);
+                    }
+                    if fields.is_empty() {
+                        fields = page.fields;
+                    }
+                    items.extend(page.items);
+                }
+                record(Some(&month), &fields, &items)?;
+            }
+        }
+        PartitionKind::DateRange => {
+            for range in month_ranges(since, until)? {
+                let page = fetch(
+                    client,
+                    &api.name,
+                    serde_json::json!({ "start_date": range.start, "end_date": range.end }),
+                )
+                .await?;
+                if page.truncated {
+                    truncated.push(range.month.clone());
+                }
+                record(Some(&range.month), &page.fields, &page.items)?;
+            }
+        }
+        PartitionKind::Period => {
+            for period in quarter_periods(since, until)? {
+                let page = fetch(client, &api.name, serde_json::json!({ "period": period })).await?;
+                if page.truncated {
+                    truncated.push(period.clone());
+                }
+                record(Some(&period[..6]), &page.fields, &page.items)?;
+            }
+        }
+        PartitionKind::Snapshot => {
+            let page = fetch(client, &api.name, serde_json::json!({})).await?;
+            if page.truncated {
+                truncated.push("snapshot".to_string());
+            }
+            record(None, &page.fields, &page.items)?;
+        }
+    }
+
+    if !truncated.is_empty() {
+        entry.message = Some(format!("{} 个分区可能被截断（接口不支持 offset 翻页）", truncated.len()));
+        entry.truncated = truncated;
+    }
+    Ok(())
+}
+
+/// 一次下载（含翻页）的结果
+struct Fetched {
+    fields: Vec<String>,
+    items: Vec<Vec<serde_json::Value>>,
+    /// 触及单次行数上限且无法翻页，数据可能不完整
+    truncated: bool,
+}
+
+/// 调用 API 并返回字段和数据行
+///
+/// 返回的行数可能触及接口的单次行数上限时，按 `offset` 继续翻页；接口忽略
+/// `offset`（返回与上一页相同的数据）时停止翻页并标记为可能被截断。
+async fn fetch(client: &TushareClient, api_name: &str, params: serde_json::Value) -> TResult<Fetched> {
+    let mut fetched = Fetched {
+        fields: Vec::new(),
+        items: Vec::new(),
+        truncated: false,
+    };
+    for _ in 0..MAX_PAGES {
+        let mut params = params.clone();
+        if !fetched.items.is_empty() {
+            params["offset"] = serde_json::json!(fetched.items.len());
+        }
+        let response = client.call(api_name, Some(params), None).await?;
+        let Some(data) = response.data else {
+            return Ok(fetched);
+        };
+
+        if fetched.fields.is_empty() {
+            fetched.fields = data.fields;
+        }
+        if !fetched.items.is_empty() && data.items.first() == fetched.items.first() {
+            fetched.truncated = true;
+            return Ok(fetched);
+        }
+        let full = may_be_truncated(data.items.len());
+        fetched.items.extend(data.items);
+        if !full {
+            return Ok(fetched);
+        }
+    }
+    fetched.truncated = true;
+    Ok(fetched)
+}
+
+/// 返回的行数是否可能触及接口的单次行数上限
+///
+/// 各接口的上限都是整百（如 daily 6000、top_list 10000），返回整百行时需要继续翻页确认。
+pub fn may_be_truncated(rows: usize) -> bool {
+    rows > 0 && rows % PAGE_ROUNDING == 0
+}
+
+/// 根据接口参数判断分区方式
+///
+/// 含有日期以外的必选参数（如 ts_code）的接口无法整市场镜像，返回跳过原因。
+pub fn partition_kind(api: &ApiDefinition) -> Result<PartitionKind, String> {
+    let required: Vec<&str> = api
+        .parameters
+        .iter()
+        .filter(|p| p.required && !DATE_PARAMS.contains(&p.name.as_str()))
+        .map(|p| p.name.as_str())
+        .collect();
+    if !required.is_empty() {
+        return Err(format!("需要必选参数: {}", required.join(", ")));
+    }
+
+    let has = |name: &str| api.parameters.iter().any(|p| p.name == name);
+    if has("trade_date") {
+        Ok(PartitionKind::TradeDate)
+    } else if has("start_date") && has("end_date") {
+        Ok(PartitionKind::DateRange)
+    } else if has("period") {
+        Ok(PartitionKind::Period)
+    } else {
+        Ok(PartitionKind::Snapshot)
+    }
+}
+
+/// 将 [since, until] 拆分为自然月区间
+pub fn month_ranges(since: &str, until: &str) -> TResult<Vec<MonthRange>> {
+    let start = parse_date(since)?;
+    let end = parse_date(until)?;
+
+    let mut ranges = Vec::new();
+    let mut first = NaiveDate::from_ymd_opt(start.year(), start.month(), 1).unwrap_or(start);
+    while first <= end {
+        let next = if first.month() == 12 {
+            NaiveDate::from_ymd_opt(first.year() + 1, 1, 1)
+        } else {
+            NaiveDate::from_ymd_opt(first.year(), first.month() + 1, 1)
+        }
+        .ok_or_else(|| TushareError::ValidationError(format!("日期超出范围: {}", first)))?;
+        let last = next.pred_opt().unwrap_or(next);
+
+        ranges.push(MonthRange {
+            month: first.format("%Y%m").to_string(),
+            start: format_date(first.max(start)),
+            end: format_date(last.min(end)),
+        });
+        first = next;
+    }
+
+    Ok(ranges)
+}
+
+/// 获取 [since, until] 内的季度报告期（YYYYMMDD）
+pub fn quarter_periods(since: &str, until: &str) -> TResult<Vec<String>> {
+    let start = parse_date(since)?;
+    let end = parse_date(until)?;
+
+    let mut periods = Vec::new();
+    for year in start.year()..=end.year() {
+        for (month, day) in [(3, 31), (6, 30), (9, 30), (12, 31)] {
+            if let Some(date) = NaiveDate::from_ymd_opt(year, month, day) {
+                if date >= start && date <= end {
+                    periods.push(format_date(date));
+                }
+            }
+        }
+    }
+
+    Ok(periods)
+}
+
+/// 按月份（YYYYMM）对交易日分组
+pub fn group_by_month(days: &[String]) -> BTreeMap<String, Vec<String>> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for day in days.iter().filter(|d| d.len() >= 6) {
+        groups.entry(day[..6].to_string()).or_default().push(day.clone());
+    }
+    groups
+}
+
+/// 判断 API 错误是否为权限或积分不足
+fn is_permission_error(msg: &str) -> bool {
+    msg.contains("权限") || msg.contains("积分")
+}
+
+/// 写入分区文件
+fn write_partition(
+    dir: &Path,
+    api_name: &str,
+    month: Option<&str>,
+    fields: &[String],
+    items: &[Vec<serde_json::Value>],
+) -> TResult<PathBuf> {
+    let mut path = dir.join(api_name);
+    if let Some(month) = month {
+        path.push(format!("date={}", month));
+    }
+    std::fs::create_dir_all(&path)?;
+    path.push("part.csv");
+
+    let mut file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+    let header: Vec<String> = fields.iter().map(|f| escape_csv_value(f)).collect();
+    writeln!(file, "{}", header.join(","))?;
+    for item in items {
+        let values: Vec<String> = item
+            .iter()
+            .map(|v| escape_csv_value(&format_value(v)))
+            .collect();
+        writeln!(file, "{}", values.join(","))?;
+    }
+    file.flush()?;
+
+    Ok(path)
+}
+
+/// 写入清单文件
+fn write_manifest(dir: &Path, manifest: &MirrorManifest) -> TResult<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join("manifest.json");
+    std::fs::write(&path, serde_json::to_string_pretty(manifest)?)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::ApiParameter;
+
+    fn api_with_params(params: &[(&str, bool)]) -> ApiDefinition {
+        ApiDefinition {
+            name: "test_api".to_string(),
+            description: String::new(),
+            category: "股票数据".to_string(),
+            doc_id: 0,
+            parameters: params
+                .iter()
+                .map(|(name, required)| ApiParameter {
+                    name: name.to_string(),
+                    param_type: "str".to_string(),
+                    required: *required,
+                    description: String::new(),
+                })
+                .collect(),
+            output_fields: vec![],
+            requires_points: None,
+        }
+    }
+
+    #[test]
+    fn test_partition_kind() {
+        let api = api_with_params(&[("ts_code", false), ("trade_date", false), ("start_date", false), ("end_date", false)]);
+        assert_eq!(partition_kind(&api), Ok(PartitionKind::TradeDate));
+
+        let api = api_with_params(&[("start_date", false), ("end_date", false)]);
+        assert_eq!(partition_kind(&api), Ok(PartitionKind::DateRange));
+
+        let api = api_with_params(&[("period", false)]);
+        assert_eq!(partition_kind(&api), Ok(PartitionKind::Period));
+
+        let api = api_with_params(&[("exchange", false)]);
+        assert_eq!(partition_kind(&api), Ok(PartitionKind::Snapshot));
+
+        let api = api_with_params(&[("ts_code", true), ("trade_date", false)]);
+        assert!(partition_kind(&api).is_err());
+    }
+
+    #[test]
+    fn test_month_ranges() {
+        let ranges = month_ranges("20240115", "20240310").unwrap();
+        assert_eq!(ranges.len(), 3);
+        assert_eq!(ranges[0], MonthRange {
+            month: "202401".to_string(),
+            start: "20240115".to_string(),
+            end: "20240131".to_string(),
+        });
+        assert_eq!(ranges[1].end, "20240229");
+        assert_eq!(ranges[2].end, "20240310");
+
+        let ranges = month_ranges("20231201", "20240105").unwrap();
+        assert_eq!(ranges[1].month, "202401");
+    }
+
+    #[test]
+    fn test_quarter_periods() {
+        let periods = quarter_periods("20230401", "20240331").unwrap();
+        assert_eq!(periods, vec!["20230630", "20230930", "20231231", "20240331"]);
+    }
+
+    #[test]
+    fn test_group_by_month() {
+        let days = vec!["20240130".to_string(), "20240131".to_string(), "20240201".to_string()];
+        let groups = group_by_month(&days);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups["202401"].len(), 2);
+    }
+
+    #[test]
+    fn test_may_be_truncated() {
+        assert!(may_be_truncated(6000));
+        assert!(may_be_truncated(100));
+        assert!(!may_be_truncated(5123));
+        assert!(!may_be_truncated(0));
+    }
+}
@@ -5,6 +5,19 @@
 pub mod args;
 pub mod parser;
 pub mod commands;
+pub mod common;
+pub mod sync;
+pub mod mirror;
+pub mod join;
+pub mod sql;
+pub mod bar;
+pub mod cal;
+pub mod statement;
+pub mod screen;
+pub mod index_diff;
+pub mod watchlist;
+pub mod portfolio;
+pub mod diff;
 
 pub use args::{ParsedArgs, Options, OutputFormat, ParamValue};
 pub use parser::parse_args;
@@ -1,7 +1,9 @@
 //! CLI 参数解析器
 
-use crate::cli::args::{OutputFormat, ParsedArgs, ParamValue, Options};
+use crate::cli::args::{ChartKind, DecimalSpec, HeaderMode, NumberStyle, OutputFormat, ParsedArgs, ParamValue, Options};
 use crate::error::{TushareError, TResult};
+use crate::check::CheckRule;
+use crate::resample::Frequency;
 
 /// 将 kebab-case 转换为 snake_case
 pub fn kebab_to_snake(s: &str) -> String {
@@ -13,6 +15,13 @@ pub fn snake_to_kebab(s: &str) -> String {
     s.replace('_', "-")
 }
 
+/// 解析非负整数选项值
+fn parse_count(opt: &str, value: &str) -> TResult<usize> {
+    value
+        .parse()
+        .map_err(|_| TushareError::ParseError(format!("--{} 需要非负整数: {}", opt, value)))
+}
+
 /// 解析命令行参数
 pub fn parse_args(args: Vec<String>) -> TResult<ParsedArgs> {
     let mut iter = args.iter().peekable();
@@ -23,12 +32,11 @@ pub fn parse_args(args: Vec<String>) -> TResult<ParsedArgs> {
 
     // 解析选项和参数
     while let Some(arg) = iter.next() {
-        // 处理选项
-        if arg.starts_with('-') {
+        // 处理选项（单独的 `-` 表示标准输入，作为位置参数）
+        if arg.starts_with('-') && arg != "-" {
             // 短选项
-            if arg.starts_with("--") {
+            if let Some(opt) = arg.strip_prefix("--") {
                 // 长选项
-                let opt = &arg[2..];
                 match opt {
                     "help" => options.help = true,
                     "version" => options.version = true,
@@ -45,11 +53,146 @@ pub fn parse_args(args: Vec<String>) -> TResult<ParsedArgs> {
                         }
                     }
                     "pretty" => options.pretty = true,
+                    "max-width" => {
+                        if let Some(width) = iter.next() {
+                            options.max_width = parse_count(opt, width)?;
+                        }
+                    }
+                    "max-rows" => {
+                        if let Some(rows) = iter.next() {
+                            options.max_rows = Some(parse_count(opt, rows)?);
+                        }
+                    }
+                    "head" => {
+                        if let Some(rows) = iter.next() {
+                            options.head = Some(parse_count(opt, rows)?);
+                        }
+                    }
+                    "tail" => {
+                        if let Some(rows) = iter.next() {
+                            options.tail = Some(parse_count(opt, rows)?);
+                        }
+                    }
+                    "no-pager" => options.no_pager = true,
+                    "number-format" => {
+                        if let Some(style) = iter.next() {
+                            options.number_style = NumberStyle::from_str(style).ok_or_else(|| {
+                                TushareError::ParseError(format!("无效的数值格式: {} (可选 raw|cn|en)", style))
+                            })?;
+                        }
+                    }
+                    "decimals" => {
+                        if let Some(spec) = iter.next() {
+                            options.decimals = DecimalSpec::parse(spec).ok_or_else(|| {
+                                TushareError::ParseError(format!("无效的小数位数设置: {}", spec))
+                            })?;
+                        }
+                    }
+                    "no-color" => options.no_color = true,
+                    "header" => {
+                        if let Some(mode) = iter.next() {
+                            options.header = HeaderMode::from_str(mode).ok_or_else(|| {
+                                TushareError::ParseError(format!("无效的表头方式: {} (可选 desc|name|both)", mode))
+                            })?;
+                        }
+                    }
                     "token" => {
                         if let Some(token) = iter.next() {
                             options.token = Some(token.clone());
                         }
                     }
+                    "chart" => {
+                        if let Some(kind) = iter.next() {
+                            options.chart = Some(ChartKind::from_str(kind).ok_or_else(|| {
+                                TushareError::ParseError(format!("无效的图表类型: {} (可选 line|candle|bar)", kind))
+                            })?);
+                        }
+                    }
+                    "x" => {
+                        if let Some(field) = iter.next() {
+                            options.chart_x = Some(kebab_to_snake(field));
+                        }
+                    }
+                    "y" => {
+                        if let Some(field) = iter.next() {
+                            options.chart_y = Some(kebab_to_snake(field));
+                        }
+                    }
+                    "where" => {
+                        if let Some(clause) = iter.next() {
+                            options.where_clause = Some(clause.clone());
+                        }
+                    }
+                    "group-by" => {
+                        if let Some(fields) = iter.next() {
+                            options.group_by = Some(
+                                fields
+                                    .split(',')
+                                    .map(|f| kebab_to_snake(f.trim()))
+                                    .filter(|f| !f.is_empty())
+                                    .collect(),
+                            );
+                        }
+                    }
+                    "agg" => {
+                        if let Some(spec) = iter.next() {
+                            options.agg = Some(spec.clone());
+                        }
+                    }
+                    "indicators" => {
+                        if let Some(spec) = iter.next() {
+                            options.indicators = Some(spec.clone());
+                        }
+                    }
+                    "resample" => {
+                        if let Some(freq) = iter.next() {
+                            options.resample = Some(Frequency::from_str(freq).ok_or_else(|| {
+                                TushareError::ParseError(format!(
+                                    "无效的重采样周期: {} (可选 30min|2h|D|W|M|Q|Y，分钟数不超过 240)",
+                                    freq
+                                ))
+                            })?);
+                        }
+                    }
+                    "check" => {
+                        // 规则列表可省略（按接口选择默认规则），后面是命令名或其它选项时不消耗该参数
+                        let next = iter.peek().filter(|s| !s.starts_with('-'));
+                        let rules = next.and_then(|s| CheckRule::parse_list(s));
+                        match (next, &rules) {
+                            (Some(_), Some(_)) => {
+                                iter.next();
+                            }
+                            (Some(s), None) if s.contains(',') || !parsed.command.is_empty() => {
+                                return Err(TushareError::ParseError(format!(
+                                    "无效的检查规则: {} (可选 gaps,dups,nulls,ohlc,limit 或 all)",
+                                    s
+                                )));
+                            }
+                            _ => {}
+                        }
+                        options.check = Some(rules.unwrap_or_default());
+                    }
+                    "sort" => {
+                        if let Some(spec) = iter.next() {
+                            options.sort = Some(spec.clone());
+                        }
+                    }
+                    "select" => {
+                        if let Some(fields) = iter.next() {
+                            options.select = Some(
+                                fields
+                                    .split(',')
+                                    .map(|f| kebab_to_snake(f.trim()))
+                                    .filter(|f| !f.is_empty())
+                                    .collect(),
+                            );
+                        }
+                    }
+                    "output" => {
+                        if let Some(path) = iter.next() {
+                            options.output = Some(path.clone());
+                        }
+                    }
                     _ => {
                         // 可能是 API 参数（格式：--param value 或 --param=value）
                         if let Some(eq_idx) = opt.find('=') {
@@ -61,6 +204,9 @@ pub fn parse_args(args: Vec<String>) -> TResult<ParsedArgs> {
                             if !value.starts_with('-') {
                                 let value = iter.next().unwrap();
                                 parsed.add_param(kebab_to_snake(opt), ParamValue::from_str(value.clone()));
+                            } else {
+                                // 后面紧跟其他选项，视为标志参数
+                                parsed.add_param(kebab_to_snake(opt), ParamValue::Boolean(true));
                             }
                         } else {
                             // 标志参数，视为布尔值 true
@@ -84,6 +230,11 @@ pub fn parse_args(args: Vec<String>) -> TResult<ParsedArgs> {
                             }
                         }
                         'p' => options.pretty = true,
+                        'o' => {
+                            if let Some(path) = iter.next() {
+                                options.output = Some(path.clone());
+                            }
+                        }
                         't' => {
                             if let Some(token) = iter.next() {
                                 options.token = Some(token.clone());
@@ -107,8 +258,6 @@ pub fn parse_args(args: Vec<String>) -> TResult<ParsedArgs> {
     if parsed.command.is_empty() {
         if options.version {
             parsed.command = "version".to_string();
-        } else if options.help {
-            parsed.command = "help".to_string();
         } else {
             parsed.command = "help".to_string();
         }
@@ -164,6 +313,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_stdin_positional() {
+        let args: Vec<String> = ["tushare", "diff", "before.json", "-", "--key", "ts_code"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let parsed = parse_args(args).unwrap();
+        assert_eq!(parsed.positional, ["before.json", "-"]);
+        assert_eq!(parsed.get_param("key"), Some(&ParamValue::String("ts_code".to_string())));
+    }
+
     #[test]
     fn test_parse_with_equals() {
         let args = vec![
@@ -175,6 +335,208 @@ mod tests {
         assert_eq!(parsed.get_param("limit"), Some(&ParamValue::Number(10.0)));
     }
 
+    #[test]
+    fn test_parse_flag_before_option() {
+        let args = vec![
+            "tushare".to_string(),
+            "sync".to_string(),
+            "daily".to_string(),
+            "--dry-run".to_string(),
+            "--store".to_string(),
+            "market.db".to_string(),
+        ];
+        let parsed = parse_args(args).unwrap();
+        assert_eq!(parsed.positional, vec!["daily"]);
+        assert_eq!(parsed.get_param("dry_run"), Some(&ParamValue::Boolean(true)));
+        assert_eq!(
+            parsed.get_param("store"),
+            Some(&ParamValue::String("market.db".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_max_width() {
+        let args = vec![
+            "tushare".to_string(),
+            "stock_company".to_string(),
+            "--max-width".to_string(),
+            "12".to_string(),
+        ];
+        let parsed = parse_args(args).unwrap();
+        assert_eq!(parsed.options.max_width, 12);
+        assert!(parsed.params.is_empty());
+
+        let args = vec![
+            "tushare".to_string(),
+            "--max-width".to_string(),
+            "abc".to_string(),
+        ];
+        assert!(parse_args(args).is_err());
+    }
+
+    #[test]
+    fn test_parse_row_options() {
+        let args = vec![
+            "tushare".to_string(),
+            "daily".to_string(),
+            "--max-rows".to_string(),
+            "0".to_string(),
+            "--tail".to_string(),
+            "5".to_string(),
+            "--no-pager".to_string(),
+        ];
+        let parsed = parse_args(args).unwrap();
+        assert_eq!(parsed.options.max_rows, Some(0));
+        assert_eq!(parsed.options.tail, Some(5));
+        assert_eq!(parsed.options.head, None);
+        assert!(parsed.options.no_pager);
+
+        let args = vec![
+            "tushare".to_string(),
+            "--head".to_string(),
+            "-1".to_string(),
+        ];
+        assert!(parse_args(args).is_err());
+    }
+
+    #[test]
+    fn test_parse_number_options() {
+        let args = vec![
+            "tushare".to_string(),
+            "daily_basic".to_string(),
+            "--number-format".to_string(),
+            "cn".to_string(),
+            "--decimals".to_string(),
+            "close=2".to_string(),
+            "--no-color".to_string(),
+        ];
+        let parsed = parse_args(args).unwrap();
+        assert_eq!(parsed.options.number_style, NumberStyle::Chinese);
+        assert_eq!(parsed.options.decimals.for_column("close"), Some(2));
+        assert!(parsed.options.no_color);
+        assert_eq!(parsed.options.header, HeaderMode::Name);
+
+        let args = vec![
+            "tushare".to_string(),
+            "--number-format".to_string(),
+            "fr".to_string(),
+        ];
+        assert!(parse_args(args).is_err());
+    }
+
+    #[test]
+    fn test_parse_header() {
+        let args = vec![
+            "tushare".to_string(),
+            "daily_basic".to_string(),
+            "--header".to_string(),
+            "desc".to_string(),
+        ];
+        let parsed = parse_args(args).unwrap();
+        assert_eq!(parsed.options.header, HeaderMode::Desc);
+        assert!(parsed.params.is_empty());
+    }
+
+    #[test]
+    fn test_parse_output() {
+        let args = vec![
+            "tushare".to_string(),
+            "daily".to_string(),
+            "-f".to_string(),
+            "html".to_string(),
+            "--output".to_string(),
+            "report.html".to_string(),
+        ];
+        let parsed = parse_args(args).unwrap();
+        assert_eq!(parsed.options.format, OutputFormat::Html);
+        assert_eq!(parsed.options.output.as_deref(), Some("report.html"));
+        assert!(parsed.params.is_empty());
+    }
+
+    #[test]
+    fn test_parse_chart() {
+        let args = vec![
+            "tushare".to_string(),
+            "daily".to_string(),
+            "--chart".to_string(),
+            "candle".to_string(),
+            "--x".to_string(),
+            "trade_date".to_string(),
+            "--y".to_string(),
+            "close".to_string(),
+        ];
+        let parsed = parse_args(args).unwrap();
+        assert_eq!(parsed.options.chart, Some(ChartKind::Candle));
+        assert_eq!(parsed.options.chart_x.as_deref(), Some("trade_date"));
+        assert_eq!(parsed.options.chart_y.as_deref(), Some("close"));
+        assert!(parsed.params.is_empty());
+
+        let args = vec!["tushare".to_string(), "--chart".to_string(), "pie".to_string()];
+        assert!(parse_args(args).is_err());
+    }
+
+    #[test]
+    fn test_parse_post_processing() {
+        let args = vec![
+            "tushare".to_string(),
+            "daily".to_string(),
+            "--where".to_string(),
+            "pct_chg > 9.5".to_string(),
+            "--group-by".to_string(),
+            "industry,trade_date".to_string(),
+            "--agg".to_string(),
+            "sum(amount),count()".to_string(),
+            "--sort".to_string(),
+            "-amount,ts_code".to_string(),
+            "--select".to_string(),
+            "ts_code, pct_chg".to_string(),
+            "--indicators".to_string(),
+            "ma5,macd".to_string(),
+            "--resample".to_string(),
+            "2h".to_string(),
+        ];
+        let parsed = parse_args(args).unwrap();
+        assert_eq!(parsed.options.where_clause.as_deref(), Some("pct_chg > 9.5"));
+        assert_eq!(
+            parsed.options.group_by,
+            Some(vec!["industry".to_string(), "trade_date".to_string()])
+        );
+        assert_eq!(parsed.options.agg.as_deref(), Some("sum(amount),count()"));
+        assert_eq!(parsed.options.sort.as_deref(), Some("-amount,ts_code"));
+        assert_eq!(
+            parsed.options.select,
+            Some(vec!["ts_code".to_string(), "pct_chg".to_string()])
+        );
+        assert_eq!(parsed.options.indicators.as_deref(), Some("ma5,macd"));
+        assert_eq!(parsed.options.resample, Some(Frequency::Minutes(120)));
+        assert!(parsed.params.is_empty());
+    }
+
+    #[test]
+    fn test_parse_check() {
+        let parse = |args: &[&str]| parse_args(args.iter().map(|s| s.to_string()).collect()).unwrap();
+
+        let parsed = parse(&["tushare", "daily", "--check", "ohlc,limit", "--ts-code", "000001.SZ"]);
+        assert_eq!(parsed.options.check, Some(vec![CheckRule::Ohlc, CheckRule::Limit]));
+
+        // 省略规则时为默认规则（空列表），后面的命令名不被当作规则
+        let parsed = parse(&["tushare", "--check", "daily", "--ts-code", "000001.SZ"]);
+        assert_eq!(parsed.command, "daily");
+        assert_eq!(parsed.options.check, Some(Vec::new()));
+
+        let parsed = parse(&["tushare", "daily", "--check", "--ts-code", "000001.SZ"]);
+        assert_eq!(parsed.options.check, Some(Vec::new()));
+
+        let parsed = parse(&["tushare", "daily", "--check", "all"]);
+        assert_eq!(parsed.options.check, Some(CheckRule::ALL.to_vec()));
+
+        // 拼错的规则名报错，而不是静默执行全部规则
+        let parse_err = |args: &[&str]| parse_args(args.iter().map(|s| s.to_string()).collect()).is_err();
+        assert!(parse_err(&["tushare", "daily", "--check", "olhc,limit"]));
+        assert!(parse_err(&["tushare", "daily", "--check", "olhc"]));
+        assert!(parse_err(&["tushare", "--check", "olhc,limit", "daily"]));
+    }
+
     #[test]
     fn test_parse_options() {
         let args = vec![
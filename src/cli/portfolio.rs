@@ -0,0 +1,544 @@
+//! 持仓估值命令
+//!
+//! `tushare portfolio value holdings.csv --date 20240630` 读取持仓（代码、数量、成本价），
+//! 按证券类型调用 `daily`、`fund_daily`、`hk_daily` 或 `fut_daily` 取得截至当日的最新价格，
+//! 计算市值、浮动盈亏、权重和当日盈亏。非人民币资产通过 `fx_daily` 折算为 `--base` 币种。
+//!
+//! 持仓文件为带表头的 CSV，必需列为 `ts_code`（或 `code`）、`qty`、`cost`，可选列：
+//!
+//! - `type`：`stock`/`fund`/`hk`/`fut`，默认按代码判断
+//! - `currency`：计价币种，港股默认 HKD，其余默认 CNY
+//! - `multiplier`：合约乘数，期货未指定时取 `fut_basic`
+
+use crate::api::ApiDefinition;
+use crate::calendar::{format_date, futures_exchange, parse_date};
+use crate::cli::args::ParsedArgs;
+use crate::cli::commands::{create_client, request_api};
+use crate::cli::watchlist::parse_csv;
+use crate::client::{Row, TushareClient};
+use crate::error::{TushareError, TResult};
+use crate::output::{format_value, output_data};
+use crate::query::{round_to, value_as_f64};
+use chrono::Duration;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+
+/// 默认折算币种
+const DEFAULT_BASE: &str = "CNY";
+
+/// 金额和比例保留的小数位
+const VALUE_DECIMALS: i32 = 2;
+
+/// 向前查找价格的自然日数（覆盖长假）
+const LOOKBACK_DAYS: i64 = 20;
+
+/// 汇率报价商
+const FX_EXCHANGE: &str = "FXCM";
+
+/// 以美元为报价币种的货币对（如 EURUSD），其余为 USDxxx
+const USD_QUOTED: [&str; 4] = ["EUR", "GBP", "AUD", "NZD"];
+
+/// 证券类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecurityKind {
+    /// A 股
+    Stock,
+    /// 场内基金
+    Fund,
+    /// 港股
+    Hk,
+    /// 期货
+    Future,
+}
+
+impl SecurityKind {
+    /// 从字符串解析证券类型
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "stock" => Some(SecurityKind::Stock),
+            "fund" | "etf" => Some(SecurityKind::Fund),
+            "hk" => Some(SecurityKind::Hk),
+            "fut" | "future" => Some(SecurityKind::Future),
+            _ => None,
+        }
+    }
+
+    /// 按代码判断证券类型
+    pub fn detect(ts_code: &str) -> Self {
+        let (symbol, suffix) = ts_code.rsplit_once('.').unwrap_or((ts_code, ""));
+        match suffix.to_uppercase().as_str() {
+            "HK" => SecurityKind::Hk,
+            _ if futures_exchange(ts_code).is_some() => SecurityKind::Future,
+            // 沪市 5 开头、深市 15/16/18 开头为场内基金
+            "SH" if symbol.starts_with('5') => SecurityKind::Fund,
+            "SZ" if ["15", "16", "18"].iter().any(|p| symbol.starts_with(p)) => SecurityKind::Fund,
+            _ => SecurityKind::Stock,
+        }
+    }
+
+    /// 输出中的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecurityKind::Stock => "stock",
+            SecurityKind::Fund => "fund",
+            SecurityKind::Hk => "hk",
+            SecurityKind::Future => "fut",
+        }
+    }
+
+    /// 行情接口
+    pub fn api_name(&self) -> &'static str {
+        match self {
+            SecurityKind::Stock => "daily",
+            SecurityKind::Fund => "fund_daily",
+            SecurityKind::Hk => "hk_daily",
+            SecurityKind::Future => "fut_daily",
+        }
+    }
+
+    /// 默认计价币种
+    pub fn currency(&self) -> &'static str {
+        match self {
+            SecurityKind::Hk => "HKD",
+            _ => "CNY",
+        }
+    }
+}
+
+/// 一笔持仓
+#[derive(Debug, Clone, PartialEq)]
+pub struct Holding {
+    /// 代码
+    pub ts_code: String,
+    /// 数量（负数为空头）
+    pub qty: f64,
+    /// 成本价
+    pub cost: f64,
+    /// 证券类型
+    pub kind: SecurityKind,
+    /// 计价币种
+    pub currency: String,
+    /// 合约乘数
+    pub multiplier: Option<f64>,
+}
+
+/// 截至估值日的最新价格
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quote {
+    /// 价格日期
+    pub trade_date: String,
+    /// 收盘价（期货为结算价）
+    pub close: f64,
+    /// 前收盘价（期货为前结算价）
+    pub pre_close: Option<f64>,
+}
+
+/// 处理 portfolio 命令
+pub async fn run_portfolio(args: &ParsedArgs) -> TResult<()> {
+    let (Some("value"), Some(file)) = (args.positional.first().map(|s| s.as_str()), args.positional.get(1)) else {
+        return Err(TushareError::ParseError(
+            "用法: tushare portfolio value <持仓文件.csv> [--date YYYYMMDD] [--base CNY]".to_string(),
+        ));
+    };
+    let date = match args.get_param("date") {
+        Some(v) => parse_date(&v.to_string())?,
+        None => chrono::Local::now().date_naive(),
+    };
+    let base = args
+        .get_param("base")
+        .map(|v| v.to_string().to_uppercase())
+        .unwrap_or_else(|| DEFAULT_BASE.to_string());
+
+    let mut holdings = parse_holdings(&parse_csv(&fs::read_to_string(file)?))?;
+    let client = create_client(args)?;
+    let (start, end) = (format_date(date - Duration::days(LOOKBACK_DAYS)), format_date(date));
+
+    let mut quotes = HashMap::new();
+    for holding in &holdings {
+        let rows = request_api(
+            &client,
+            holding.kind.api_name(),
+            serde_json::json!({ "ts_code": holding.ts_code, "start_date": start, "end_date": end }),
+        )
+        .await?
+        .to_objects();
+        match latest_quote(&rows, holding.kind) {
+            Some(quote) => {
+                quotes.insert(holding.ts_code.clone(), quote);
+            }
+            None => eprintln!("{}: {} 至 {} 没有行情", holding.ts_code, start, end),
+        }
+    }
+    fill_multipliers(&client, &mut holdings).await?;
+
+    let currencies: BTreeSet<&str> = holdings.iter().map(|h| h.currency.as_str()).collect();
+    let mut fx = HashMap::new();
+    for currency in currencies.iter().copied().chain([base.as_str()]) {
+        let Some((pair, _)) = usd_pair(currency) else { continue };
+        if fx.contains_key(&pair) {
+            continue;
+        }
+        let rows = request_api(
+            &client,
+            "fx_daily",
+            serde_json::json!({ "ts_code": pair, "start_date": start, "end_date": end, "exchange": FX_EXCHANGE }),
+        )
+        .await?
+        .to_objects();
+        if let Some(rate) = latest_fx(&rows) {
+            fx.insert(pair, rate);
+        }
+    }
+    let mut rates = HashMap::new();
+    for currency in currencies {
+        match fx_rate(currency, &base, &fx) {
+            Some(rate) => {
+                rates.insert(currency.to_string(), rate);
+            }
+            None => eprintln!("{}: 未获取到 {} 兑 {} 的汇率", format_date(date), currency, base),
+        }
+    }
+
+    let rows = valuation(&holdings, &quotes, &rates, &format_date(date));
+    let total = |field: &str| rows.iter().filter_map(|r| r.get(field).and_then(value_as_f64)).sum::<f64>();
+    eprintln!(
+        "{} 估值 ({}): 市值 {:.2}，浮动盈亏 {:.2}，当日盈亏 {:.2}",
+        format_date(date),
+        base,
+        total("market_value"),
+        total("pnl"),
+        total("day_change")
+    );
+    output_data(&rows, &args.options, Some(&portfolio_definition()), Some(&args.params_to_json()))
+}
+
+/// 解析持仓文件
+pub fn parse_holdings(rows: &[Row]) -> TResult<Vec<Holding>> {
+    let text = |row: &Row, names: &[&str]| {
+        names
+            .iter()
+            .find_map(|n| row.get(*n))
+            .map(format_value)
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+    let number = |row: &Row, names: &[&str], line: usize| -> TResult<Option<f64>> {
+        text(row, names)
+            .map(|s| {
+                s.replace(',', "").parse().map_err(|_| {
+                    TushareError::ValidationError(format!("持仓第 {} 行 {} 不是数字: {}", line, names[0], s))
+                })
+            })
+            .transpose()
+    };
+
+    let mut holdings = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        // 表头为第 1 行
+        let line = i + 2;
+        let required = |name: &str| TushareError::ValidationError(format!("持仓第 {} 行缺少 {}", line, name));
+        let ts_code = text(row, &["ts_code", "code"]).ok_or_else(|| required("ts_code"))?.to_uppercase();
+        let kind = match text(row, &["type"]) {
+            Some(t) => SecurityKind::from_str(&t)
+                .ok_or_else(|| TushareError::ValidationError(format!("持仓第 {} 行证券类型无效: {}", line, t)))?,
+            None => SecurityKind::detect(&ts_code),
+        };
+        holdings.push(Holding {
+            qty: number(row, &["qty", "quantity", "shares"], line)?.ok_or_else(|| required("qty"))?,
+            cost: number(row, &["cost", "cost_price"], line)?.ok_or_else(|| required("cost"))?,
+            currency: text(row, &["currency"])
+                .map(|c| c.to_uppercase())
+                .unwrap_or_else(|| kind.currency().to_string()),
+            multiplier: number(row, &["multiplier"], line)?,
+            ts_code,
+            kind,
+        });
+    }
+    Ok(holdings)
+}
+
+/// 最新一天的价格，期货使用结算价
+pub fn latest_quote(rows: &[Row], kind: SecurityKind) -> Option<Quote> {
+    let (close, pre_close) = match kind {
+        SecurityKind::Future => ("settle", "pre_settle"),
+        _ => ("close", "pre_close"),
+    };
+    let row = rows.iter().max_by_key(|r| r.get("trade_date").map(format_value).unwrap_or_default())?;
+    Some(Quote {
+        trade_date: row.get("trade_date").map(format_value)?,
+        close: row.get(close).and_then(value_as_f64)?,
+        pre_close: row.get(pre_close).and_then(value_as_f64),
+    })
+}
+
+/// 期货合约未指定乘数时从 `fut_basic` 获取（按交易所查询）
+async fn fill_multipliers(client: &TushareClient, holdings: &mut [Holding]) -> TResult<()> {
+    let mut exchanges: BTreeSet<&'static str> = BTreeSet::new();
+    for holding in holdings.iter().filter(|h| h.kind == SecurityKind::Future && h.multiplier.is_none()) {
+        if let Some(exchange) = futures_exchange(&holding.ts_code) {
+            exchanges.insert(exchange);
+        }
+    }
+
+    let mut multipliers = HashMap::new();
+    for exchange in exchanges {
+        let rows = client
+            .call(
+                "fut_basic",
+                Some(serde_json::json!({ "exchange": exchange })),
+                Some("ts_code,multiplier,per_unit".to_string()),
+            )
+            .await?
+            .to_objects();
+        for row in rows {
+            // 商品期货的乘数为每手单位，金融期货为合约乘数
+            let multiplier = ["multiplier", "per_unit"]
+                .iter()
+                .find_map(|f| row.get(*f).and_then(value_as_f64).filter(|m| *m > 0.0));
+            if let (Some(code), Some(multiplier)) = (row.get("ts_code").map(format_value), multiplier) {
+                multipliers.insert(code, multiplier);
+            }
+        }
+    }
+
+    for holding in holdings.iter_mut().filter(|h| h.kind == SecurityKind::Future && h.multiplier.is_none()) {
+        holding.multiplier = multipliers.get(&holding.ts_code).copied();
+        if holding.multiplier.is_none() {
+            eprintln!("{}: 未获取到合约乘数，按 1 计算", holding.ts_code);
+        }
+    }
+    Ok(())
+}
+
+/// 币种与美元的货币对，及是否以美元报价（XXXUSD），美元本身为 None
+///
+/// FXCM 没有在岸人民币，CNY 使用离岸人民币 USDCNH。
+pub fn usd_pair(currency: &str) -> Option<(String, bool)> {
+    let currency = match currency {
+        "USD" => return None,
+        "CNY" => "CNH",
+        other => other,
+    };
+    if USD_QUOTED.contains(&currency) {
+        Some((format!("{}USD.{}", currency, FX_EXCHANGE), true))
+    } else {
+        Some((format!("USD{}.{}", currency, FX_EXCHANGE), false))
+    }
+}
+
+/// 最新一天买卖收盘价的中间价
+pub fn latest_fx(rows: &[Row]) -> Option<f64> {
+    let row = rows.iter().max_by_key(|r| r.get("trade_date").map(format_value).unwrap_or_default())?;
+    let bid = row.get("bid_close").and_then(value_as_f64);
+    let ask = row.get("ask_close").and_then(value_as_f64);
+    match (bid, ask) {
+        (Some(bid), Some(ask)) => Some((bid + ask) / 2.0),
+        (bid, ask) => bid.or(ask),
+    }
+}
+
+/// 1 单位 `currency` 折合多少 `base`，`fx` 为货币对的中间价
+pub fn fx_rate(currency: &str, base: &str, fx: &HashMap<String, f64>) -> Option<f64> {
+    let usd_value = |currency: &str| -> Option<f64> {
+        match usd_pair(currency) {
+            None => Some(1.0),
+            Some((pair, true)) => fx.get(&pair).copied(),
+            Some((pair, false)) => fx.get(&pair).filter(|r| **r != 0.0).map(|r| 1.0 / r),
+        }
+    };
+    if currency == base {
+        return Some(1.0);
+    }
+    Some(usd_value(currency)? / usd_value(base)?)
+}
+
+/// 计算每笔持仓的市值和盈亏，金额按 `rates` 折算为基准币种
+///
+/// 价格日期早于估值日 `date`（停牌或该市场休市）时，当日涨跌为 0。
+pub fn valuation(
+    holdings: &[Holding],
+    quotes: &HashMap<String, Quote>,
+    rates: &HashMap<String, f64>,
+    date: &str,
+) -> Vec<Row> {
+    // 每单位的当日涨跌
+    let change = |q: &Quote| if q.trade_date == date { q.pre_close.map(|p| q.close - p) } else { Some(0.0) };
+    let values: Vec<Option<(f64, f64, Option<f64>)>> = holdings
+        .iter()
+        .map(|h| {
+            let quote = quotes.get(&h.ts_code)?;
+            let unit = h.qty * h.multiplier.unwrap_or(1.0) * rates.get(&h.currency)?;
+            let day_change = change(quote).map(|c| c * unit);
+            Some((quote.close * unit, h.cost * unit, day_change))
+        })
+        .collect();
+    let total: f64 = values.iter().flatten().map(|v| v.0).sum();
+
+    holdings
+        .iter()
+        .zip(values)
+        .map(|(h, value)| {
+            let quote = quotes.get(&h.ts_code);
+            let mut row = Row::new();
+            let mut set = |name: &str, value: serde_json::Value| {
+                row.insert(name.to_string(), value);
+            };
+            set("ts_code", serde_json::json!(h.ts_code));
+            set("type", serde_json::json!(h.kind.as_str()));
+            set("currency", serde_json::json!(h.currency));
+            set("qty", serde_json::json!(h.qty));
+            set("cost", serde_json::json!(h.cost));
+            set("trade_date", serde_json::json!(quote.map(|q| q.trade_date.clone())));
+            set("close", serde_json::json!(quote.map(|q| q.close)));
+            set("pre_close", serde_json::json!(quote.and_then(|q| q.pre_close)));
+            set("fx_rate", serde_json::json!(rates.get(&h.currency)));
+
+            let (market_value, cost_value, day_change) = match value {
+                Some((market_value, cost_value, day_change)) => (Some(market_value), Some(cost_value), day_change),
+                None => (None, None, None),
+            };
+            let pnl = market_value.zip(cost_value).map(|(m, c)| m - c);
+            let round = |v: f64| round_to(v, VALUE_DECIMALS);
+            let ratio = |a: Option<f64>, b: Option<f64>| {
+                a.zip(b.filter(|b| *b != 0.0)).map(|(a, b)| round(a / b.abs() * 100.0))
+            };
+            set("market_value", serde_json::json!(market_value.map(round)));
+            set("cost_value", serde_json::json!(cost_value.map(round)));
+            set("pnl", serde_json::json!(pnl.map(round)));
+            set("pnl_pct", serde_json::json!(ratio(pnl, cost_value)));
+            set("weight", serde_json::json!(ratio(market_value, Some(total))));
+            set("day_change", serde_json::json!(day_change.map(round)));
+            set("pct_chg", serde_json::json!(ratio(quote.and_then(change), quote.and_then(|q| q.pre_close))));
+            row
+        })
+        .collect()
+}
+
+/// 输出字段定义
+fn portfolio_definition() -> ApiDefinition {
+    ApiDefinition::synthetic(
+        "portfolio",
+        "持仓估值",
+        &[
+            ("ts_code", "str", "代码"),
+            ("type", "str", "类型"),
+            ("currency", "str", "币种"),
+            ("qty", "float", "数量"),
+            ("cost", "float", "成本价"),
+            ("trade_date", "str", "价格日期"),
+            ("close", "float", "现价"),
+            ("pre_close", "float", "昨收"),
+            ("fx_rate", "float", "汇率"),
+            ("market_value", "float", "市值"),
+            ("cost_value", "float", "成本"),
+            ("pnl", "float", "浮动盈亏"),
+            ("pnl_pct", "float", "收益率(%)"),
+            ("weight", "float", "权重(%)"),
+            ("day_change", "float", "当日盈亏"),
+            ("pct_chg", "float", "涨跌幅(%)"),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::rows_from_json;
+    use serde_json::json;
+
+    #[test]
+    fn test_detect_kind() {
+        assert_eq!(SecurityKind::detect("600519.SH"), SecurityKind::Stock);
+        assert_eq!(SecurityKind::detect("510300.SH"), SecurityKind::Fund);
+        assert_eq!(SecurityKind::detect("159915.SZ"), SecurityKind::Fund);
+        assert_eq!(SecurityKind::detect("000001.SZ"), SecurityKind::Stock);
+        assert_eq!(SecurityKind::detect("00700.HK"), SecurityKind::Hk);
+        assert_eq!(SecurityKind::detect("IF2409.CFX"), SecurityKind::Future);
+    }
+
+    #[test]
+    fn test_parse_holdings() {
+        let holdings = parse_holdings(&parse_csv(
+            "code,qty,cost,type,multiplier\n600519.sh,100,\"1,500.5\",,\n00700.HK,200,300,,\nIF2409.CFX,-1,3500,fut,300\n",
+        ))
+        .unwrap();
+        assert_eq!(holdings[0].ts_code, "600519.SH");
+        assert_eq!(holdings[0].cost, 1500.5);
+        assert_eq!(holdings[1].currency, "HKD");
+        assert_eq!(holdings[2].qty, -1.0);
+        assert_eq!(holdings[2].multiplier, Some(300.0));
+
+        assert!(parse_holdings(&parse_csv("code,qty\n600519.SH,100\n")).is_err());
+        assert!(parse_holdings(&parse_csv("code,qty,cost\n600519.SH,abc,1\n")).is_err());
+    }
+
+    #[test]
+    fn test_latest_quote() {
+        let data = rows_from_json(json!([
+            {"trade_date": "20240628", "close": 3500.0, "pre_close": 3480.0, "settle": 3510.0, "pre_settle": 3490.0},
+            {"trade_date": "20240627", "close": 3480.0, "pre_close": 3470.0, "settle": 3490.0, "pre_settle": 3475.0},
+        ]));
+        let quote = latest_quote(&data, SecurityKind::Stock).unwrap();
+        assert_eq!((quote.trade_date.as_str(), quote.close, quote.pre_close), ("20240628", 3500.0, Some(3480.0)));
+        assert_eq!(latest_quote(&data, SecurityKind::Future).unwrap().close, 3510.0);
+        assert_eq!(latest_quote(&[], SecurityKind::Stock), None);
+    }
+
+    #[test]
+    fn test_fx_rate() {
+        let fx: HashMap<String, f64> = [("USDCNH.FXCM", 7.3), ("USDHKD.FXCM", 7.8), ("EURUSD.FXCM", 1.07)]
+            .iter()
+            .map(|(k, v)| (k.to_string(), *v))
+            .collect();
+        assert_eq!(fx_rate("CNY", "CNY", &fx), Some(1.0));
+        assert!((fx_rate("USD", "CNY", &fx).unwrap() - 7.3).abs() < 1e-12);
+        assert!((fx_rate("HKD", "CNY", &fx).unwrap() - 7.3 / 7.8).abs() < 1e-12);
+        assert!((fx_rate("EUR", "CNY", &fx).unwrap() - 1.07 * 7.3).abs() < 1e-12);
+        assert_eq!(fx_rate("JPY", "CNY", &fx), None);
+
+        let data = rows_from_json(json!([{"trade_date": "20240628", "bid_close": 7.29, "ask_close": 7.31}]));
+        assert!((latest_fx(&data).unwrap() - 7.3).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_valuation() {
+        let holdings = parse_holdings(&parse_csv(
+            "code,qty,cost\n600519.SH,100,1500\n00700.HK,1000,350\n000001.SZ,100,10\n",
+        ))
+        .unwrap();
+        let quotes: HashMap<String, Quote> = [
+            ("600519.SH", 1470.0, 1460.0),
+            ("00700.HK", 372.0, 370.0),
+        ]
+        .iter()
+        .map(|(code, close, pre_close)| {
+            let quote = Quote { trade_date: "20240628".to_string(), close: *close, pre_close: Some(*pre_close) };
+            (code.to_string(), quote)
+        })
+        .collect();
+        let rates: HashMap<String, f64> = [("CNY".to_string(), 1.0), ("HKD".to_string(), 0.9)].into_iter().collect();
+
+        let rows = valuation(&holdings, &quotes, &rates, "20240628");
+        assert_eq!(rows[0]["market_value"], json!(147000.0));
+        assert_eq!(rows[0]["pnl"], json!(-3000.0));
+        assert_eq!(rows[0]["pnl_pct"], json!(-2.0));
+        assert_eq!(rows[0]["day_change"], json!(1000.0));
+        assert_eq!(rows[0]["pct_chg"], json!(0.68));
+
+        assert_eq!(rows[1]["market_value"], json!(334800.0));
+        assert_eq!(rows[1]["pnl"], json!(19800.0));
+        assert_eq!(rows[1]["weight"], json!(69.49));
+        assert_eq!(rows[1]["day_change"], json!(1800.0));
+
+        // 没有行情的持仓只输出持仓信息
+        assert_eq!(rows[2]["market_value"], json!(null));
+        assert_eq!(rows[2]["weight"], json!(null));
+
+        // 估值日没有新行情（停牌、休市）时市值取最近价格，当日涨跌为 0
+        let rows = valuation(&holdings, &quotes, &rates, "20240701");
+        assert_eq!(rows[0]["market_value"], json!(147000.0));
+        assert_eq!(rows[0]["day_change"], json!(0.0));
+        assert_eq!(rows[0]["pct_chg"], json!(0.0));
+    }
+}
@@ -0,0 +1,369 @@
+//! 选股命令
+//!
+//! `tushare screen --date last-trade-day --where "pe_ttm < 15 and roe > 15"` 组装全市场快照：
+//! 当日 `daily_basic`、`stock_basic` 的名称和行业，以及表达式用到财务指标时每只股票
+//! 截至当日已公告的最新一期 `fina_indicator_vip`，按 `ts_code` 关联后过滤、排序并编号。
+//! 快照按日期缓存在 `~/.tushare/cache/screen/<日期>/`。
+
+use crate::api::{find_api_by_name, load_api_definitions, ApiDefinition, ApiOutputField};
+use crate::calendar::{format_date, load_calendar, parse_date, DEFAULT_EXCHANGE};
+use crate::cli::args::ParsedArgs;
+use crate::cli::commands::{create_client, request_api};
+use crate::cli::join::{join_tables, joined_definition, JoinKind, JoinTable};
+use crate::client::{Row, TushareClient};
+use crate::config::get_cache_dir;
+use crate::error::{TushareError, TResult};
+use crate::output::{get_fields, output_data};
+use crate::query::expr::Expr;
+use crate::query::{field_text, parse_sort};
+use chrono::{Datelike, Duration, NaiveDate};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 默认按总市值降序排名
+const DEFAULT_SORT: &str = "-total_mv";
+
+/// 默认输出的基础字段
+const BASE_COLUMNS: [&str; 5] = ["rank", "ts_code", "name", "industry", "close"];
+
+/// 查找最新财务指标时回溯的报告期数量
+const FINA_PERIODS: usize = 3;
+
+/// 股票基础信息中需要的字段
+const STOCK_FIELDS: &str = "ts_code,symbol,name,area,industry,market,list_date";
+
+/// 处理 screen 命令
+pub async fn run_screen(args: &ParsedArgs) -> TResult<()> {
+    let definitions = load_api_definitions();
+    let client = create_client(args)?;
+
+    let mut options = args.options.clone();
+    if options.sort.is_none() && options.group_by.is_none() && options.agg.is_none() {
+        options.sort = Some(DEFAULT_SORT.to_string());
+    }
+    let referenced = referenced_fields(&options)?;
+
+    // 确定交易日，最近交易日的数据尚未更新时使用上一交易日
+    let auto = args.get_param("date").map(|v| v.to_string()).is_none_or(|v| v == "last-trade-day");
+    let mut date = match args.get_param("date") {
+        Some(v) if !auto => parse_date(&v.to_string())?,
+        _ => last_trade_day(&client).await?,
+    };
+    let cache_dir = get_cache_dir().join("screen");
+
+    let mut basic = snapshot(&cache_dir, date, "daily_basic", || {
+        request_api(&client, "daily_basic", serde_json::json!({ "trade_date": format_date(date) }))
+    })
+    .await?;
+    if basic.is_empty() && auto {
+        let previous = date - Duration::days(1);
+        date = load_calendar(&client, DEFAULT_EXCHANGE, previous - Duration::days(30), previous)
+            .await?
+            .prev(date, 1)
+            .ok_or_else(|| TushareError::ValidationError("未找到上一交易日".to_string()))?;
+        eprintln!("最近交易日的 daily_basic 尚未更新，使用 {}", format_date(date));
+        basic = snapshot(&cache_dir, date, "daily_basic", || {
+            request_api(&client, "daily_basic", serde_json::json!({ "trade_date": format_date(date) }))
+        })
+        .await?;
+    }
+    if basic.is_empty() {
+        return Err(TushareError::ValidationError(format!("{} 没有 daily_basic 数据", format_date(date))));
+    }
+
+    let stocks = snapshot(&cache_dir, date, "stock_basic", || {
+        client.call("stock_basic", Some(serde_json::json!({ "list_status": "L" })), Some(STOCK_FIELDS.to_string()))
+    })
+    .await?;
+
+    let basic_api = find_api_by_name(&definitions, "daily_basic");
+    let stock_api = find_api_by_name(&definitions, "stock_basic");
+    let fina_api = find_api_by_name(&definitions, "fina_indicator");
+
+    let mut tables = vec![table("daily_basic", basic, basic_api), table("stock_basic", stocks, stock_api)];
+    let mut apis = vec![basic_api, stock_api];
+
+    // 只有用到财务指标时才拉取
+    let known: Vec<String> = tables.iter().flat_map(|t| t.fields.clone()).collect();
+    let needs_fina = fina_api.is_some_and(|api| {
+        referenced
+            .iter()
+            .any(|f| !known.contains(f) && api.output_fields.iter().any(|o| &o.name == f))
+    });
+    if let (true, Some(api)) = (needs_fina, fina_api) {
+        let fina = latest_fina(&client, &cache_dir, date, api).await?;
+        tables.push(table("fina_indicator", fina, Some(api)));
+        apis.push(Some(api));
+    }
+    eprintln!(
+        "{}: {}",
+        format_date(date),
+        tables.iter().map(|t| format!("{} {} 行", t.name, t.rows.len())).collect::<Vec<_>>().join("，")
+    );
+
+    let (rows, renames) = join_tables(&tables, &["ts_code".to_string()], JoinKind::Left)?;
+    let definition = joined_definition(&tables, &apis, &renames);
+    let params = args.params_to_json();
+
+    // 分组聚合时直接输出聚合结果
+    if options.group_by.is_some() || options.agg.is_some() {
+        return output_data(&rows, &options, Some(&definition), Some(&params));
+    }
+
+    let mut filter = options.clone();
+    filter.select = None;
+    let result = crate::query::apply(&rows, &filter, Some(&definition))?;
+    let ranked = rank_rows(result.rows);
+
+    options.where_clause = None;
+    options.sort = None;
+    options.select = Some(match &options.select {
+        Some(select) if select.iter().any(|f| f == "rank") => select.clone(),
+        Some(select) => std::iter::once("rank".to_string()).chain(select.iter().cloned()).collect(),
+        None => default_columns(&referenced),
+    });
+    output_data(&ranked, &options, Some(&ranked_definition(definition)), Some(&params))
+}
+
+/// 表达式、排序和投影中引用的字段
+fn referenced_fields(options: &crate::cli::args::Options) -> TResult<Vec<String>> {
+    let mut fields: Vec<String> = Vec::new();
+    if let Some(clause) = &options.where_clause {
+        fields.extend(Expr::parse(clause)?.fields().into_iter().map(String::from));
+    }
+    if let Some(sort) = &options.sort {
+        fields.extend(parse_sort(sort)?.into_iter().map(|k| k.field));
+    }
+    for list in [&options.select, &options.group_by].into_iter().flatten() {
+        fields.extend(list.iter().cloned());
+    }
+    let mut seen = Vec::new();
+    fields.retain(|f| {
+        let new = !seen.contains(f);
+        seen.push(f.clone());
+        new
+    });
+    Ok(fields)
+}
+
+/// 默认输出列：基础字段，加上表达式和排序中引用的字段
+fn default_columns(referenced: &[String]) -> Vec<String> {
+    let mut columns: Vec<String> = BASE_COLUMNS.iter().map(|s| s.to_string()).collect();
+    for field in referenced {
+        if !columns.contains(field) {
+            columns.push(field.clone());
+        }
+    }
+    columns
+}
+
+/// 按当前顺序编号
+fn rank_rows(rows: Vec<Row>) -> Vec<Row> {
+    rows.into_iter()
+        .enumerate()
+        .map(|(i, mut row)| {
+            row.insert("rank".to_string(), serde_json::json!(i + 1));
+            row
+        })
+        .collect()
+}
+
+/// 在关联后的定义前加上排名字段
+fn ranked_definition(mut definition: ApiDefinition) -> ApiDefinition {
+    definition.output_fields.insert(0, ApiOutputField::new("rank", "int", "排名"));
+    definition
+}
+
+fn table(name: &str, rows: Vec<Row>, api: Option<&ApiDefinition>) -> JoinTable {
+    let mut fields = get_fields(&rows);
+    if fields.is_empty() {
+        fields = api.map(|a| a.output_fields.iter().map(|f| f.name.clone()).collect()).unwrap_or_default();
+    }
+    JoinTable { name: name.to_string(), rows, fields }
+}
+
+/// 最近的交易日（含今天）
+async fn last_trade_day(client: &TushareClient) -> TResult<NaiveDate> {
+    let today = chrono::Local::now().date_naive();
+    let calendar = load_calendar(client, DEFAULT_EXCHANGE, today - Duration::days(30), today).await?;
+    match calendar.is_open(today) {
+        Some(true) => Some(today),
+        _ => calendar.prev(today, 1),
+    }
+    .ok_or_else(|| TushareError::ValidationError("未找到最近的交易日".to_string()))
+}
+
+/// 读取当日快照缓存，没有时调用接口并写入缓存（空结果不缓存）
+async fn snapshot<F, Fut>(cache_dir: &Path, date: NaiveDate, name: &str, fetch: F) -> TResult<Vec<Row>>
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = TResult<crate::client::TushareResponse>>,
+{
+    let path = snapshot_path(cache_dir, date, name);
+    if let Some(rows) = read_snapshot(&path) {
+        return Ok(rows);
+    }
+    let rows = fetch().await?.to_objects();
+    if !rows.is_empty() {
+        let _ = write_snapshot(&path, &rows);
+    }
+    Ok(rows)
+}
+
+/// 截至当日已公告的最新一期财务指标，每只股票一行
+async fn latest_fina(
+    client: &TushareClient,
+    cache_dir: &Path,
+    date: NaiveDate,
+    api: &ApiDefinition,
+) -> TResult<Vec<Row>> {
+    let path = snapshot_path(cache_dir, date, "fina_indicator");
+    if let Some(rows) = read_snapshot(&path) {
+        return Ok(rows);
+    }
+
+    let fields: Vec<&str> = api.output_fields.iter().map(|f| f.name.as_str()).collect();
+    let mut reports = Vec::new();
+    for period in report_periods(date, FINA_PERIODS) {
+        let response = client
+            .call(
+                "fina_indicator_vip",
+                Some(serde_json::json!({ "period": format_date(period) })),
+                Some(fields.join(",")),
+            )
+            .await?;
+        reports.extend(response.to_objects());
+    }
+
+    let rows = latest_per_code(&reports, &format_date(date));
+    if !rows.is_empty() {
+        let _ = write_snapshot(&path, &rows);
+    }
+    Ok(rows)
+}
+
+/// 当日之前最近的若干个报告期（季末），从新到旧
+pub fn report_periods(date: NaiveDate, count: usize) -> Vec<NaiveDate> {
+    const QUARTER_ENDS: [(u32, u32); 4] = [(12, 31), (9, 30), (6, 30), (3, 31)];
+    let mut periods = Vec::new();
+    let mut year = date.year();
+    while periods.len() < count {
+        for (month, day) in QUARTER_ENDS {
+            match NaiveDate::from_ymd_opt(year, month, day) {
+                // 当日所在季度尚未结束
+                Some(end) if end < date && periods.len() < count => periods.push(end),
+                _ => {}
+            }
+        }
+        year -= 1;
+    }
+    periods
+}
+
+/// 每只股票保留公告日期不晚于当日的最新一期报告（同期取更正后的报告）
+pub fn latest_per_code(reports: &[Row], date: &str) -> Vec<Row> {
+    let rank = |row: &Row| (field_text(row, "end_date"), field_text(row, "update_flag"), field_text(row, "ann_date"));
+
+    let mut latest: HashMap<String, &Row> = HashMap::new();
+    for row in reports {
+        let ann_date = field_text(row, "ann_date");
+        if ann_date.is_empty() || ann_date.as_str() > date {
+            continue;
+        }
+        let code = field_text(row, "ts_code");
+        match latest.get(&code) {
+            Some(current) if rank(current) >= rank(row) => {}
+            _ => {
+                latest.insert(code, row);
+            }
+        }
+    }
+
+    let mut rows: Vec<Row> = latest.into_values().cloned().collect();
+    rows.sort_by_key(|row| field_text(row, "ts_code"));
+    rows
+}
+
+fn snapshot_path(cache_dir: &Path, date: NaiveDate, name: &str) -> PathBuf {
+    cache_dir.join(format_date(date)).join(format!("{}.json", name))
+}
+
+fn read_snapshot(path: &Path) -> Option<Vec<Row>> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+fn write_snapshot(path: &Path, rows: &[Row]) -> TResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string(rows)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn date(s: &str) -> NaiveDate {
+        parse_date(s).unwrap()
+    }
+
+    fn report(code: &str, end_date: &str, ann_date: &str, roe: f64) -> Row {
+        [
+            ("ts_code", json!(code)),
+            ("end_date", json!(end_date)),
+            ("ann_date", json!(ann_date)),
+            ("roe", json!(roe)),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect()
+    }
+
+    #[test]
+    fn test_report_periods() {
+        assert_eq!(
+            report_periods(date("20240610"), 3),
+            vec![date("20240331"), date("20231231"), date("20230930")]
+        );
+        assert_eq!(report_periods(date("20240331"), 1), vec![date("20231231")]);
+        assert_eq!(report_periods(date("20241001"), 2), vec![date("20240930"), date("20240630")]);
+    }
+
+    #[test]
+    fn test_latest_per_code() {
+        let reports = vec![
+            report("600519.SH", "20231231", "20240403", 34.2),
+            report("600519.SH", "20240331", "20240427", 11.6),
+            report("000001.SZ", "20231231", "20240315", 10.5),
+            report("000001.SZ", "20240331", "20240420", 3.0),
+        ];
+        // 20240420 时茅台的一季报尚未公告
+        let rows = latest_per_code(&reports, "20240420");
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["ts_code"], json!("000001.SZ"));
+        assert_eq!(rows[0]["end_date"], json!("20240331"));
+        assert_eq!(rows[1]["end_date"], json!("20231231"));
+    }
+
+    #[test]
+    fn test_default_columns_and_rank() {
+        let columns = default_columns(&["pe_ttm".to_string(), "close".to_string(), "roe".to_string()]);
+        assert_eq!(columns, ["rank", "ts_code", "name", "industry", "close", "pe_ttm", "roe"]);
+
+        let rows = rank_rows(vec![report("A", "", "", 1.0), report("B", "", "", 2.0)]);
+        assert_eq!(rows[1]["rank"], json!(2));
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let dir = std::env::temp_dir().join(format!("tushare-screen-{}", std::process::id()));
+        let path = snapshot_path(&dir, date("20240102"), "daily_basic");
+        let rows = vec![report("600519.SH", "20231231", "20240403", 34.2)];
+        write_snapshot(&path, &rows).unwrap();
+        assert_eq!(read_snapshot(&path), Some(rows));
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,163 @@
+//! 内嵌 SQL 查询命令
+//!
+//! `tushare query "<SQL>" --from daily:ts_code=000001.SZ,start_date=20240101` 调用
+//! `--from` 指定的接口，将结果按 `output_fields` 的类型载入内存 SQLite 数据表
+//! （表名默认为接口名），再执行任意 SQL。需启用 `sql` 特性。
+
+use crate::cli::args::ParsedArgs;
+use crate::error::{TushareError, TResult};
+
+/// `--from` 指定的数据源
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableSource {
+    /// 表名
+    pub table: String,
+    /// API 名称
+    pub api_name: String,
+    /// 请求参数
+    pub params: serde_json::Map<String, serde_json::Value>,
+}
+
+impl TableSource {
+    /// 解析数据源，格式为 `[表名=]接口名[:参数=值,参数=值]`
+    pub fn parse(spec: &str) -> TResult<Self> {
+        let error = || {
+            TushareError::ParseError(format!(
+                "无效的数据源: {} (格式为 [表名=]接口名[:参数=值,...]，如 daily:ts_code=000001.SZ)",
+                spec
+            ))
+        };
+
+        let (head, params) = match spec.split_once(':') {
+            Some((head, params)) => (head.trim(), params),
+            None => (spec.trim(), ""),
+        };
+        let (table, api_name) = match head.split_once('=') {
+            Some((table, api)) => (table.trim(), api.trim()),
+            None => (head, head),
+        };
+        let valid = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid(table) || !valid(api_name) {
+            return Err(error());
+        }
+
+        let mut map = serde_json::Map::new();
+        for pair in params.split(',').map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').ok_or_else(error)?;
+            // 参数值保留为字符串，避免代码的前导零丢失
+            map.insert(key.trim().replace('-', "_"), serde_json::Value::String(value.trim().to_string()));
+        }
+
+        Ok(Self {
+            table: table.to_string(),
+            api_name: api_name.to_string(),
+            params: map,
+        })
+    }
+}
+
+/// 处理 query 命令
+pub async fn run_query(args: &ParsedArgs) -> TResult<()> {
+    #[cfg(feature = "sql")]
+    {
+        query_with_sqlite(args).await
+    }
+
+    #[cfg(not(feature = "sql"))]
+    {
+        let _ = args;
+        Err(TushareError::ValidationError(
+            "query 命令需要启用 sql 特性，请使用 `cargo install tushare --features sql` 重新安装".to_string(),
+        ))
+    }
+}
+
+/// 载入数据源并执行查询
+#[cfg(feature = "sql")]
+async fn query_with_sqlite(args: &ParsedArgs) -> TResult<()> {
+    use crate::api::{find_api_by_name, load_api_definitions};
+    use crate::cli::commands::{create_client, request_api};
+    use crate::output::output_data;
+    use crate::store::SqliteStore;
+    use std::collections::HashMap;
+
+    let sql = args.positional.first().ok_or_else(|| {
+        TushareError::ParseError(
+            "请指定 SQL，例如: tushare query \"SELECT * FROM daily\" --from daily:ts_code=000001.SZ".to_string(),
+        )
+    })?;
+
+    let sources = args
+        .params
+        .iter()
+        .filter(|(key, _)| key == "from")
+        .map(|(_, value)| TableSource::parse(&value.to_string()))
+        .collect::<TResult<Vec<_>>>()?;
+    if sources.is_empty() {
+        return Err(TushareError::ParseError(
+            "请使用 --from <接口名:参数=值,...> 指定要载入的数据".to_string(),
+        ));
+    }
+
+    let definitions = load_api_definitions();
+    let client = create_client(args)?;
+    let mut store = SqliteStore::open_in_memory()?;
+
+    for source in &sources {
+        let field_types: HashMap<String, String> = find_api_by_name(&definitions, &source.api_name)
+            .map(|api| {
+                api.output_fields
+                    .iter()
+                    .map(|f| (f.name.clone(), f.field_type.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let response = request_api(
+            &client,
+            &source.api_name,
+            serde_json::Value::Object(source.params.clone()),
+        )
+        .await?;
+        let rows = match &response.data {
+            Some(data) => store.write_rows(&source.table, &data.fields, &data.items, &field_types, None)?,
+            None => 0,
+        };
+        eprintln!("{}: {} 行", source.table, rows);
+    }
+
+    let (columns, rows) = store.query(sql)?;
+
+    // 保持 SQL 中的列顺序
+    let mut options = args.options.clone();
+    if options.select.is_none() {
+        options.select = Some(columns);
+    }
+    output_data(&rows, &options, None, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_table_source() {
+        let source = TableSource::parse("daily:ts_code=000001.SZ,start_date=20240101").unwrap();
+        assert_eq!(source.table, "daily");
+        assert_eq!(source.api_name, "daily");
+        assert_eq!(source.params["ts_code"], "000001.SZ");
+        assert_eq!(source.params["start_date"], "20240101");
+
+        let source = TableSource::parse("b=daily_basic:symbol=000001").unwrap();
+        assert_eq!(source.table, "b");
+        assert_eq!(source.api_name, "daily_basic");
+        assert_eq!(source.params["symbol"], "000001");
+
+        let source = TableSource::parse("stock_basic").unwrap();
+        assert!(source.params.is_empty());
+
+        assert!(TableSource::parse("").is_err());
+        assert!(TableSource::parse("daily:ts_code").is_err());
+        assert!(TableSource::parse("my table=daily").is_err());
+    }
+}
@@ -0,0 +1,331 @@
+//! 财务报表透视命令
+//!
+//! `tushare statement --ts-code 600519.SH --type income --periods 8` 获取财务报表，
+//! 同一报告期有更正报告时保留最新的一份（`update_flag` 最大、公告日期最晚），
+//! 再转置为 科目 × 报告期 的表格，科目名称取自字段说明。
+
+use crate::api::{find_api_by_name, load_api_definitions, ApiDefinition, ApiOutputField};
+use crate::cli::args::ParsedArgs;
+use crate::cli::commands::create_client;
+use crate::client::Row;
+use crate::error::{TushareError, TResult};
+use crate::output::{format_value, output_data};
+use crate::query::field_text;
+use std::collections::{BTreeSet, HashMap};
+
+/// 默认报告期数量
+const DEFAULT_PERIODS: usize = 8;
+
+/// 报表的标识字段，不作为科目输出
+const META_FIELDS: [&str; 8] = [
+    "ts_code",
+    "ann_date",
+    "f_ann_date",
+    "end_date",
+    "report_type",
+    "comp_type",
+    "end_type",
+    "update_flag",
+];
+
+/// 报表类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatementKind {
+    /// 利润表
+    Income,
+    /// 资产负债表
+    Balance,
+    /// 现金流量表
+    Cashflow,
+    /// 财务指标
+    Indicator,
+}
+
+impl StatementKind {
+    /// 从字符串解析报表类型
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "income" => Some(StatementKind::Income),
+            "balance" | "balancesheet" => Some(StatementKind::Balance),
+            "cashflow" => Some(StatementKind::Cashflow),
+            "indicator" | "fina_indicator" => Some(StatementKind::Indicator),
+            _ => None,
+        }
+    }
+
+    /// 对应的接口
+    pub fn api_name(&self) -> &'static str {
+        match self {
+            StatementKind::Income => "income",
+            StatementKind::Balance => "balancesheet",
+            StatementKind::Cashflow => "cashflow",
+            StatementKind::Indicator => "fina_indicator",
+        }
+    }
+}
+
+/// 处理 statement 命令
+///
+/// `codes` 为 `--ts-code @名称` 展开后的代码，多个代码时逐个透视后合并。
+pub async fn run_statement(args: &ParsedArgs, codes: Option<Vec<String>>) -> TResult<()> {
+    if args.get_param("ts_code").is_none() {
+        return Err(TushareError::ParseError(
+            "请指定 --ts-code，例如: tushare statement --ts-code 600519.SH --type income".to_string(),
+        ));
+    }
+    let kind = match args.get_param("type") {
+        Some(v) => StatementKind::from_str(&v.to_string()).ok_or_else(|| {
+            TushareError::ParseError(format!("无效的报表类型: {} (可选 income|balance|cashflow|indicator)", v))
+        })?,
+        None => StatementKind::Income,
+    };
+    let periods = match args.get_param("periods") {
+        Some(v) => v
+            .to_string()
+            .parse()
+            .ok()
+            .filter(|n| *n > 0)
+            .ok_or_else(|| TushareError::ParseError(format!("--periods 需要正整数: {}", v)))?,
+        None => DEFAULT_PERIODS,
+    };
+
+    let definitions = load_api_definitions();
+    let api = find_api_by_name(&definitions, kind.api_name())
+        .ok_or_else(|| TushareError::ApiNotFound(kind.api_name().to_string()))?;
+
+    // 只传接口接受的参数，参数值保留为字符串
+    let params: serde_json::Map<String, serde_json::Value> = args
+        .params
+        .iter()
+        .filter(|(key, _)| api.parameters.iter().any(|p| &p.name == key))
+        .map(|(key, value)| (key.clone(), serde_json::Value::String(value.to_string())))
+        .collect();
+    // 默认只返回部分字段，需要显式请求全部科目
+    let fields: Vec<&str> = api.output_fields.iter().map(|f| f.name.as_str()).collect();
+    let fields = fields.join(",");
+
+    let client = create_client(args)?;
+    let (rows, definition) = match codes {
+        Some(codes) if codes.len() > 1 => {
+            let mut pivots = Vec::new();
+            for (i, code) in codes.iter().enumerate() {
+                let mut params = params.clone();
+                params.insert("ts_code".to_string(), serde_json::json!(code));
+                let response = client
+                    .call(api.name.as_str(), Some(serde_json::Value::Object(params)), Some(fields.clone()))
+                    .await?;
+                let reports = latest_reports(&response.to_objects(), periods);
+                eprintln!("[{}/{}] {}: {} 期", i + 1, codes.len(), code, reports.len());
+                pivots.push((code.clone(), pivot_statement(&reports, api)));
+            }
+            merge_pivots(pivots, api)
+        }
+        _ => {
+            let response = client
+                .call(api.name.as_str(), Some(serde_json::Value::Object(params)), Some(fields))
+                .await?;
+            pivot_statement(&latest_reports(&response.to_objects(), periods), api)
+        }
+    };
+    output_data(&rows, &args.options, Some(&definition), Some(&args.params_to_json()))
+}
+
+/// 每个报告期保留最新的一份报告，按报告期倒序取前 `periods` 期
+pub fn latest_reports(
+    rows: &[HashMap<String, serde_json::Value>],
+    periods: usize,
+) -> Vec<HashMap<String, serde_json::Value>> {
+    // 更正后的报告 update_flag 为 1，其次比较实际公告日期
+    let rank = |row: &HashMap<String, serde_json::Value>| {
+        (field_text(row, "update_flag"), field_text(row, "f_ann_date"), field_text(row, "ann_date"))
+    };
+
+    let mut latest: HashMap<String, &HashMap<String, serde_json::Value>> = HashMap::new();
+    for row in rows {
+        let period = field_text(row, "end_date");
+        if period.is_empty() {
+            continue;
+        }
+        match latest.get(&period) {
+            Some(current) if rank(current) >= rank(row) => {}
+            _ => {
+                latest.insert(period, row);
+            }
+        }
+    }
+
+    let mut reports: Vec<HashMap<String, serde_json::Value>> = latest.into_values().cloned().collect();
+    reports.sort_by_key(|row| std::cmp::Reverse(field_text(row, "end_date")));
+    reports.truncate(periods);
+    reports
+}
+
+/// 转置为 科目 × 报告期，跳过所有报告期都为空的科目
+///
+/// 每行包含 `item`（字段名）、`label`（科目名称）和各报告期的数值，返回的字段定义
+/// 决定列的顺序（报告期从新到旧）。
+pub fn pivot_statement(
+    reports: &[HashMap<String, serde_json::Value>],
+    api: &ApiDefinition,
+) -> (Vec<HashMap<String, serde_json::Value>>, ApiDefinition) {
+    let periods: Vec<String> = reports
+        .iter()
+        .map(|r| r.get("end_date").map(format_value).unwrap_or_default())
+        .collect();
+
+    let mut rows = Vec::new();
+    for field in api.output_fields.iter().filter(|f| !META_FIELDS.contains(&f.name.as_str())) {
+        let values: Vec<serde_json::Value> = reports
+            .iter()
+            .map(|r| r.get(&field.name).cloned().unwrap_or_default())
+            .collect();
+        if values.iter().all(|v| v.is_null()) {
+            continue;
+        }
+
+        let mut row = HashMap::new();
+        row.insert("item".to_string(), serde_json::json!(field.name));
+        row.insert("label".to_string(), serde_json::json!(field.description.trim()));
+        for (period, value) in periods.iter().zip(values) {
+            row.insert(period.clone(), value);
+        }
+        rows.push(row);
+    }
+
+    let output_fields = [ApiOutputField::new("item", "str", "字段"), ApiOutputField::new("label", "str", "项目")]
+        .into_iter()
+        .chain(periods.iter().map(|p| ApiOutputField::new(p, "float", p)))
+        .collect();
+    (rows, ApiDefinition::derived(Some(api), output_fields))
+}
+
+/// 合并多个代码的透视结果
+///
+/// 每行加上 `ts_code`，列为 `ts_code`、`item`、`label` 和各代码报告期的并集（从新到旧）。
+pub fn merge_pivots(
+    pivots: Vec<(String, (Vec<Row>, ApiDefinition))>,
+    api: &ApiDefinition,
+) -> (Vec<Row>, ApiDefinition) {
+    let mut periods = BTreeSet::new();
+    let mut rows = Vec::new();
+    for (code, (pivot, definition)) in pivots {
+        periods.extend(definition.output_fields.into_iter().skip(2).map(|f| f.name));
+        rows.extend(pivot.into_iter().map(|mut row| {
+            row.insert("ts_code".to_string(), serde_json::json!(code));
+            row
+        }));
+    }
+
+    let (_, mut definition) = pivot_statement(&[], api);
+    definition.output_fields.insert(0, ApiOutputField::new("ts_code", "str", "TS代码"));
+    definition.output_fields.extend(periods.iter().rev().map(|p| ApiOutputField::new(p, "float", p)));
+    (rows, definition)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn report(end_date: &str, f_ann_date: &str, update_flag: &str, revenue: f64) -> HashMap<String, serde_json::Value> {
+        [
+            ("ts_code", json!("600519.SH")),
+            ("end_date", json!(end_date)),
+            ("f_ann_date", json!(f_ann_date)),
+            ("update_flag", json!(update_flag)),
+            ("total_revenue", json!(revenue)),
+            ("int_income", json!(null)),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect()
+    }
+
+    fn api() -> ApiDefinition {
+        ApiDefinition::for_test(
+            "income",
+            &[
+                ("ts_code", "str", "TS代码"),
+                ("end_date", "str", "报告期"),
+                ("total_revenue", "float", "营业总收入"),
+                ("int_income", "float", "利息收入"),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_latest_reports() {
+        let rows = vec![
+            report("20230930", "20231021", "0", 1.0),
+            report("20231231", "20240403", "0", 2.0),
+            report("20231231", "20240420", "1", 3.0),
+            report("20230630", "20230802", "1", 4.0),
+            report("20230630", "20230801", "0", 5.0),
+        ];
+        let reports = latest_reports(&rows, 2);
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0]["end_date"], json!("20231231"));
+        assert_eq!(reports[0]["total_revenue"], json!(3.0));
+        assert_eq!(reports[1]["end_date"], json!("20230930"));
+
+        let reports = latest_reports(&rows, 8);
+        assert_eq!(reports[2]["total_revenue"], json!(4.0));
+    }
+
+    #[test]
+    fn test_pivot_statement() {
+        let reports = latest_reports(&[report("20231231", "20240403", "1", 2.0), report("20230930", "20231021", "1", 1.0)], 8);
+        let (rows, definition) = pivot_statement(&reports, &api());
+
+        // 标识字段和全为空的科目不输出
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["label"], json!("营业总收入"));
+        assert_eq!(rows[0]["20231231"], json!(2.0));
+        assert_eq!(rows[0]["20230930"], json!(1.0));
+
+        let names: Vec<&str> = definition.output_fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["item", "label", "20231231", "20230930"]);
+
+        // 表格按定义的顺序输出列：科目在前，报告期从新到旧
+        let options = crate::cli::args::Options { no_color: true, ..Default::default() };
+        let rendered = crate::output::table::render_table(&rows, &options, Some(&definition), None);
+        let header = rendered.lines().nth(1).unwrap();
+        let position = |name: &str| header.find(name).unwrap();
+        assert!(position("item") < position("label"));
+        assert!(position("label") < position("20231231"));
+        assert!(position("20231231") < position("20230930"));
+    }
+
+    #[test]
+    fn test_merge_pivots() {
+        let pivot = |code: &str, periods: &[&str]| {
+            let reports: Vec<_> = periods
+                .iter()
+                .map(|p| {
+                    let mut row = report(p, p, "1", 1.0);
+                    row.insert("ts_code".to_string(), json!(code));
+                    row
+                })
+                .collect();
+            (code.to_string(), pivot_statement(&reports, &api()))
+        };
+        let (rows, definition) = merge_pivots(
+            vec![pivot("600519.SH", &["20231231", "20230930"]), pivot("000858.SZ", &["20230930", "20230630"])],
+            &api(),
+        );
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1]["ts_code"], json!("000858.SZ"));
+        assert_eq!(rows[1]["20230630"], json!(1.0));
+        let names: Vec<&str> = definition.output_fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["ts_code", "item", "label", "20231231", "20230930", "20230630"]);
+    }
+
+    #[test]
+    fn test_statement_kind() {
+        assert_eq!(StatementKind::from_str("balance").map(|k| k.api_name()), Some("balancesheet"));
+        assert_eq!(StatementKind::from_str("FINA_INDICATOR"), Some(StatementKind::Indicator));
+        assert_eq!(StatementKind::from_str("profit"), None);
+    }
+}
@@ -0,0 +1,219 @@
+//! 增量同步命令
+//!
+//! `tushare sync <接口名> --store <文件>` 读取本地存储中已有的最大
+//! `trade_date`，按交易日历（见 [`crate::calendar`]）获取之后的交易日，逐日拉取并写入存储。
+
+use crate::calendar::{format_date, parse_date};
+use crate::cli::args::ParsedArgs;
+use crate::error::{TushareError, TResult};
+
+/// 同步计划中的单次 API 调用
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncCall {
+    /// API 名称
+    pub api_name: String,
+    /// 交易日期
+    pub trade_date: String,
+}
+
+/// 处理 sync 命令
+pub async fn run_sync(args: &ParsedArgs) -> TResult<()> {
+    #[cfg(feature = "sqlite")]
+    {
+        sync_with_store(args).await
+    }
+
+    #[cfg(not(feature = "sqlite"))]
+    {
+        let _ = args;
+        Err(TushareError::ValidationError(
+            "sync 命令需要启用 sqlite 特性，请使用 `cargo install tushare --features sqlite` 重新安装"
+                .to_string(),
+        ))
+    }
+}
+
+/// 执行同步
+#[cfg(feature = "sqlite")]
+async fn sync_with_store(args: &ParsedArgs) -> TResult<()> {
+    use crate::api::{find_api_by_name, load_api_definitions};
+    use crate::calendar::{load_calendar, DEFAULT_EXCHANGE};
+    use crate::cli::commands::{create_client, request_api};
+    use crate::store::SqliteStore;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    let api_name = args
+        .positional
+        .first()
+        .ok_or_else(|| TushareError::ParseError("请指定要同步的接口，例如: tushare sync daily --store market.db".to_string()))?;
+
+    let definitions = load_api_definitions();
+    let api = find_api_by_name(&definitions, api_name)
+        .ok_or_else(|| TushareError::ApiNotFound(api_name.clone()))?;
+
+    if !api.parameters.iter().any(|p| p.name == "trade_date") {
+        return Err(TushareError::ValidationError(format!(
+            "接口 {} 不支持按 trade_date 查询，无法增量同步",
+            api_name
+        )));
+    }
+
+    let store_path = args
+        .get_param("store")
+        .map(|v| PathBuf::from(v.to_string()))
+        .ok_or_else(|| TushareError::ParseError("请使用 --store <文件> 指定本地存储".to_string()))?;
+    let mut store = SqliteStore::open(&store_path)?;
+
+    // 确定同步区间
+    let since = args.get_param("since").map(|v| v.to_string());
+    let stored = match store.max_value(api_name, "trade_date")? {
+        Some(date) => Some(date),
+        None => store.sync_state(api_name)?.map(|s| s.last_date),
+    };
+    let start_date = resolve_start_date(since.as_deref(), stored.as_deref())?;
+    let end_date = match args.get_param("until") {
+        Some(v) => {
+            let date = v.to_string();
+            parse_date(&date)?;
+            date
+        }
+        None => format_date(chrono::Local::now().date_naive()),
+    };
+
+    if start_date > end_date {
+        println!("{} 已是最新 (最后交易日: {})", api_name, stored.unwrap_or_default());
+        return Ok(());
+    }
+
+    let exchange = args
+        .get_param("exchange")
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| DEFAULT_EXCHANGE.to_string());
+
+    // 获取交易日历
+    let client = create_client(args)?;
+    let (start, end) = (parse_date(&start_date)?, parse_date(&end_date)?);
+    let calendar = load_calendar(&client, &exchange, start, end).await?;
+    let trading_days: Vec<String> = calendar.range(start, end).into_iter().map(format_date).collect();
+    let plan = build_plan(api_name, &trading_days);
+
+    if plan.is_empty() {
+        println!("{} 在 {} 至 {} 之间没有新的交易日", api_name, start_date, end_date);
+        return Ok(());
+    }
+
+    // 仅输出同步计划
+    if matches!(args.get_param("dry_run"), Some(crate::cli::args::ParamValue::Boolean(true))) {
+        println!("同步计划: {} → {} (共 {} 次调用)", api_name, store_path.display(), plan.len());
+        println!();
+        for call in &plan {
+            println!("  tushare {} --trade-date {}", call.api_name, call.trade_date);
+        }
+        return Ok(());
+    }
+
+    let field_types: HashMap<String, String> = api
+        .output_fields
+        .iter()
+        .map(|f| (f.name.clone(), f.field_type.clone()))
+        .collect();
+
+    let mut total = 0;
+    for (i, call) in plan.iter().enumerate() {
+        let response = request_api(
+            &client,
+            &call.api_name,
+            serde_json::json!({ "trade_date": call.trade_date }),
+        )
+        .await?;
+
+        let rows = match &response.data {
+            Some(data) => store.write_rows(
+                &call.api_name,
+                &data.fields,
+                &data.items,
+                &field_types,
+                Some("trade_date"),
+            )?,
+            None => 0,
+        };
+        store.record_sync(&call.api_name, &call.trade_date, rows)?;
+        total += rows;
+
+        eprintln!("[{}/{}] {} {}: {} 行", i + 1, plan.len(), call.api_name, call.trade_date, rows);
+    }
+
+    println!(
+        "同步完成: {} 共 {} 个交易日，写入 {} 行",
+        api_name,
+        plan.len(),
+        total
+    );
+
+    Ok(())
+}
+
+/// 获取下一个自然日
+pub fn next_date(date: &str) -> TResult<String> {
+    let date = parse_date(date)?;
+    let next = date
+        .succ_opt()
+        .ok_or_else(|| TushareError::ValidationError(format!("日期超出范围: {}", date)))?;
+    Ok(format_date(next))
+}
+
+/// 确定同步起始日期
+///
+/// `--since` 优先；否则从存储中最后一个交易日的下一天开始。
+pub fn resolve_start_date(since: Option<&str>, stored: Option<&str>) -> TResult<String> {
+    match (since, stored) {
+        (Some(since), _) => {
+            parse_date(since)?;
+            Ok(since.to_string())
+        }
+        (None, Some(stored)) => next_date(stored),
+        (None, None) => Err(TushareError::ValidationError(
+            "存储中没有该接口的数据，首次同步请使用 --since <YYYYMMDD> 指定起始日期".to_string(),
+        )),
+    }
+}
+
+/// 生成同步计划
+pub fn build_plan(api_name: &str, trading_days: &[String]) -> Vec<SyncCall> {
+    trading_days
+        .iter()
+        .map(|day| SyncCall {
+            api_name: api_name.to_string(),
+            trade_date: day.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_date() {
+        assert_eq!(next_date("20240131").unwrap(), "20240201");
+        assert_eq!(next_date("20231231").unwrap(), "20240101");
+        assert!(next_date("2024-01-01").is_err());
+    }
+
+    #[test]
+    fn test_resolve_start_date() {
+        assert_eq!(resolve_start_date(Some("20200101"), Some("20240102")).unwrap(), "20200101");
+        assert_eq!(resolve_start_date(None, Some("20240102")).unwrap(), "20240103");
+        assert!(resolve_start_date(None, None).is_err());
+        assert!(resolve_start_date(Some("abc"), None).is_err());
+    }
+
+    #[test]
+    fn test_build_plan() {
+        let plan = build_plan("daily", &["20240102".to_string(), "20240103".to_string()]);
+        assert_eq!(plan.len(), 2);
+        assert_eq!(plan[0].api_name, "daily");
+        assert_eq!(plan[1].trade_date, "20240103");
+    }
+}
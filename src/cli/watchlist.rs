@@ -0,0 +1,250 @@
+//! 自选列表命令
+//!
+//! ```text
+//! tushare watchlist ls                          所有列表及代码数量
+//! tushare watchlist show <名称>                 列表中的代码
+//! tushare watchlist add <名称> <代码>...        添加代码（不存在时创建列表）
+//! tushare watchlist rm <名称> [代码...]         移除代码，不指定代码时删除列表
+//! tushare watchlist import <名称> --file a.csv [--column ts_code]
+//! tushare watchlist import <名称> --api stock_basic [--where ...] [接口参数]
+//! ```
+//!
+//! `import` 默认合并到已有列表，`--replace` 覆盖。列表可在任意 `ts_code` 参数中以
+//! `@名称` 引用。
+
+use crate::api::ApiDefinition;
+use crate::cli::args::{ParamValue, ParsedArgs};
+use crate::cli::commands::{create_client, request_api};
+use crate::client::Row;
+use crate::config::watchlist::{
+    expand_codes, list_watchlists, load_watchlist, read_watchlist, references_watchlist, watchlist_path,
+    write_watchlist,
+};
+use crate::error::{TushareError, TResult};
+use crate::output::{format_value, output_data};
+use std::fs;
+
+/// import 自身的选项，不传给接口
+const IMPORT_OPTIONS: [&str; 4] = ["file", "column", "api", "replace"];
+
+/// 未指定 --column 时依次尝试的代码列
+const CODE_COLUMNS: [&str; 2] = ["ts_code", "con_code"];
+
+/// 处理 watchlist 命令
+pub async fn run_watchlist(args: &ParsedArgs) -> TResult<()> {
+    let usage = || {
+        TushareError::ParseError(
+            "用法: tushare watchlist ls | show <名称> | add <名称> <代码>... | rm <名称> [代码...] | import <名称> --file <CSV>|--api <接口>"
+                .to_string(),
+        )
+    };
+    let action = args.positional.first().ok_or_else(usage)?;
+    let name = args.positional.get(1);
+    let codes = &args.positional[args.positional.len().min(2)..];
+
+    match (action.as_str(), name) {
+        ("ls" | "list", _) => {
+            let rows = list_watchlists()?
+                .into_iter()
+                .map(|name| {
+                    let count = load_watchlist(&name).map(|c| c.len()).unwrap_or_default();
+                    row(&[("name", serde_json::json!(name)), ("count", serde_json::json!(count))])
+                })
+                .collect::<Vec<_>>();
+            let definition = definition(&[("name", "str", "名称"), ("count", "int", "代码数量")]);
+            output_data(&rows, &args.options, Some(&definition), None)
+        }
+        ("show", Some(name)) => {
+            let rows: Vec<Row> = load_watchlist(name)?
+                .into_iter()
+                .map(|code| row(&[("ts_code", serde_json::json!(code))]))
+                .collect();
+            output_data(&rows, &args.options, Some(&definition(&[("ts_code", "str", "TS代码")])), None)
+        }
+        ("add", Some(name)) if !codes.is_empty() => {
+            // 代码也可以逗号分隔或引用其它列表
+            let added = expand_codes(&codes.join(","))?;
+            update(name, added, false)
+        }
+        ("rm" | "remove", Some(name)) => {
+            let path = watchlist_path(name)?;
+            let existing = load_watchlist(name)?;
+            if codes.is_empty() {
+                fs::remove_file(&path)?;
+                println!("已删除自选列表 {}", name);
+                return Ok(());
+            }
+            let removed = expand_codes(&codes.join(","))?;
+            let remaining: Vec<String> = existing.iter().filter(|c| !removed.contains(c)).cloned().collect();
+            write_watchlist(&path, &remaining)?;
+            println!("{}: 移除 {} 个代码，剩余 {} 个", name, existing.len() - remaining.len(), remaining.len());
+            Ok(())
+        }
+        ("import", Some(name)) => {
+            let column = args.get_param("column").map(|v| v.to_string());
+            let rows = if let Some(file) = args.get_param("file") {
+                let content = fs::read_to_string(file.to_string())?;
+                parse_csv(&content)
+            } else if let Some(api) = args.get_param("api") {
+                fetch_rows(args, &api.to_string()).await?
+            } else {
+                return Err(TushareError::ParseError(
+                    "请指定 --file <CSV 文件> 或 --api <接口名>".to_string(),
+                ));
+            };
+            let imported = extract_codes(&rows, column.as_deref())?;
+            update(name, imported, args.get_param("replace").is_some())
+        }
+        _ => Err(usage()),
+    }
+}
+
+/// 展开 `ts_code` 参数中的 `@名称`，引用了列表时返回展开后的代码
+///
+/// 参数替换为逗号分隔的代码；直接调用接口和 `bar`、`statement` 按返回的代码逐个请求，其它内置命令只接受单个代码。
+pub fn expand_ts_code(args: &mut ParsedArgs) -> TResult<Option<Vec<String>>> {
+    let Some((_, value)) = args.params.iter_mut().find(|(key, _)| key == "ts_code") else {
+        return Ok(None);
+    };
+    let raw = value.to_string();
+    if !references_watchlist(&raw) {
+        return Ok(None);
+    }
+    let codes = expand_codes(&raw)?;
+    *value = ParamValue::String(codes.join(","));
+    Ok(Some(codes))
+}
+
+/// 合并或覆盖列表
+fn update(name: &str, codes: Vec<String>, replace: bool) -> TResult<()> {
+    let path = watchlist_path(name)?;
+    let mut merged = if replace { Vec::new() } else { read_watchlist(&path)?.unwrap_or_default() };
+    let before = merged.len();
+    for code in codes {
+        if !merged.contains(&code) {
+            merged.push(code);
+        }
+    }
+    write_watchlist(&path, &merged)?;
+    if replace {
+        println!("{}: 共 {} 个代码", name, merged.len());
+    } else {
+        println!("{}: 新增 {} 个代码，共 {} 个", name, merged.len() - before, merged.len());
+    }
+    Ok(())
+}
+
+/// 调用接口（接口参数照常传入），按 --where 过滤
+async fn fetch_rows(args: &ParsedArgs, api: &str) -> TResult<Vec<Row>> {
+    let mut params = args.params_to_json();
+    if let Some(map) = params.as_object_mut() {
+        map.retain(|key, _| !IMPORT_OPTIONS.contains(&key.as_str()));
+    }
+    let client = create_client(args)?;
+    let rows = request_api(&client, api, params).await?.to_objects();
+
+    let filter = crate::cli::args::Options {
+        where_clause: args.options.where_clause.clone(),
+        ..Default::default()
+    };
+    Ok(crate::query::apply(&rows, &filter, None)?.rows)
+}
+
+/// 取出代码列（去重并保持顺序），未指定时使用 ts_code 或 con_code
+pub fn extract_codes(rows: &[Row], column: Option<&str>) -> TResult<Vec<String>> {
+    let column = match column {
+        Some(c) => c.to_string(),
+        None => CODE_COLUMNS
+            .iter()
+            .find(|c| rows.iter().any(|r| r.contains_key(**c)))
+            .map(|c| c.to_string())
+            .ok_or_else(|| TushareError::ValidationError("未找到代码列，请用 --column 指定".to_string()))?,
+    };
+    if !rows.is_empty() && !rows.iter().any(|r| r.contains_key(&column)) {
+        return Err(TushareError::ValidationError(format!("没有 {} 列", column)));
+    }
+
+    let mut codes: Vec<String> = Vec::new();
+    for value in rows.iter().filter_map(|r| r.get(&column)) {
+        let code = format_value(value).trim().to_string();
+        if !code.is_empty() && !codes.contains(&code) {
+            codes.push(code);
+        }
+    }
+    Ok(codes)
+}
+
+/// 解析带表头的 CSV（支持双引号包裹的字段），所有值作为字符串
+pub fn parse_csv(content: &str) -> Vec<Row> {
+    let mut lines = content.trim_start_matches('\u{feff}').lines().filter(|l| !l.trim().is_empty());
+    let Some(header) = lines.next() else {
+        return Vec::new();
+    };
+    let header = split_csv_line(header);
+    lines
+        .map(|line| {
+            header
+                .iter()
+                .cloned()
+                .zip(split_csv_line(line).into_iter().map(serde_json::Value::String))
+                .collect()
+        })
+        .collect()
+}
+
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+fn row(values: &[(&str, serde_json::Value)]) -> Row {
+    values.iter().map(|(k, v)| (k.to_string(), v.clone())).collect()
+}
+
+fn definition(columns: &[(&str, &str, &str)]) -> ApiDefinition {
+    ApiDefinition::synthetic("watchlist", "自选列表", columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::rows_from_json;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_csv() {
+        let rows = parse_csv("\u{feff}ts_code,name\n000001.SZ,平安银行\n\"600036.SH\",\"招商银行, \"\"招行\"\"\"\n\n");
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["ts_code"], json!("000001.SZ"));
+        assert_eq!(rows[1]["name"], json!("招商银行, \"招行\""));
+        assert!(parse_csv("").is_empty());
+    }
+
+    #[test]
+    fn test_extract_codes() {
+        let rows = rows_from_json(json!([
+            {"index_code": "000300.SH", "con_code": "600519.SH"},
+            {"index_code": "000300.SH", "con_code": "000001.SZ"},
+            {"index_code": "000300.SH", "con_code": "600519.SH"},
+        ]));
+        assert_eq!(extract_codes(&rows, None).unwrap(), ["600519.SH", "000001.SZ"]);
+        assert_eq!(extract_codes(&rows, Some("index_code")).unwrap(), ["000300.SH"]);
+        assert!(extract_codes(&rows, Some("symbol")).is_err());
+        assert!(extract_codes(&parse_csv("code\n1"), None).is_err());
+    }
+}
@@ -154,7 +154,6 @@ mod tests {
 
     #[test]
     fn test_request_building() {
-        let client = TushareClient::with_token("test_token".to_string()).unwrap();
         let request = TushareRequest::new("stock_basic".to_string(), "test_token".to_string());
 
         assert_eq!(request.api_name, "stock_basic");
@@ -4,8 +4,11 @@
 
 pub mod request;
 pub mod response;
+#[allow(clippy::module_inception)]
 pub mod client;
 
 pub use client::TushareClient;
 pub use request::TushareRequest;
-pub use response::{TushareResponse, ResponseData, TushareErrorResponse};
+pub use response::{TushareResponse, ResponseData, TushareErrorResponse, Row};
+#[cfg(test)]
+pub use response::rows_from_json;
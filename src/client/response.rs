@@ -3,6 +3,15 @@
 use serde::{Deserialize, Deserializer};
 use std::collections::HashMap;
 
+/// 一行数据（字段名 → 值）
+pub type Row = HashMap<String, serde_json::Value>;
+
+/// 测试用：由 JSON 对象数组构造数据行
+#[cfg(test)]
+pub fn rows_from_json(value: serde_json::Value) -> Vec<Row> {
+    serde_json::from_value(value).unwrap()
+}
+
 /// Tushare API 响应
 #[derive(Debug, Clone, Deserialize)]
 pub struct TushareResponse {
@@ -93,7 +102,7 @@ impl TushareResponse {
     }
 
     /// 将二维数组转换为对象数组
-    pub fn to_objects(&self) -> Vec<HashMap<String, serde_json::Value>> {
+    pub fn to_objects(&self) -> Vec<Row> {
         let mut result = Vec::new();
 
         if let Some(ref data) = self.data {
@@ -0,0 +1,20 @@
+//! 本地缓存目录
+
+use std::env;
+use std::path::PathBuf;
+
+/// 获取缓存目录
+pub fn get_cache_dir() -> PathBuf {
+    // 优先使用环境变量指定的路径
+    if let Ok(path) = env::var("TUSHARE_CACHE_DIR") {
+        return PathBuf::from(path);
+    }
+
+    // 使用用户主目录下的缓存目录
+    let mut path = env::var("HOME")
+        .or_else(|_| env::var("USERPROFILE"))
+        .unwrap_or_else(|_| ".".to_string());
+    path.push_str("/.tushare/cache");
+
+    PathBuf::from(path)
+}
@@ -1,5 +1,8 @@
 //! 配置模块
 
 pub mod token;
+pub mod cache;
+pub mod watchlist;
 
 pub use token::{load_token, save_token, get_token_path};
+pub use cache::get_cache_dir;
@@ -0,0 +1,161 @@
+//! 自选列表
+//!
+//! 每个列表一个文本文件（`~/.tushare/watchlists/<名称>.txt`，与 Token 配置文件同目录），
+//! 每行一个代码，`#` 开头的行为注释。参数中的 `@名称` 会展开为列表中的代码。
+
+use crate::config::get_token_path;
+use crate::error::{TushareError, TResult};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 列表引用前缀（如 `--ts-code @banks`）
+pub const WATCHLIST_PREFIX: char = '@';
+
+/// 获取自选列表目录
+pub fn get_watchlist_dir() -> PathBuf {
+    get_token_path()
+        .parent()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("watchlists")
+}
+
+/// 列表名称对应的文件路径
+pub fn watchlist_path(name: &str) -> TResult<PathBuf> {
+    validate_name(name)?;
+    Ok(get_watchlist_dir().join(format!("{}.txt", name)))
+}
+
+/// 列表名称不能为空，不能包含路径分隔符、空白、逗号或 `@`
+pub fn validate_name(name: &str) -> TResult<()> {
+    let invalid = name.is_empty()
+        || name.starts_with('.')
+        || name
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '/' | '\\' | ',' | WATCHLIST_PREFIX));
+    if invalid {
+        return Err(TushareError::ValidationError(format!("无效的列表名称: {}", name)));
+    }
+    Ok(())
+}
+
+/// 读取列表文件，文件不存在时为 None
+pub fn read_watchlist(path: &Path) -> TResult<Option<Vec<String>>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let codes = fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect();
+    Ok(Some(codes))
+}
+
+/// 写入列表文件
+pub fn write_watchlist(path: &Path, codes: &[String]) -> TResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut content = codes.join("\n");
+    content.push('\n');
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// 加载指定名称的列表，不存在时报错
+pub fn load_watchlist(name: &str) -> TResult<Vec<String>> {
+    read_watchlist(&watchlist_path(name)?)?.ok_or_else(|| {
+        TushareError::ValidationError(format!("自选列表 {} 不存在 (使用 tushare watchlist ls 查看)", name))
+    })
+}
+
+/// 所有列表的名称（按名称排序）
+pub fn list_watchlists() -> TResult<Vec<String>> {
+    let dir = get_watchlist_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut names: Vec<String> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            (path.extension()? == "txt").then(|| path.file_stem()?.to_str().map(String::from))?
+        })
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// 参数值中是否引用了列表
+pub fn references_watchlist(value: &str) -> bool {
+    value.split(',').any(|part| part.trim().starts_with(WATCHLIST_PREFIX))
+}
+
+/// 展开逗号分隔的代码，`@名称` 替换为列表中的代码，去重并保持顺序
+pub fn expand_codes(value: &str) -> TResult<Vec<String>> {
+    expand_with(value, load_watchlist)
+}
+
+fn expand_with(value: &str, load: impl Fn(&str) -> TResult<Vec<String>>) -> TResult<Vec<String>> {
+    let mut codes: Vec<String> = Vec::new();
+    for part in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let expanded = match part.strip_prefix(WATCHLIST_PREFIX) {
+            Some(name) => load(name)?,
+            None => vec![part.to_string()],
+        };
+        for code in expanded {
+            if !codes.contains(&code) {
+                codes.push(code);
+            }
+        }
+    }
+    Ok(codes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watchlist_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("tushare-watchlist-{}", std::process::id()))
+            .join("banks.txt");
+        assert_eq!(read_watchlist(&path).unwrap(), None);
+
+        let codes = vec!["000001.SZ".to_string(), "600036.SH".to_string()];
+        write_watchlist(&path, &codes).unwrap();
+        assert_eq!(read_watchlist(&path).unwrap(), Some(codes.clone()));
+
+        // 手工编辑的注释和空行会被忽略
+        fs::write(&path, "# 银行\n000001.SZ\n\n600036.SH\n").unwrap();
+        assert_eq!(read_watchlist(&path).unwrap(), Some(codes));
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("banks").is_ok());
+        assert!(validate_name("白酒").is_ok());
+        for name in ["", ".hidden", "a/b", "a b", "a,b", "@a"] {
+            assert!(validate_name(name).is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_expand_codes() {
+        let load = |name: &str| match name {
+            "banks" => Ok(vec!["000001.SZ".to_string(), "600036.SH".to_string()]),
+            _ => Err(TushareError::ValidationError(name.to_string())),
+        };
+        assert_eq!(
+            expand_with("600519.SH, @banks,000001.SZ", load).unwrap(),
+            ["600519.SH", "000001.SZ", "600036.SH"]
+        );
+        assert!(expand_with("@missing", load).is_err());
+        assert!(references_watchlist("600519.SH,@banks"));
+        assert!(!references_watchlist("600519.SH"));
+    }
+}
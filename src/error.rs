@@ -42,6 +42,30 @@ pub enum TushareError {
     /// 输出格式错误
     #[error("输出格式错误: {0}")]
     OutputError(String),
+
+    /// 比较的数据有差异（diff 命令）
+    #[error("发现 {0} 处差异")]
+    DiffFound(usize),
+
+    /// 数据质量检查未通过（--check）
+    #[error("数据检查发现 {0} 个问题")]
+    CheckFailed(usize),
+
+    /// 本地存储错误
+    #[cfg(feature = "sqlite")]
+    #[error("本地存储操作失败: {0}")]
+    StoreError(#[from] rusqlite::Error),
+}
+
+impl TushareError {
+    /// 进程退出码：发现差异为 2，数据检查未通过为 3，其它错误为 1
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            TushareError::DiffFound(_) => 2,
+            TushareError::CheckFailed(_) => 3,
+            _ => 1,
+        }
+    }
 }
 
 /// TResult 类型别名
@@ -60,6 +84,13 @@ mod tests {
         assert_eq!(format!("{}", err), "API 返回错误 (code=-1): 测试错误");
     }
 
+    #[test]
+    fn test_exit_code() {
+        assert_eq!(TushareError::DiffFound(3).exit_code(), 2);
+        assert_eq!(TushareError::CheckFailed(1).exit_code(), 3);
+        assert_eq!(TushareError::TokenNotConfigured.exit_code(), 1);
+    }
+
     #[test]
     fn test_token_not_configured() {
         let err = TushareError::TokenNotConfigured;
@@ -0,0 +1,389 @@
+//! 技术指标
+//!
+//! `--indicators ma5,ma20,macd,rsi14` 对 K 线结果（`daily`、`index_daily`、`fund_daily`、
+//! `bar` 等）按 `ts_code` 分组、按 `trade_date` 升序计算指标，并追加为新的列。
+//! 支持 `maN`、`emaN`、`rsiN`、`atrN`、`macd(12,26,9)`、`kdj(9,3,3)`、`boll(20,2)`，
+//! 括号内参数可省略。
+
+pub mod series;
+
+use crate::api::{ApiDefinition, ApiOutputField};
+use crate::error::{TushareError, TResult};
+use crate::output::{format_value, get_fields};
+use crate::query::{number_value, round_to, value_as_f64};
+use series::Series;
+use std::collections::HashMap;
+
+/// 指标结果保留的小数位数
+const INDICATOR_DECIMALS: i32 = 4;
+
+/// 技术指标
+#[derive(Debug, Clone, PartialEq)]
+pub enum Indicator {
+    /// 简单移动平均
+    Ma(usize),
+    /// 指数移动平均
+    Ema(usize),
+    /// MACD
+    Macd { fast: usize, slow: usize, signal: usize },
+    /// 相对强弱指标
+    Rsi(usize),
+    /// 随机指标
+    Kdj { n: usize, m1: usize, m2: usize },
+    /// 布林线
+    Boll { n: usize, width: f64 },
+    /// 平均真实波幅
+    Atr(usize),
+}
+
+impl Indicator {
+    /// 解析指标列表，如 `ma5,ma20,macd,rsi14,boll(20,2)`
+    pub fn parse_list(spec: &str) -> TResult<Vec<Indicator>> {
+        let indicators = crate::query::aggregate::split_top_level(spec)
+            .into_iter()
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(Indicator::parse)
+            .collect::<TResult<Vec<_>>>()?;
+        if indicators.is_empty() {
+            return Err(TushareError::ParseError(format!("无效的指标设置: {}", spec)));
+        }
+        Ok(indicators)
+    }
+
+    /// 解析单个指标
+    pub fn parse(item: &str) -> TResult<Indicator> {
+        let error = || {
+            TushareError::ParseError(format!(
+                "无效的指标: {} (可选 maN|emaN|rsiN|atrN|macd(12,26,9)|kdj(9,3,3)|boll(20,2))",
+                item
+            ))
+        };
+
+        let item = item.trim().to_lowercase();
+        let name_end = item.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(item.len());
+        let (name, rest) = item.split_at(name_end);
+        let args: Vec<&str> = if rest.is_empty() {
+            Vec::new()
+        } else if let Some(inner) = rest.strip_prefix('(').and_then(|r| r.strip_suffix(')')) {
+            inner.split(',').map(str::trim).collect()
+        } else {
+            vec![rest]
+        };
+
+        let period = |index: usize, default: Option<usize>| -> TResult<usize> {
+            match args.get(index) {
+                Some(arg) => arg.parse().ok().filter(|n| *n > 0).ok_or_else(error),
+                None => default.ok_or_else(error),
+            }
+        };
+
+        let max_args = match name {
+            "macd" | "kdj" => 3,
+            "boll" => 2,
+            _ => 1,
+        };
+        if args.len() > max_args {
+            return Err(error());
+        }
+
+        match name {
+            "ma" => Ok(Indicator::Ma(period(0, None)?)),
+            "ema" => Ok(Indicator::Ema(period(0, None)?)),
+            "rsi" => Ok(Indicator::Rsi(period(0, Some(14))?)),
+            "atr" => Ok(Indicator::Atr(period(0, Some(14))?)),
+            "macd" => {
+                let (fast, slow, signal) = (period(0, Some(12))?, period(1, Some(26))?, period(2, Some(9))?);
+                if fast >= slow {
+                    return Err(error());
+                }
+                Ok(Indicator::Macd { fast, slow, signal })
+            }
+            "kdj" => Ok(Indicator::Kdj {
+                n: period(0, Some(9))?,
+                m1: period(1, Some(3))?,
+                m2: period(2, Some(3))?,
+            }),
+            "boll" => {
+                let width = match args.get(1) {
+                    Some(arg) => arg.parse().ok().filter(|w: &f64| *w > 0.0).ok_or_else(error)?,
+                    None => 2.0,
+                };
+                Ok(Indicator::Boll { n: period(0, Some(20))?, width })
+            }
+            _ => Err(error()),
+        }
+    }
+
+    /// 输出列（列名, 说明）
+    pub fn columns(&self) -> Vec<(String, String)> {
+        match self {
+            Indicator::Ma(n) => vec![(format!("ma{}", n), format!("{}日均线", n))],
+            Indicator::Ema(n) => vec![(format!("ema{}", n), format!("{}日指数均线", n))],
+            Indicator::Rsi(n) => vec![(format!("rsi{}", n), format!("{}日相对强弱指标", n))],
+            Indicator::Atr(n) => vec![(format!("atr{}", n), format!("{}日平均真实波幅", n))],
+            Indicator::Macd { .. } => vec![
+                ("macd_dif".to_string(), "MACD 快线 DIF".to_string()),
+                ("macd_dea".to_string(), "MACD 慢线 DEA".to_string()),
+                ("macd".to_string(), "MACD 柱".to_string()),
+            ],
+            Indicator::Kdj { .. } => vec![
+                ("kdj_k".to_string(), "KDJ K 值".to_string()),
+                ("kdj_d".to_string(), "KDJ D 值".to_string()),
+                ("kdj_j".to_string(), "KDJ J 值".to_string()),
+            ],
+            Indicator::Boll { .. } => vec![
+                ("boll_mid".to_string(), "布林线中轨".to_string()),
+                ("boll_upper".to_string(), "布林线上轨".to_string()),
+                ("boll_lower".to_string(), "布林线下轨".to_string()),
+            ],
+        }
+    }
+
+    /// 计算所需的价格字段
+    fn required_fields(&self) -> &'static [&'static str] {
+        match self {
+            Indicator::Kdj { .. } | Indicator::Atr(_) => &["high", "low", "close"],
+            _ => &["close"],
+        }
+    }
+
+    /// 计算指标序列，顺序与 `columns` 一致
+    fn compute(&self, prices: &Prices) -> Vec<Series> {
+        let (high, low, close) = (&prices.high, &prices.low, &prices.close);
+        match *self {
+            Indicator::Ma(n) => vec![series::ma(close, n)],
+            Indicator::Ema(n) => vec![series::ema(close, n)],
+            Indicator::Rsi(n) => vec![series::rsi(close, n)],
+            Indicator::Atr(n) => vec![series::atr(high, low, close, n)],
+            Indicator::Macd { fast, slow, signal } => {
+                let (dif, dea, hist) = series::macd(close, fast, slow, signal);
+                vec![dif, dea, hist]
+            }
+            Indicator::Kdj { n, m1, m2 } => {
+                let (k, d, j) = series::kdj(high, low, close, n, m1, m2);
+                vec![k, d, j]
+            }
+            Indicator::Boll { n, width } => {
+                let (mid, upper, lower) = series::boll(close, n, width);
+                vec![mid, upper, lower]
+            }
+        }
+    }
+}
+
+/// 单只证券按时间升序的价格
+#[derive(Default)]
+struct Prices {
+    high: Vec<f64>,
+    low: Vec<f64>,
+    close: Vec<f64>,
+}
+
+/// 计算指标并追加为新的列，行的顺序保持不变
+pub fn apply(
+    data: &[HashMap<String, serde_json::Value>],
+    indicators: &[Indicator],
+) -> TResult<Vec<HashMap<String, serde_json::Value>>> {
+    let mut rows = data.to_vec();
+    let Some(first) = data.first() else {
+        return Ok(rows);
+    };
+
+    let mut required = vec!["trade_date"];
+    for indicator in indicators {
+        required.extend(indicator.required_fields());
+    }
+    if let Some(missing) = required.iter().find(|f| !first.contains_key(**f)) {
+        return Err(TushareError::ValidationError(format!(
+            "计算技术指标需要 {} 字段，请使用 K 线接口（如 daily、index_daily、fund_daily）",
+            missing
+        )));
+    }
+
+    // 按证券分组，组内按交易日期升序
+    let mut groups: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, row) in data.iter().enumerate() {
+        let code = row.get("ts_code").map(format_value).unwrap_or_default();
+        groups.entry(code).or_default().push(index);
+    }
+
+    for mut indices in groups.into_values() {
+        indices.sort_by_key(|&i| format_value(&data[i]["trade_date"]));
+
+        let mut prices = Prices::default();
+        for &i in &indices {
+            let price = |field: &str| -> TResult<f64> {
+                data[i].get(field).and_then(value_as_f64).ok_or_else(|| {
+                    TushareError::ValidationError(format!(
+                        "{} 的 {} 为空或不是数值，无法计算技术指标",
+                        format_value(&data[i]["trade_date"]),
+                        field
+                    ))
+                })
+            };
+            prices.close.push(price("close")?);
+            if required.contains(&"high") {
+                prices.high.push(price("high")?);
+                prices.low.push(price("low")?);
+            }
+        }
+
+        for indicator in indicators {
+            for ((name, _), values) in indicator.columns().into_iter().zip(indicator.compute(&prices)) {
+                for (&i, value) in indices.iter().zip(values) {
+                    rows[i].insert(name.clone(), number_value(value.map(|v| round_to(v, INDICATOR_DECIMALS))));
+                }
+            }
+        }
+    }
+
+    Ok(rows)
+}
+
+/// 生成追加指标列后的字段定义：原字段在前，指标列在后
+pub fn indicator_definition(
+    api: Option<&ApiDefinition>,
+    data: &[HashMap<String, serde_json::Value>],
+    indicators: &[Indicator],
+) -> ApiDefinition {
+    let columns: Vec<(String, String)> = indicators.iter().flat_map(Indicator::columns).collect();
+
+    let mut output_fields: Vec<ApiOutputField> = match api {
+        Some(api) => api.output_fields.clone(),
+        None => get_fields(data)
+            .into_iter()
+            .map(|name| ApiOutputField::new(&name, "", ""))
+            .collect(),
+    };
+    output_fields.retain(|f| !columns.iter().any(|(name, _)| *name == f.name));
+
+    for (name, description) in columns {
+        if !output_fields.iter().any(|f| f.name == name) {
+            output_fields.push(ApiOutputField::new(&name, "float", &description));
+        }
+    }
+
+    ApiDefinition::derived(api, output_fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::TushareResponse;
+    use serde_json::json;
+
+    fn fixture() -> Vec<HashMap<String, serde_json::Value>> {
+        serde_json::from_str::<TushareResponse>(include_str!("../../tests/fixtures/indicators_000001_daily.json"))
+            .unwrap()
+            .to_objects()
+    }
+
+    #[test]
+    fn test_parse_indicators() {
+        let indicators = Indicator::parse_list("ma5, ema12,macd,rsi,kdj(9,3,3),boll(20,2.5),atr14").unwrap();
+        assert_eq!(
+            indicators,
+            vec![
+                Indicator::Ma(5),
+                Indicator::Ema(12),
+                Indicator::Macd { fast: 12, slow: 26, signal: 9 },
+                Indicator::Rsi(14),
+                Indicator::Kdj { n: 9, m1: 3, m2: 3 },
+                Indicator::Boll { n: 20, width: 2.5 },
+                Indicator::Atr(14),
+            ]
+        );
+        assert_eq!(Indicator::parse("MA(10)").unwrap(), Indicator::Ma(10));
+
+        for bad in ["ma", "ma0", "foo5", "macd(26,12)", "rsi(1,2)", "boll(20,-1)", ""] {
+            assert!(Indicator::parse(bad).is_err(), "{}", bad);
+        }
+    }
+
+    /// 期望值由通达信公式（MyTT 实现）对同一份数据计算得到
+    #[test]
+    fn test_reference_values() {
+        let indicators = Indicator::parse_list("ma5,ma20,ema12,macd,rsi14,kdj,boll,atr14").unwrap();
+        let rows = apply(&fixture(), &indicators).unwrap();
+
+        // 行的顺序保持不变，最新的交易日在前
+        let latest = &rows[0];
+        assert_eq!(latest["trade_date"], json!("20240226"));
+        let expected = [
+            ("ma5", 7.114),
+            ("ma20", 7.303500000000001),
+            ("ema12", 7.188849204992624),
+            ("macd_dif", -0.3235995942001155),
+            ("macd_dea", -0.3856119387230676),
+            ("macd", 0.12402468904590414),
+            ("rsi14", 38.02712099817621),
+            ("kdj_k", 58.68807699150584),
+            ("kdj_d", 46.50693029891224),
+            ("kdj_j", 83.05037037669302),
+            ("boll_mid", 7.303500000000001),
+            ("boll_upper", 7.958916661368935),
+            ("boll_lower", 6.648083338631068),
+            ("atr14", 0.2864285714285712),
+        ];
+        for (column, want) in expected {
+            let got = latest[column].as_f64().unwrap();
+            assert!((got - want).abs() < 1e-4, "{}: {} != {}", column, got, want);
+        }
+
+        let row = rows.iter().find(|r| r["trade_date"] == json!("20240206")).unwrap();
+        assert!((row["kdj_j"].as_f64().unwrap() - 41.22099441852565).abs() < 1e-4);
+        assert!((row["atr14"].as_f64().unwrap() - 0.3357142857142857).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_warmup_is_null() {
+        let rows = apply(&fixture(), &Indicator::parse_list("ma5,rsi14,kdj,atr14").unwrap()).unwrap();
+        let on = |date: &str| rows.iter().find(|r| r["trade_date"] == json!(date)).unwrap();
+        assert_eq!(on("20240105")["ma5"], json!(null));
+        assert!(on("20240108")["ma5"].is_number());
+        assert_eq!(on("20240102")["rsi14"], json!(null));
+        assert_eq!(on("20240111")["kdj_k"], json!(null));
+        assert!(on("20240112")["kdj_k"].is_number());
+        assert_eq!(on("20240119")["atr14"], json!(null));
+        assert!(on("20240122")["atr14"].is_number());
+    }
+
+    #[test]
+    fn test_groups_by_ts_code() {
+        let row = |code: &str, date: &str, close: f64| -> HashMap<String, serde_json::Value> {
+            [("ts_code", json!(code)), ("trade_date", json!(date)), ("close", json!(close))]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect()
+        };
+        let data = vec![
+            row("A", "20240103", 2.0),
+            row("B", "20240103", 20.0),
+            row("A", "20240102", 1.0),
+            row("B", "20240102", 10.0),
+        ];
+        let rows = apply(&data, &[Indicator::Ma(2)]).unwrap();
+        assert_eq!(rows[0]["ma2"], json!(1.5));
+        assert_eq!(rows[1]["ma2"], json!(15.0));
+        assert_eq!(rows[2]["ma2"], json!(null));
+    }
+
+    #[test]
+    fn test_missing_fields() {
+        let data = vec![[("trade_date".to_string(), json!("20240102"))].into_iter().collect()];
+        assert!(apply(&data, &[Indicator::Ma(5)]).is_err());
+        assert!(apply(&[], &[Indicator::Ma(5)]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_indicator_definition() {
+        let indicators = Indicator::parse_list("ma5,macd").unwrap();
+        let rows = apply(&fixture(), &indicators).unwrap();
+        let definition = indicator_definition(None, &fixture(), &indicators);
+        let names: Vec<&str> = definition.output_fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(&names[names.len() - 4..], ["ma5", "macd_dif", "macd_dea", "macd"]);
+        assert_eq!(definition.output_fields.last().unwrap().description, "MACD 柱");
+        assert!(rows[0].contains_key("macd"));
+    }
+}
@@ -0,0 +1,189 @@
+//! 指标序列计算
+//!
+//! 输入按时间升序排列，输出与输入等长，数据不足的位置为 `None`。
+//! 公式与通达信一致：`MA`/`STD` 为固定窗口，`EMA`/`SMA` 以第一个有效值为初值递推。
+
+/// 数据不足时为 None 的序列
+pub type Series = Vec<Option<f64>>;
+
+/// 简单移动平均 MA(X, N)
+pub fn ma(values: &[f64], n: usize) -> Series {
+    rolling_mean(&to_series(values), n)
+}
+
+/// 指数移动平均 EMA(X, N)，平滑系数 2/(N+1)
+pub fn ema(values: &[f64], n: usize) -> Series {
+    ewm(&to_series(values), 2.0 / (n as f64 + 1.0))
+}
+
+/// 通达信 SMA(X, N, M)，平滑系数 M/N
+pub fn sma(values: &[f64], n: usize, m: usize) -> Series {
+    ewm(&to_series(values), m as f64 / n as f64)
+}
+
+/// MACD(快线, 慢线, 信号线)，返回 (DIF, DEA, MACD 柱)，柱 = 2 × (DIF − DEA)
+pub fn macd(close: &[f64], fast: usize, slow: usize, signal: usize) -> (Series, Series, Series) {
+    let dif: Series = ema(close, fast)
+        .iter()
+        .zip(ema(close, slow))
+        .map(|(f, s)| Some((*f)? - s?))
+        .collect();
+    let dea = ewm(&dif, 2.0 / (signal as f64 + 1.0));
+    let hist = dif.iter().zip(&dea).map(|(d, e)| Some(2.0 * ((*d)? - (*e)?))).collect();
+    (dif, dea, hist)
+}
+
+/// RSI(N) = SMA(MAX(C − LC, 0), N, 1) / SMA(ABS(C − LC), N, 1) × 100
+pub fn rsi(close: &[f64], n: usize) -> Series {
+    let diff: Series = (0..close.len())
+        .map(|i| if i == 0 { None } else { Some(close[i] - close[i - 1]) })
+        .collect();
+    let alpha = 1.0 / n as f64;
+    let up = ewm(&diff.iter().map(|d| d.map(|d| d.max(0.0))).collect::<Series>(), alpha);
+    let all = ewm(&diff.iter().map(|d| d.map(f64::abs)).collect::<Series>(), alpha);
+    up.iter()
+        .zip(all)
+        .map(|(u, a)| {
+            let (u, a) = ((*u)?, a?);
+            (a != 0.0).then(|| u / a * 100.0)
+        })
+        .collect()
+}
+
+/// KDJ(N, M1, M2)，返回 (K, D, J)
+///
+/// RSV = (C − LLV(L, N)) / (HHV(H, N) − LLV(L, N)) × 100，K = SMA(RSV, M1, 1)，
+/// D = SMA(K, M2, 1)，J = 3K − 2D。
+pub fn kdj(high: &[f64], low: &[f64], close: &[f64], n: usize, m1: usize, m2: usize) -> (Series, Series, Series) {
+    let rsv: Series = (0..close.len())
+        .map(|i| {
+            let start = (i + 1).checked_sub(n)?;
+            let lowest = low[start..=i].iter().copied().fold(f64::INFINITY, f64::min);
+            let highest = high[start..=i].iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            if highest == lowest {
+                Some(0.0)
+            } else {
+                Some((close[i] - lowest) / (highest - lowest) * 100.0)
+            }
+        })
+        .collect();
+    let k = ewm(&rsv, 1.0 / m1 as f64);
+    let d = ewm(&k, 1.0 / m2 as f64);
+    let j = k.iter().zip(&d).map(|(k, d)| Some(3.0 * (*k)? - 2.0 * (*d)?)).collect();
+    (k, d, j)
+}
+
+/// 布林线 BOLL(N, K)，返回 (中轨, 上轨, 下轨)，标准差为总体标准差
+pub fn boll(close: &[f64], n: usize, width: f64) -> (Series, Series, Series) {
+    let mid = ma(close, n);
+    let std: Series = (0..close.len())
+        .map(|i| {
+            let start = (i + 1).checked_sub(n)?;
+            let window = &close[start..=i];
+            let mean = window.iter().sum::<f64>() / n as f64;
+            Some((window.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n as f64).sqrt())
+        })
+        .collect();
+    let band = |sign: f64| -> Series {
+        mid.iter().zip(&std).map(|(m, s)| Some((*m)? + sign * width * (*s)?)).collect()
+    };
+    let upper = band(1.0);
+    let lower = band(-1.0);
+    (mid, upper, lower)
+}
+
+/// 平均真实波幅 ATR(N) = MA(TR, N)
+///
+/// TR = MAX(H − L, |LC − H|, |LC − L|)，第一根 K 线没有前收盘价，TR 为空。
+pub fn atr(high: &[f64], low: &[f64], close: &[f64], n: usize) -> Series {
+    let tr: Series = (0..close.len())
+        .map(|i| {
+            let prev = close[..i].last()?;
+            Some((high[i] - low[i]).max((prev - high[i]).abs()).max((prev - low[i]).abs()))
+        })
+        .collect();
+    rolling_mean(&tr, n)
+}
+
+fn to_series(values: &[f64]) -> Series {
+    values.iter().copied().map(Some).collect()
+}
+
+/// 固定窗口均值，窗口内有空值时为空
+fn rolling_mean(values: &[Option<f64>], n: usize) -> Series {
+    (0..values.len())
+        .map(|i| {
+            let start = (i + 1).checked_sub(n)?;
+            values[start..=i]
+                .iter()
+                .try_fold(0.0, |sum, v| Some(sum + (*v)?))
+                .map(|sum| sum / n as f64)
+        })
+        .collect()
+}
+
+/// 指数加权递推，以第一个有效值为初值，之前的空值保持为空
+fn ewm(values: &[Option<f64>], alpha: f64) -> Series {
+    let mut prev: Option<f64> = None;
+    values
+        .iter()
+        .map(|v| {
+            if let Some(v) = v {
+                prev = Some(match prev {
+                    Some(p) => alpha * v + (1.0 - alpha) * p,
+                    None => *v,
+                });
+            }
+            prev
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(got: Option<f64>, want: f64) {
+        let got = got.expect("指标值为空");
+        assert!((got - want).abs() < 1e-9, "{} != {}", got, want);
+    }
+
+    #[test]
+    fn test_ma_and_ema() {
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(ma(&values, 3), vec![None, None, Some(2.0), Some(3.0), Some(4.0)]);
+        // EMA(3)：系数 0.5，1 → 1.5 → 2.25 → 3.125 → 4.0625
+        assert_eq!(ema(&values, 3), vec![Some(1.0), Some(1.5), Some(2.25), Some(3.125), Some(4.0625)]);
+        assert_eq!(sma(&values, 2, 1), ema(&values, 3));
+    }
+
+    #[test]
+    fn test_rsi() {
+        // 差值 1, -1, 2：up 1 → 0.5 → 1.25，abs 1 → 1 → 1.5
+        let rsi = rsi(&[10.0, 11.0, 10.0, 12.0], 2);
+        assert_eq!(rsi[0], None);
+        assert_close(rsi[1], 100.0);
+        assert_close(rsi[2], 50.0);
+        assert_close(rsi[3], 1.25 / 1.5 * 100.0);
+        // 价格不变时没有定义
+        assert_eq!(super::rsi(&[10.0, 10.0], 2)[1], None);
+    }
+
+    #[test]
+    fn test_boll_and_atr() {
+        let close = [1.0, 2.0, 3.0];
+        let (mid, upper, lower) = boll(&close, 3, 2.0);
+        let std = (2.0f64 / 3.0).sqrt();
+        assert_eq!(mid[1], None);
+        assert_close(mid[2], 2.0);
+        assert_close(upper[2], 2.0 + 2.0 * std);
+        assert_close(lower[2], 2.0 - 2.0 * std);
+
+        let high = [1.5, 2.5, 4.0];
+        let low = [0.5, 1.0, 2.5];
+        // TR: 空, max(1.5, 1.5, 0) = 1.5, max(1.5, 2, 0.5) = 2
+        let atr = atr(&high, &low, &close, 2);
+        assert_eq!(atr[1], None);
+        assert_close(atr[2], 1.75);
+    }
+}
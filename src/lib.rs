@@ -6,3 +6,9 @@ pub mod error;
 pub mod output;
 pub mod api;
 pub mod config;
+pub mod store;
+pub mod query;
+pub mod indicators;
+pub mod resample;
+pub mod check;
+pub mod calendar;
@@ -2,14 +2,8 @@
 //!
 //! 获取中国金融市场数据的命令行工具
 
-mod client;
-mod cli;
-mod error;
-mod output;
-mod api;
-mod config;
-
-use cli::{parse_args, handle_command};
+use tushare::cli::{parse_args, handle_command};
+use tushare::error::TushareError;
 
 #[tokio::main]
 async fn main() {
@@ -27,7 +21,10 @@ async fn main() {
 
     // 处理命令
     if let Err(e) = handle_command(parsed_args).await {
-        eprintln!("错误: {}", e);
-        std::process::exit(1);
+        // 差异和检查报告已经输出，只设置退出码
+        if !matches!(e, TushareError::DiffFound(_) | TushareError::CheckFailed(_)) {
+            eprintln!("错误: {}", e);
+        }
+        std::process::exit(e.exit_code());
     }
 }
@@ -0,0 +1,428 @@
+//! 终端图表输出
+//!
+//! 将返回的行情序列绘制为终端图表（`--chart line|candle|bar`），宽度适应终端。
+//! K 线图自动识别 `open/high/low/close` 字段，并按 A 股习惯着色（红涨绿跌）。
+
+use crate::api::ApiDefinition;
+use crate::cli::args::{ChartKind, Options};
+use crate::output::display::group_thousands;
+use crate::output::pager::{stdout_is_tty, terminal_size};
+use crate::output::ordered_fields;
+use crate::error::{TushareError, TResult};
+use std::collections::HashMap;
+
+/// 无法获取终端尺寸时的图表宽度
+const DEFAULT_CHART_WIDTH: usize = 80;
+
+/// 图表高度（行）
+const DEFAULT_CHART_HEIGHT: usize = 16;
+
+/// 图表最小绘图宽度
+const MIN_PLOT_WIDTH: usize = 10;
+
+/// 按顺序尝试的横轴字段
+const X_CANDIDATES: [&str; 6] = ["trade_date", "nav_date", "end_date", "ann_date", "cal_date", "month"];
+
+/// 按顺序尝试的纵轴字段
+const Y_CANDIDATES: [&str; 4] = ["close", "unit_nav", "accum_nav", "vol"];
+
+const ANSI_RED: &str = "\x1b[31m";
+const ANSI_GREEN: &str = "\x1b[32m";
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// 图表中的一个采样点（多行数据合并时取首开、末收、最高、最低）
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartPoint {
+    /// 横轴标签
+    pub x: String,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+/// 以终端图表输出数据
+pub fn output_chart(
+    data: &[HashMap<String, serde_json::Value>],
+    options: &Options,
+    api: Option<&ApiDefinition>,
+    kind: ChartKind,
+) -> TResult<()> {
+    if data.is_empty() {
+        println!("(无数据)");
+        return Ok(());
+    }
+
+    let (width, height) = match terminal_size() {
+        Some((cols, rows)) => (cols, DEFAULT_CHART_HEIGHT.min(rows.saturating_sub(4)).max(4)),
+        None => (DEFAULT_CHART_WIDTH, DEFAULT_CHART_HEIGHT),
+    };
+    let colored = !options.no_color && std::env::var_os("NO_COLOR").is_none() && stdout_is_tty();
+
+    println!("{}", render_chart(data, options, api, kind, width, height, colored)?);
+    Ok(())
+}
+
+/// 渲染图表
+pub fn render_chart(
+    data: &[HashMap<String, serde_json::Value>],
+    options: &Options,
+    api: Option<&ApiDefinition>,
+    kind: ChartKind,
+    width: usize,
+    height: usize,
+    colored: bool,
+) -> TResult<String> {
+    let fields = ordered_fields(data, api);
+    let x_field = match &options.chart_x {
+        Some(x) => Some(require_field(&fields, x)?),
+        None => X_CANDIDATES.iter().find(|c| fields.iter().any(|f| f == *c)).map(|c| c.to_string()),
+    };
+    let y_field = match &options.chart_y {
+        Some(y) => require_field(&fields, y)?,
+        None => default_y_field(data, &fields)?,
+    };
+
+    let points = extract_points(data, x_field.as_deref(), &y_field, kind)?;
+    if points.is_empty() {
+        return Err(TushareError::ValidationError(format!("字段 {} 没有可绘制的数值", y_field)));
+    }
+
+    // 纵轴范围
+    let (mut min, mut max) = points.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| {
+        (lo.min(p.low), hi.max(p.high))
+    });
+    if kind == ChartKind::Bar {
+        min = min.min(0.0);
+        max = max.max(0.0);
+    }
+
+    let labels = [format_axis(max), format_axis((max + min) / 2.0), format_axis(min)];
+    let label_width = labels.iter().map(|l| l.len()).max().unwrap_or(0);
+    let plot_width = width.saturating_sub(label_width + 2).max(MIN_PLOT_WIDTH);
+    let height = height.max(2);
+
+    let points = bucket_points(&points, plot_width);
+    let grid = match kind {
+        ChartKind::Line => draw_line(&points, height, min, max),
+        ChartKind::Bar => draw_bar(&points, height, min, max),
+        ChartKind::Candle => draw_candle(&points, height, min, max),
+    };
+
+    let mut out = String::new();
+    let first = points.first().map(|p| p.x.as_str()).unwrap_or_default();
+    let last = points.last().map(|p| p.x.as_str()).unwrap_or_default();
+    let latest = points.last().map(|p| p.close).unwrap_or_default();
+    out.push_str(&format!(
+        "{} ({})  {} ~ {}  最新 {}  最高 {}  最低 {}\n",
+        y_field,
+        kind.as_str(),
+        first,
+        last,
+        format_axis(latest),
+        labels[0],
+        labels[2]
+    ));
+
+    for (r, row) in grid.iter().enumerate() {
+        let label = if r == 0 {
+            labels[0].as_str()
+        } else if r == height - 1 {
+            labels[2].as_str()
+        } else if r == (height - 1) / 2 {
+            labels[1].as_str()
+        } else {
+            ""
+        };
+        let tick = if label.is_empty() { '│' } else { '┤' };
+        out.push_str(&format!("{:>width$} {}", label, tick, width = label_width));
+        for cell in row {
+            match cell.up {
+                Some(true) if colored => out.push_str(&format!("{}{}{}", ANSI_RED, cell.ch, ANSI_RESET)),
+                Some(false) if colored => out.push_str(&format!("{}{}{}", ANSI_GREEN, cell.ch, ANSI_RESET)),
+                _ => out.push(cell.ch),
+            }
+        }
+        out.push('\n');
+    }
+
+    out.push_str(&format!("{:>width$} └{}\n", "", "─".repeat(points.len()), width = label_width));
+    let gap = points.len().saturating_sub(first.len() + last.len()).max(1);
+    out.push_str(&format!("{:>width$}  {}{}{}", "", first, " ".repeat(gap), last, width = label_width));
+
+    Ok(out)
+}
+
+/// 检查字段是否存在
+fn require_field(fields: &[String], field: &str) -> TResult<String> {
+    if fields.iter().any(|f| f == field) {
+        Ok(field.to_string())
+    } else {
+        Err(TushareError::ValidationError(format!(
+            "结果中没有字段 {} (可用字段: {})",
+            field,
+            fields.join(", ")
+        )))
+    }
+}
+
+/// 选择默认纵轴字段：常见价格字段优先，否则取第一个数值字段
+fn default_y_field(data: &[HashMap<String, serde_json::Value>], fields: &[String]) -> TResult<String> {
+    if let Some(field) = Y_CANDIDATES.iter().find(|c| fields.iter().any(|f| f == *c)) {
+        return Ok(field.to_string());
+    }
+    fields
+        .iter()
+        .find(|f| data.iter().any(|row| row.get(*f).map(|v| v.is_number()).unwrap_or(false)))
+        .cloned()
+        .ok_or_else(|| TushareError::ValidationError("结果中没有数值字段，请使用 --y 指定".to_string()))
+}
+
+/// 读取数值（兼容字符串形式的数字）
+fn number(row: &HashMap<String, serde_json::Value>, field: &str) -> Option<f64> {
+    match row.get(field)? {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// 从数据行提取采样点，按横轴升序排列（接口通常按日期倒序返回）
+pub fn extract_points(
+    data: &[HashMap<String, serde_json::Value>],
+    x_field: Option<&str>,
+    y_field: &str,
+    kind: ChartKind,
+) -> TResult<Vec<ChartPoint>> {
+    if kind == ChartKind::Candle {
+        let missing: Vec<&str> = ["open", "high", "low", "close"]
+            .into_iter()
+            .filter(|f| !data.iter().any(|row| row.contains_key(*f)))
+            .collect();
+        if !missing.is_empty() {
+            return Err(TushareError::ValidationError(format!(
+                "K 线图需要 open/high/low/close 字段，缺少: {}",
+                missing.join(", ")
+            )));
+        }
+    }
+
+    let mut points: Vec<ChartPoint> = data
+        .iter()
+        .enumerate()
+        .filter_map(|(i, row)| {
+            let x = match x_field {
+                Some(f) => row.get(f).map(crate::output::format_value).unwrap_or_default(),
+                None => (i + 1).to_string(),
+            };
+            if kind == ChartKind::Candle {
+                Some(ChartPoint {
+                    x,
+                    open: number(row, "open")?,
+                    high: number(row, "high")?,
+                    low: number(row, "low")?,
+                    close: number(row, "close")?,
+                })
+            } else {
+                let v = number(row, y_field)?;
+                Some(ChartPoint { x, open: v, high: v, low: v, close: v })
+            }
+        })
+        .collect();
+
+    if x_field.is_some() {
+        points.sort_by(|a, b| a.x.cmp(&b.x));
+    }
+    Ok(points)
+}
+
+/// 采样点多于绘图宽度时按区间合并
+pub fn bucket_points(points: &[ChartPoint], width: usize) -> Vec<ChartPoint> {
+    if points.len() <= width {
+        return points.to_vec();
+    }
+
+    (0..width)
+        .filter_map(|i| {
+            let bucket = &points[i * points.len() / width..(i + 1) * points.len() / width];
+            let first = bucket.first()?;
+            let last = bucket.last()?;
+            Some(ChartPoint {
+                x: last.x.clone(),
+                open: first.open,
+                high: bucket.iter().map(|p| p.high).fold(f64::NEG_INFINITY, f64::max),
+                low: bucket.iter().map(|p| p.low).fold(f64::INFINITY, f64::min),
+                close: last.close,
+            })
+        })
+        .collect()
+}
+
+/// 图表单元格
+#[derive(Debug, Clone, Copy)]
+struct ChartCell {
+    ch: char,
+    /// 涨跌（用于着色），None 表示不着色
+    up: Option<bool>,
+}
+
+impl Default for ChartCell {
+    fn default() -> Self {
+        Self { ch: ' ', up: None }
+    }
+}
+
+/// 数值映射到行号（0 为顶部）
+fn value_row(value: f64, min: f64, max: f64, height: usize) -> usize {
+    if max <= min {
+        return height / 2;
+    }
+    let level = ((value - min) / (max - min) * (height - 1) as f64).round() as usize;
+    height - 1 - level.min(height - 1)
+}
+
+/// 折线图：每列一个点，相邻点之间用竖线连接
+fn draw_line(points: &[ChartPoint], height: usize, min: f64, max: f64) -> Vec<Vec<ChartCell>> {
+    let mut grid = vec![vec![ChartCell::default(); points.len()]; height];
+    let mut prev: Option<usize> = None;
+    for (col, point) in points.iter().enumerate() {
+        let row = value_row(point.close, min, max, height);
+        if let Some(prev) = prev {
+            let (from, to) = if prev < row { (prev + 1, row) } else { (row + 1, prev) };
+            for line in grid.iter_mut().take(to).skip(from) {
+                line[col].ch = '│';
+            }
+        }
+        grid[row][col].ch = '•';
+        prev = Some(row);
+    }
+    grid
+}
+
+/// 柱状图：每列一根柱子，从零轴向上（正值）或向下（负值）填充
+fn draw_bar(points: &[ChartPoint], height: usize, min: f64, max: f64) -> Vec<Vec<ChartCell>> {
+    let mut grid = vec![vec![ChartCell::default(); points.len()]; height];
+    let baseline = value_row(0.0, min, max, height);
+    for (col, point) in points.iter().enumerate() {
+        if point.close == 0.0 {
+            continue;
+        }
+        let row = value_row(point.close, min, max, height);
+        let (top, bottom) = if row <= baseline { (row, baseline) } else { (baseline, row) };
+        for line in &mut grid[top..=bottom] {
+            line[col].ch = '█';
+        }
+    }
+    grid
+}
+
+/// K 线图：影线为 │，实体为 █（开收相等时为 ─）
+fn draw_candle(points: &[ChartPoint], height: usize, min: f64, max: f64) -> Vec<Vec<ChartCell>> {
+    let mut grid = vec![vec![ChartCell::default(); points.len()]; height];
+    for (col, point) in points.iter().enumerate() {
+        let up = point.close >= point.open;
+        let high = value_row(point.high, min, max, height);
+        let low = value_row(point.low, min, max, height);
+        let body_top = value_row(point.open.max(point.close), min, max, height);
+        let body_bottom = value_row(point.open.min(point.close), min, max, height);
+
+        for (r, line) in grid.iter_mut().enumerate().take(low + 1).skip(high) {
+            let ch = if r >= body_top && r <= body_bottom {
+                if point.open == point.close { '─' } else { '█' }
+            } else {
+                '│'
+            };
+            line[col] = ChartCell { ch, up: Some(up) };
+        }
+    }
+    grid
+}
+
+/// 格式化纵轴刻度
+fn format_axis(value: f64) -> String {
+    let text = if value.abs() >= 1000.0 {
+        format!("{:.0}", value)
+    } else {
+        let text = format!("{:.2}", value);
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    };
+    group_thousands(&text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn bars() -> Vec<HashMap<String, serde_json::Value>> {
+        // 接口按日期倒序返回
+        [("20240104", 10.0, 10.8, 9.9, 10.6), ("20240103", 10.2, 10.3, 9.8, 10.0), ("20240102", 10.0, 10.4, 9.9, 10.2)]
+            .iter()
+            .map(|(date, open, high, low, close)| {
+                vec![
+                    ("trade_date".to_string(), json!(date)),
+                    ("open".to_string(), json!(open)),
+                    ("high".to_string(), json!(high)),
+                    ("low".to_string(), json!(low)),
+                    ("close".to_string(), json!(close)),
+                ]
+                .into_iter()
+                .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_extract_points_sorted() {
+        let points = extract_points(&bars(), Some("trade_date"), "close", ChartKind::Line).unwrap();
+        let dates: Vec<&str> = points.iter().map(|p| p.x.as_str()).collect();
+        assert_eq!(dates, vec!["20240102", "20240103", "20240104"]);
+        assert_eq!(points[2].close, 10.6);
+    }
+
+    #[test]
+    fn test_candle_requires_ohlc() {
+        let data = vec![vec![("close".to_string(), json!(1.0))].into_iter().collect()];
+        let err = extract_points(&data, None, "close", ChartKind::Candle).unwrap_err();
+        assert!(err.to_string().contains("open"));
+    }
+
+    #[test]
+    fn test_bucket_points() {
+        let points = extract_points(&bars(), Some("trade_date"), "close", ChartKind::Candle).unwrap();
+        let merged = bucket_points(&points, 1);
+        assert_eq!(
+            merged,
+            vec![ChartPoint { x: "20240104".to_string(), open: 10.0, high: 10.8, low: 9.8, close: 10.6 }]
+        );
+    }
+
+    #[test]
+    fn test_render_chart() {
+        for kind in [ChartKind::Line, ChartKind::Bar, ChartKind::Candle] {
+            let chart = render_chart(&bars(), &Options::default(), None, kind, 40, 6, false).unwrap();
+            let lines: Vec<&str> = chart.lines().collect();
+            // 标题 + 6 行图 + 横轴 + 日期
+            assert_eq!(lines.len(), 9);
+            assert!(lines[0].starts_with("close"));
+            assert!(lines[8].contains("20240102") && lines[8].contains("20240104"));
+        }
+    }
+
+    #[test]
+    fn test_draw_bar_mixed_sign() {
+        let point = |close: f64| ChartPoint { x: String::new(), open: close, high: close, low: close, close };
+        let grid = draw_bar(&[point(2.0), point(-2.0), point(0.0)], 5, -2.0, 2.0);
+        let column = |col: usize| grid.iter().map(|line| line[col].ch).collect::<String>();
+        // 零轴位于第 2 行：正值向上填充，负值向下填充
+        assert_eq!(column(0), "███  ");
+        assert_eq!(column(1), "  ███");
+        assert_eq!(column(2), "     ");
+    }
+
+    #[test]
+    fn test_render_chart_unknown_field() {
+        let options = Options { chart_y: Some("amount".to_string()), ..Options::default() };
+        assert!(render_chart(&bars(), &options, None, ChartKind::Line, 40, 6, false).is_err());
+    }
+}
@@ -1,23 +1,32 @@
 //! CSV 格式化输出
 
-use crate::output::{escape_csv_value, format_value, get_fields};
+use crate::api::ApiDefinition;
+use crate::cli::args::Options;
+use crate::output::{escape_csv_value, format_value, header_labels, ordered_fields, select_order};
 use crate::error::TResult;
 use std::collections::HashMap;
 use std::io::Write;
 
 /// 以 CSV 格式输出数据
-pub fn output_csv(data: &[HashMap<String, serde_json::Value>]) -> TResult<()> {
+pub fn output_csv(
+    data: &[HashMap<String, serde_json::Value>],
+    options: &Options,
+    api: Option<&ApiDefinition>,
+) -> TResult<()> {
     if data.is_empty() {
         println!("(无数据)");
         return Ok(());
     }
 
-    let fields = get_fields(data);
+    let fields = select_order(ordered_fields(data, api), options);
     let stdout = std::io::stdout();
     let mut handle = stdout.lock();
 
     // 输出表头
-    let header: Vec<String> = fields.iter().map(|f| escape_csv_value(f)).collect();
+    let header: Vec<String> = header_labels(&fields, api, options.header)
+        .iter()
+        .map(|f| escape_csv_value(f))
+        .collect();
     writeln!(handle, "{}", header.join(","))
         .map_err(|e| crate::error::TushareError::OutputError(format!("写入输出失败: {}", e)))?;
 
@@ -60,7 +69,7 @@ mod tests {
             .collect(),
         ];
 
-        let result = output_csv(&data);
+        let result = output_csv(&data, &Options::default(), None);
         assert!(result.is_ok());
     }
 
@@ -73,7 +82,7 @@ mod tests {
         .into_iter()
         .collect()];
 
-        let result = output_csv(&data);
+        let result = output_csv(&data, &Options::default(), None);
         assert!(result.is_ok());
     }
 }
@@ -0,0 +1,321 @@
+//! 数值显示格式化
+//!
+//! 为 table/markdown 输出提供人性化的数值显示：按字段描述中的单位
+//! （如 `成交额 （千元）`、`总市值 （万元）`）换算为万/亿或 K/M/B，
+//! 添加千分位、固定小数位和百分号。CSV/JSON 输出不经过此层。
+
+use crate::api::ApiDefinition;
+use crate::cli::args::{DecimalSpec, NumberStyle, Options};
+use crate::output::format_value;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// 换算后默认保留的小数位数
+const DEFAULT_DECIMALS: usize = 2;
+
+/// 没有单位描述时按数量处理的字段名关键字
+const QUANTITY_KEYWORDS: [&str; 10] = [
+    "amount", "vol", "mv", "share", "revenue", "income", "profit", "asset", "liab", "cash",
+];
+
+/// 列的显示规则
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColumnRule {
+    /// 单位换算倍数（如 千元 为 1000）
+    pub multiplier: f64,
+    /// 基础单位（如 元、手、股）
+    pub base_unit: String,
+    /// 是否为可换算的数量（金额、成交量、股本等）
+    pub quantity: bool,
+    /// 是否为百分比
+    pub percent: bool,
+    /// 是否为涨跌类字段
+    pub change: bool,
+}
+
+/// 数值显示格式化器
+#[derive(Debug, Clone)]
+pub struct DisplayFormatter {
+    style: NumberStyle,
+    decimals: DecimalSpec,
+    rules: HashMap<String, ColumnRule>,
+}
+
+impl DisplayFormatter {
+    /// 根据输出选项和 API 定义创建格式化器
+    pub fn new(options: &Options, api: Option<&ApiDefinition>) -> Self {
+        let descriptions: HashMap<&str, &str> = api
+            .map(|api| {
+                api.output_fields
+                    .iter()
+                    .map(|f| (f.name.as_str(), f.description.as_str()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let rules = descriptions
+            .iter()
+            .map(|(name, desc)| (name.to_string(), column_rule(name, desc)))
+            .collect();
+
+        Self {
+            style: options.number_style,
+            decimals: options.decimals.clone(),
+            rules,
+        }
+    }
+
+    /// 获取列的显示规则（定义中没有的字段按字段名推断）
+    fn rule(&self, field: &str) -> ColumnRule {
+        self.rules
+            .get(field)
+            .cloned()
+            .unwrap_or_else(|| column_rule(field, ""))
+    }
+
+    /// 格式化单元格
+    pub fn format(&self, field: &str, value: &serde_json::Value) -> String {
+        let number = match value.as_f64() {
+            Some(n) if value.is_number() => n,
+            _ => return format_value(value),
+        };
+
+        let decimals = self.decimals.for_column(field);
+        if self.style == NumberStyle::Raw {
+            return match decimals {
+                Some(d) => format!("{:.*}", d, number),
+                None => format_value(value),
+            };
+        }
+
+        let rule = self.rule(field);
+        if rule.percent {
+            return format!("{:.*}%", decimals.unwrap_or(DEFAULT_DECIMALS), number);
+        }
+
+        if rule.quantity {
+            let base = number * rule.multiplier;
+            if let Some((scaled, suffix)) = scale(base, self.style) {
+                let unit = if self.style == NumberStyle::Chinese { rule.base_unit.as_str() } else { "" };
+                return format!(
+                    "{}{}{}",
+                    group_thousands(&format!("{:.*}", decimals.unwrap_or(DEFAULT_DECIMALS), scaled)),
+                    suffix,
+                    unit
+                );
+            }
+        }
+
+        let text = match decimals {
+            Some(d) => format!("{:.*}", d, number),
+            None => format_value(value),
+        };
+        group_thousands(&text)
+    }
+
+    /// 涨跌类字段的符号，用于终端着色
+    pub fn change_sign(&self, field: &str, value: &serde_json::Value) -> Option<Ordering> {
+        if !self.rule(field).change {
+            return None;
+        }
+        value.as_f64().and_then(|n| n.partial_cmp(&0.0))
+    }
+}
+
+/// 根据字段名和描述推断列的显示规则
+pub fn column_rule(name: &str, description: &str) -> ColumnRule {
+    let unit = extract_unit(description);
+    let percent = unit.as_deref() == Some("%")
+        || name.contains("pct")
+        || (name.ends_with("_rate") && description.contains('%'));
+    let change = name == "change" || name.starts_with("pct_ch") || name.ends_with("_pct_chg") || name == "chg";
+
+    let (multiplier, base_unit) = unit
+        .as_deref()
+        .and_then(unit_multiplier)
+        .map(|(m, base)| (m, base.to_string()))
+        .unwrap_or((1.0, String::new()));
+
+    let quantity = !percent
+        && (base_unit_known(unit.as_deref())
+            || (unit.is_none() && QUANTITY_KEYWORDS.iter().any(|k| name.contains(k))));
+
+    ColumnRule {
+        multiplier,
+        base_unit,
+        quantity,
+        percent,
+        change,
+    }
+}
+
+/// 从描述中提取括号内的单位，如 `成交额 （千元）` → `千元`
+pub fn extract_unit(description: &str) -> Option<String> {
+    let mut rest = description;
+    while let Some(start) = rest.find(['（', '(']) {
+        let open_len = rest[start..].chars().next().map(|c| c.len_utf8()).unwrap_or(1);
+        let inner = &rest[start + open_len..];
+        let end = inner.find(['）', ')'])?;
+        let content = inner[..end].trim().trim_start_matches("单位：").trim();
+        if content == "%" || unit_multiplier(content).is_some() {
+            return Some(content.to_string());
+        }
+        rest = &inner[end..];
+    }
+    None
+}
+
+/// 单位换算：返回（倍数, 基础单位）
+fn unit_multiplier(unit: &str) -> Option<(f64, &'static str)> {
+    let result = match unit {
+        "元" => (1.0, "元"),
+        "千元" => (1e3, "元"),
+        "万元" => (1e4, "元"),
+        "百万元" => (1e6, "元"),
+        "亿元" => (1e8, "元"),
+        "万" => (1e4, ""),
+        "亿" => (1e8, ""),
+        "手" => (1.0, "手"),
+        "股" => (1.0, "股"),
+        "万股" => (1e4, "股"),
+        "亿股" => (1e8, "股"),
+        "万份" => (1e4, "份"),
+        "张" => (1.0, "张"),
+        "万笔" => (1e4, "笔"),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// 单位是否为可换算的数量单位
+fn base_unit_known(unit: Option<&str>) -> bool {
+    unit.map(|u| u != "%" && unit_multiplier(u).is_some()).unwrap_or(false)
+}
+
+/// 按显示风格换算数值，返回（换算后数值, 后缀）；数值太小时不换算
+fn scale(value: f64, style: NumberStyle) -> Option<(f64, &'static str)> {
+    let abs = value.abs();
+    match style {
+        NumberStyle::Chinese if abs >= 1e8 => Some((value / 1e8, "亿")),
+        NumberStyle::Chinese if abs >= 1e4 => Some((value / 1e4, "万")),
+        NumberStyle::English if abs >= 1e9 => Some((value / 1e9, "B")),
+        NumberStyle::English if abs >= 1e6 => Some((value / 1e6, "M")),
+        NumberStyle::English if abs >= 1e3 => Some((value / 1e3, "K")),
+        _ => None,
+    }
+}
+
+/// 为数字字符串的整数部分添加千分位分隔符
+pub fn group_thousands(number: &str) -> String {
+    let (sign, rest) = match number.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", number),
+    };
+    let (int_part, frac_part) = match rest.find('.') {
+        Some(idx) => (&rest[..idx], &rest[idx..]),
+        None => (rest, ""),
+    };
+    if !int_part.chars().all(|c| c.is_ascii_digit()) {
+        return number.to_string();
+    }
+
+    let mut grouped = String::new();
+    for (i, c) in int_part.chars().enumerate() {
+        if i > 0 && (int_part.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+
+    format!("{}{}{}", sign, grouped, frac_part)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn daily_api() -> ApiDefinition {
+        ApiDefinition::for_test(
+            "daily",
+            &[
+                ("close", "float", "收盘价"),
+                ("pct_chg", "float", "涨跌幅 【基于除权后的昨收计算的涨跌幅：（今收-除权昨收）/除权昨收 】"),
+                ("vol", "float", "成交量 （手）"),
+                ("amount", "float", "成交额 （千元）"),
+                ("total_mv", "float", "总市值 （万元）"),
+                ("turnover_rate", "float", "换手率（%）"),
+            ],
+        )
+    }
+
+    fn formatter(style: NumberStyle) -> DisplayFormatter {
+        let options = Options {
+            number_style: style,
+            ..Options::default()
+        };
+        DisplayFormatter::new(&options, Some(&daily_api()))
+    }
+
+    #[test]
+    fn test_extract_unit() {
+        assert_eq!(extract_unit("成交额 （千元）"), Some("千元".to_string()));
+        assert_eq!(extract_unit("流通市值（万元）"), Some("万元".to_string()));
+        assert_eq!(extract_unit("股息率（TTM）（%）"), Some("%".to_string()));
+        assert_eq!(extract_unit("买入量(单位：手)"), Some("手".to_string()));
+        assert_eq!(extract_unit("市盈率（TTM，亏损的PE为空）"), None);
+        assert_eq!(extract_unit("收盘价"), None);
+    }
+
+    #[test]
+    fn test_group_thousands() {
+        assert_eq!(group_thousands("1234567.891"), "1,234,567.891");
+        assert_eq!(group_thousands("-1234"), "-1,234");
+        assert_eq!(group_thousands("123"), "123");
+        assert_eq!(group_thousands("abc"), "abc");
+    }
+
+    #[test]
+    fn test_format_chinese_units() {
+        let f = formatter(NumberStyle::Chinese);
+        // 123456789.123 千元 = 1234.57 亿元
+        assert_eq!(f.format("amount", &json!(123456789.123)), "1,234.57亿元");
+        assert_eq!(f.format("total_mv", &json!(22345678.9)), "2,234.57亿元");
+        assert_eq!(f.format("vol", &json!(1234567.0)), "123.46万手");
+        assert_eq!(f.format("vol", &json!(999.0)), "999");
+        assert_eq!(f.format("close", &json!(1712.5)), "1,712.5");
+        assert_eq!(f.format("pct_chg", &json!(1.2345)), "1.23%");
+        assert_eq!(f.format("turnover_rate", &json!(0.5)), "0.50%");
+    }
+
+    #[test]
+    fn test_format_english_units() {
+        let f = formatter(NumberStyle::English);
+        assert_eq!(f.format("amount", &json!(123456789.123)), "123.46B");
+        assert_eq!(f.format("vol", &json!(1234567.0)), "1.23M");
+    }
+
+    #[test]
+    fn test_format_raw_and_decimals() {
+        let f = formatter(NumberStyle::Raw);
+        assert_eq!(f.format("amount", &json!(123456789.123)), "123456789.123");
+
+        let options = Options {
+            number_style: NumberStyle::Chinese,
+            decimals: DecimalSpec::parse("1,close=3").unwrap(),
+            ..Options::default()
+        };
+        let f = DisplayFormatter::new(&options, Some(&daily_api()));
+        assert_eq!(f.format("close", &json!(10.5)), "10.500");
+        assert_eq!(f.format("amount", &json!(123456789.123)), "1,234.6亿元");
+        assert_eq!(f.format("ts_code", &json!("000001.SZ")), "000001.SZ");
+    }
+
+    #[test]
+    fn test_change_sign() {
+        let f = formatter(NumberStyle::Raw);
+        assert_eq!(f.change_sign("pct_chg", &json!(1.5)), Some(Ordering::Greater));
+        assert_eq!(f.change_sign("pct_chg", &json!(-0.5)), Some(Ordering::Less));
+        assert_eq!(f.change_sign("close", &json!(10.0)), None);
+    }
+}
@@ -0,0 +1,244 @@
+//! HTML 报告输出
+//!
+//! 生成单个独立的 HTML 文件（内联 CSS/JS，不依赖 CDN），包含请求参数、
+//! 可排序和筛选的表格；表头悬停显示字段说明，数值列右对齐。
+
+use crate::api::ApiDefinition;
+use crate::cli::args::Options;
+use crate::output::display::DisplayFormatter;
+use crate::output::{format_value, header_labels, ordered_fields, select_order};
+use crate::error::{TushareError, TResult};
+use std::collections::HashMap;
+use std::io::Write;
+
+/// 内联样式
+const STYLE: &str = r#"
+body { font-family: -apple-system, "PingFang SC", "Microsoft YaHei", sans-serif; margin: 24px; color: #222; }
+h1 { font-size: 20px; margin: 0 0 8px; }
+.meta { background: #f6f8fa; border: 1px solid #ddd; border-radius: 6px; padding: 8px 12px; margin-bottom: 12px; font-size: 13px; }
+.meta dt { float: left; clear: left; width: 72px; color: #666; }
+.meta dd { margin: 0 0 4px 80px; font-family: monospace; }
+#filter { padding: 6px 8px; width: 320px; margin-bottom: 8px; }
+table { border-collapse: collapse; font-size: 13px; }
+th, td { border: 1px solid #ddd; padding: 4px 8px; white-space: nowrap; }
+th { background: #f0f2f5; cursor: pointer; user-select: none; position: sticky; top: 0; }
+th.asc::after { content: " ▲"; }
+th.desc::after { content: " ▼"; }
+td.num { text-align: right; font-variant-numeric: tabular-nums; }
+tr:nth-child(even) td { background: #fafafa; }
+"#;
+
+/// 内联脚本：点击表头排序，输入框筛选
+///
+/// 数值列（表头带 `data-type="num"`）按数值比较，无法解析的值排在数字之后；空值
+/// 无论升降序都排在最后。
+const SCRIPT: &str = r#"
+(function () {
+  var table = document.getElementById('data');
+  var body = table.tBodies[0];
+  var headers = table.tHead.rows[0].cells;
+  Array.prototype.forEach.call(headers, function (th, col) {
+    th.addEventListener('click', function () {
+      var asc = !th.classList.contains('asc');
+      Array.prototype.forEach.call(headers, function (h) { h.classList.remove('asc', 'desc'); });
+      th.classList.add(asc ? 'asc' : 'desc');
+      var numeric = th.getAttribute('data-type') === 'num';
+      var rows = Array.prototype.slice.call(body.rows);
+      rows.sort(function (a, b) {
+        var x = a.cells[col].getAttribute('data-sort'), y = b.cells[col].getAttribute('data-sort');
+        if (x === '' || y === '') return (x === '') - (y === '');
+        var r;
+        if (numeric) {
+          var nx = parseFloat(x), ny = parseFloat(y);
+          r = (isNaN(nx) || isNaN(ny)) ? (isNaN(nx) - isNaN(ny)) || x.localeCompare(y, 'zh') : nx - ny;
+        } else {
+          r = x.localeCompare(y, 'zh');
+        }
+        return asc ? r : -r;
+      });
+      rows.forEach(function (row) { body.appendChild(row); });
+    });
+  });
+  document.getElementById('filter').addEventListener('input', function (e) {
+    var q = e.target.value.toLowerCase();
+    var shown = 0;
+    Array.prototype.forEach.call(body.rows, function (row) {
+      var match = row.textContent.toLowerCase().indexOf(q) >= 0;
+      row.style.display = match ? '' : 'none';
+      if (match) shown++;
+    });
+    document.getElementById('count').textContent = shown;
+  });
+})();
+"#;
+
+/// 以 HTML 报告格式输出数据，指定 `--output` 时写入文件
+pub fn output_html(
+    data: &[HashMap<String, serde_json::Value>],
+    options: &Options,
+    api: Option<&ApiDefinition>,
+    params: Option<&serde_json::Value>,
+) -> TResult<()> {
+    let html = render_html(data, options, api, params);
+
+    match &options.output {
+        Some(path) => {
+            std::fs::write(path, html)
+                .map_err(|e| TushareError::OutputError(format!("写入文件 {} 失败: {}", path, e)))?;
+            eprintln!("已生成报告: {} ({} 行)", path, data.len());
+        }
+        None => {
+            let stdout = std::io::stdout();
+            let mut handle = stdout.lock();
+            write!(handle, "{}", html)
+                .map_err(|e| TushareError::OutputError(format!("写入输出失败: {}", e)))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 渲染 HTML 报告
+pub fn render_html(
+    data: &[HashMap<String, serde_json::Value>],
+    options: &Options,
+    api: Option<&ApiDefinition>,
+    params: Option<&serde_json::Value>,
+) -> String {
+    let fields = select_order(ordered_fields(data, api), options);
+    let labels = header_labels(&fields, api, options.header);
+    let formatter = DisplayFormatter::new(options, api);
+    let descriptions: HashMap<&str, &str> = api
+        .map(|api| {
+            api.output_fields
+                .iter()
+                .map(|f| (f.name.as_str(), f.description.as_str()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // 数值列：所有非空值均为数字
+    let numeric: Vec<bool> = fields
+        .iter()
+        .map(|f| {
+            let mut values = data.iter().filter_map(|row| row.get(f)).filter(|v| !v.is_null()).peekable();
+            values.peek().is_some() && values.all(|v| v.is_number())
+        })
+        .collect();
+
+    let title = match api {
+        Some(api) => format!("{} - {}", api.name, api.description),
+        None => "Tushare 数据报告".to_string(),
+    };
+
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html lang=\"zh-CN\">\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str(&format!("<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n", escape_html(&title), STYLE));
+    out.push_str(&format!("<h1>{}</h1>\n", escape_html(&title)));
+
+    // 请求信息
+    out.push_str("<dl class=\"meta\">\n");
+    if let Some(api) = api {
+        out.push_str(&format!("<dt>接口</dt><dd>{}</dd>\n", escape_html(&api.name)));
+    }
+    out.push_str(&format!("<dt>参数</dt><dd>{}</dd>\n", escape_html(&format_params(params))));
+    out.push_str(&format!(
+        "<dt>生成时间</dt><dd>{}</dd>\n",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+    ));
+    out.push_str(&format!("<dt>行数</dt><dd><span id=\"count\">{}</span> / {}</dd>\n", data.len(), data.len()));
+    out.push_str("</dl>\n");
+
+    out.push_str("<input id=\"filter\" type=\"search\" placeholder=\"筛选...\">\n");
+    out.push_str("<table id=\"data\">\n<thead><tr>");
+    for ((field, label), is_numeric) in fields.iter().zip(&labels).zip(&numeric) {
+        let tooltip = descriptions.get(field.as_str()).copied().unwrap_or(field.as_str());
+        let kind = if *is_numeric { " data-type=\"num\"" } else { "" };
+        out.push_str(&format!("<th title=\"{}\"{}>{}</th>", escape_html(tooltip), kind, escape_html(label)));
+    }
+    out.push_str("</tr></thead>\n<tbody>\n");
+
+    for row in data {
+        out.push_str("<tr>");
+        for (field, is_numeric) in fields.iter().zip(&numeric) {
+            let (sort, text) = match row.get(field) {
+                Some(v) => (format_value(v), formatter.format(field, v)),
+                None => (String::new(), String::new()),
+            };
+            let class = if *is_numeric { " class=\"num\"" } else { "" };
+            out.push_str(&format!(
+                "<td{} data-sort=\"{}\">{}</td>",
+                class,
+                escape_html(&sort),
+                escape_html(&text)
+            ));
+        }
+        out.push_str("</tr>\n");
+    }
+
+    out.push_str("</tbody>\n</table>\n");
+    out.push_str(&format!("<script>{}</script>\n</body>\n</html>\n", SCRIPT));
+    out
+}
+
+/// 格式化请求参数为 `key=value` 列表
+fn format_params(params: Option<&serde_json::Value>) -> String {
+    match params.and_then(|p| p.as_object()) {
+        Some(map) if !map.is_empty() => map
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, format_value(v)))
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => "(无)".to_string(),
+    }
+}
+
+/// 转义 HTML 特殊字符
+pub fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn daily_api() -> ApiDefinition {
+        ApiDefinition::for_test("daily", &[("ts_code", "str", "股票代码"), ("close", "float", "收盘价")])
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(escape_html("<a href=\"x\">&</a>"), "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;");
+    }
+
+    #[test]
+    fn test_render_html() {
+        let data = vec![vec![
+            ("ts_code".to_string(), json!("000001.SZ")),
+            ("close".to_string(), json!(10.5)),
+        ]
+        .into_iter()
+        .collect()];
+        let params = json!({ "ts_code": "000001.SZ" });
+
+        let html = render_html(&data, &Options::default(), Some(&daily_api()), Some(&params));
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(!html.contains("http"));
+        assert!(html.contains("<th title=\"股票代码\">ts_code</th>"));
+        assert!(html.contains("<th title=\"收盘价\" data-type=\"num\">close</th>"));
+        assert!(html.contains("<td class=\"num\" data-sort=\"10.5\">10.5</td>"));
+        assert!(html.contains("ts_code=000001.SZ"));
+    }
+}
@@ -1,86 +1,101 @@
 //! Markdown 格式化输出
 
-use crate::output::{format_value, get_fields};
+use crate::api::ApiDefinition;
+use crate::cli::args::Options;
+use crate::output::display::DisplayFormatter;
+use crate::output::pager::print_paged;
+use crate::output::{display_width, header_labels, ordered_fields, select_order, pad_to_width, row_limit, truncate_to_width};
 use crate::error::TResult;
 use std::collections::HashMap;
-use std::io::Write;
+
+/// 终端中默认显示的最大行数
+const DEFAULT_MAX_ROWS: usize = 100;
 
 /// 以 Markdown 表格格式输出数据
-pub fn output_markdown(data: &[HashMap<String, serde_json::Value>]) -> TResult<()> {
+pub fn output_markdown(
+    data: &[HashMap<String, serde_json::Value>],
+    options: &Options,
+    api: Option<&ApiDefinition>,
+) -> TResult<()> {
     if data.is_empty() {
         println!("(无数据)");
         return Ok(());
     }
 
-    let fields = get_fields(data);
+    let max_rows = row_limit(options, DEFAULT_MAX_ROWS);
+    print_paged(&render_markdown(data, options, api, max_rows), !options.no_pager)
+}
+
+/// 渲染 Markdown 表格
+pub fn render_markdown(
+    data: &[HashMap<String, serde_json::Value>],
+    options: &Options,
+    api: Option<&ApiDefinition>,
+    max_rows: Option<usize>,
+) -> String {
+    let fields = select_order(ordered_fields(data, api), options);
+    let labels = header_labels(&fields, api, options.header);
+    let formatter = DisplayFormatter::new(options, api);
 
     // 限制显示行数
-    let max_rows = 100;
-    let display_data: Vec<_> = data.iter().take(max_rows).cloned().collect();
-
-    let stdout = std::io::stdout();
-    let mut handle = stdout.lock();
-
-    // 计算列宽
-    let mut widths: Vec<usize> = fields.iter().map(|f| f.len()).collect();
-    for row in &display_data {
-        for (i, field) in fields.iter().enumerate() {
-            let value = row.get(field).map(format_value).unwrap_or_default();
-            let value_len = if value.len() > 30 {
-                30
-            } else {
-                value.len()
-            };
-            widths[i] = widths[i].max(value_len);
+    let display_count = max_rows.unwrap_or(data.len()).min(data.len());
+
+    // 截断单元格内容
+    let cells: Vec<Vec<String>> = data[..display_count]
+        .iter()
+        .map(|row| {
+            fields
+                .iter()
+                .map(|f| {
+                    let value = row.get(f).map(|v| formatter.format(f, v)).unwrap_or_default();
+                    truncate_to_width(&value, options.max_width)
+                })
+                .collect()
+        })
+        .collect();
+
+    // 计算列宽（按显示宽度）
+    let mut widths: Vec<usize> = labels.iter().map(|f| display_width(f)).collect();
+    for row in &cells {
+        for (i, value) in row.iter().enumerate() {
+            widths[i] = widths[i].max(display_width(value));
         }
     }
 
-    // 输出表头
-    let header: Vec<String> = fields
+    let mut lines = Vec::with_capacity(cells.len() + 3);
+
+    // 表头
+    let header: Vec<String> = labels
         .iter()
         .enumerate()
-        .map(|(i, f)| format!("{:<width$}", f, width = widths[i]))
+        .map(|(i, f)| pad_to_width(f, widths[i]))
         .collect();
-    writeln!(handle, "| {} |", header.join(" | "))
-        .map_err(|e| crate::error::TushareError::OutputError(format!("写入输出失败: {}", e)))?;
+    lines.push(format!("| {} |", header.join(" | ")));
 
-    // 输出分隔行
-    let separator: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
-    writeln!(handle, "|{}|", separator.join("|"))
-        .map_err(|e| crate::error::TushareError::OutputError(format!("写入输出失败: {}", e)))?;
+    // 分隔行
+    let separator: Vec<String> = widths.iter().map(|w| "-".repeat(w + 2)).collect();
+    lines.push(format!("|{}|", separator.join("|")));
 
-    // 输出数据行
-    for row in &display_data {
-        let values: Vec<String> = fields
+    // 数据行
+    for row in &cells {
+        let values: Vec<String> = row
             .iter()
             .enumerate()
-            .map(|(i, f)| {
-                let value = row.get(f).map(format_value).unwrap_or_default();
-                let truncated = if value.len() > 30 {
-                    format!("{}...", &value[..27])
-                } else {
-                    value
-                };
-                format!("{:<width$}", truncated, width = widths[i])
-            })
+            .map(|(i, value)| pad_to_width(value, widths[i]))
             .collect();
-
-        writeln!(handle, "| {} |", values.join(" | "))
-            .map_err(|e| crate::error::TushareError::OutputError(format!("写入输出失败: {}", e)))?;
+        lines.push(format!("| {} |", values.join(" | ")));
     }
 
     // 如果数据被截断，添加提示
-    if data.len() > max_rows {
-        writeln!(
-            handle,
-            "\n... (共 {} 行，仅显示前 {} 行)",
+    if data.len() > display_count {
+        lines.push(format!(
+            "\n... (共 {} 行，仅显示前 {} 行，使用 --max-rows 0 显示全部)",
             data.len(),
-            max_rows
-        )
-        .map_err(|e| crate::error::TushareError::OutputError(format!("写入输出失败: {}", e)))?;
+            display_count
+        ));
     }
 
-    Ok(())
+    lines.join("\n")
 }
 
 #[cfg(test)]
@@ -105,14 +120,43 @@ mod tests {
             .collect(),
         ];
 
-        let result = output_markdown(&data);
+        let result = output_markdown(&data, &Options::default(), None);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_output_markdown_cjk() {
+        // 多字节字符落在截断位置时不应 panic
+        let data = vec![vec![
+            ("name".to_string(), json!("平安银行股份有限公司平安银行股份有限公司")),
+            ("area".to_string(), json!("深圳")),
+        ]
+        .into_iter()
+        .collect()];
+
+        let result = output_markdown(&data, &Options::default(), None);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_render_markdown_alignment() {
+        let data = vec![
+            vec![("name".to_string(), json!("平安银行"))].into_iter().collect(),
+            vec![("name".to_string(), json!("PAYH"))].into_iter().collect(),
+        ];
+
+        let rendered = render_markdown(&data, &Options::default(), None, None);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], "| name     |");
+        assert_eq!(lines[1], "|----------|");
+        assert_eq!(lines[2], "| 平安银行 |");
+        assert_eq!(lines[3], "| PAYH     |");
+    }
+
     #[test]
     fn test_output_empty_markdown() {
         let data: Vec<HashMap<String, serde_json::Value>> = Vec::new();
-        let result = output_markdown(&data);
+        let result = output_markdown(&data, &Options::default(), None);
         assert!(result.is_ok());
     }
 }
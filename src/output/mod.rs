@@ -6,27 +6,136 @@ pub mod json;
 pub mod table;
 pub mod csv;
 pub mod markdown;
+pub mod pager;
+pub mod display;
+pub mod record;
+pub mod yaml;
+pub mod toml;
+pub mod html;
+pub mod chart;
 
-use crate::cli::args::OutputFormat;
-use crate::error::TResult;
+use crate::api::ApiDefinition;
+use crate::cli::args::{HeaderMode, Options, OutputFormat};
+use crate::error::{TushareError, TResult};
 use std::collections::HashMap;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 pub use json::output_json;
 pub use table::output_table;
 pub use csv::output_csv;
 pub use markdown::output_markdown;
+pub use record::output_record;
+pub use yaml::output_yaml;
+pub use toml::output_toml;
+pub use html::output_html;
+pub use chart::output_chart;
 
 /// 根据指定格式输出数据
+///
+/// `api` 为数据对应的 API 定义，用于 table/markdown 的数值显示（单位换算等）；
+/// `params` 为请求参数，显示在 html 报告头部。
 pub fn output_data(
     data: &[HashMap<String, serde_json::Value>],
-    format: OutputFormat,
-    pretty: bool,
+    options: &Options,
+    api: Option<&ApiDefinition>,
+    params: Option<&serde_json::Value>,
 ) -> TResult<()> {
-    match format {
-        OutputFormat::Json => output_json(data, pretty),
-        OutputFormat::Table => output_table(data),
-        OutputFormat::Csv => output_csv(data),
-        OutputFormat::Markdown => output_markdown(data),
+    if options.output.is_some() && options.format != OutputFormat::Html {
+        return Err(TushareError::ValidationError(
+            "--output 仅支持 html 格式，其它格式请使用重定向 (> 文件)".to_string(),
+        ));
+    }
+
+    // 重采样后的 K 线再计算指标和后处理
+    if let Some(freq) = options.resample {
+        let rows = crate::resample::resample(data, freq)?;
+        let intraday = data.first().is_some_and(|r| r.contains_key("trade_time"));
+        let definition = crate::resample::resample_definition(api, intraday, freq);
+        let mut options = options.clone();
+        options.resample = None;
+        return output_data(&rows, &options, definition.as_ref(), params);
+    }
+
+    // 追加技术指标列，之后的过滤、排序等可以使用指标列
+    if let Some(spec) = &options.indicators {
+        let indicators = crate::indicators::Indicator::parse_list(spec)?;
+        let rows = crate::indicators::apply(data, &indicators)?;
+        let definition = crate::indicators::indicator_definition(api, data, &indicators);
+        let mut options = options.clone();
+        options.indicators = None;
+        return output_data(&rows, &options, Some(&definition), params);
+    }
+
+    // --where/--group-by/--sort/--select 后处理，聚合结果使用新的字段定义和顺序
+    if crate::query::is_active(options) {
+        let result = crate::query::apply(data, options, api)?;
+        let mut options = options.clone();
+        if options.select.is_none() {
+            options.select = result.columns;
+        }
+        return write_data(&result.rows, &options, result.api.as_ref().or(api), params);
+    }
+
+    write_data(data, options, api, params)
+}
+
+/// 按 --head/--tail 选择行后按格式输出
+fn write_data(
+    data: &[HashMap<String, serde_json::Value>],
+    options: &Options,
+    api: Option<&ApiDefinition>,
+    params: Option<&serde_json::Value>,
+) -> TResult<()> {
+    let data = select_rows(data, options.head, options.tail);
+
+    // 图表作为额外的输出方式，替代格式输出
+    if let Some(kind) = options.chart {
+        return output_chart(data, options, api, kind);
+    }
+
+    match options.format {
+        OutputFormat::Json => output_json(data, options.pretty),
+        // 终端中单行且列数很多的结果自动使用记录格式
+        OutputFormat::Table
+            if data.len() == 1
+                && data[0].len() > record::AUTO_RECORD_COLUMNS
+                && pager::stdout_is_tty() =>
+        {
+            output_record(data, options, api)
+        }
+        OutputFormat::Table => output_table(data, options, api),
+        OutputFormat::Csv => output_csv(data, options, api),
+        OutputFormat::Markdown => output_markdown(data, options, api),
+        OutputFormat::Record => output_record(data, options, api),
+        OutputFormat::Yaml => output_yaml(data, options, api),
+        OutputFormat::Toml => output_toml(data, options, api),
+        OutputFormat::Html => output_html(data, options, api, params),
+    }
+}
+
+/// 按 --head/--tail 选择数据行
+pub fn select_rows<T>(data: &[T], head: Option<usize>, tail: Option<usize>) -> &[T] {
+    let data = match head {
+        Some(n) => &data[..n.min(data.len())],
+        None => data,
+    };
+    match tail {
+        Some(n) => &data[data.len().saturating_sub(n)..],
+        None => data,
+    }
+}
+
+/// 计算终端格式的最大显示行数
+///
+/// 显式指定 `--max-rows` 时使用该值（0 表示不限制）；否则仅在终端中使用
+/// 格式自身的默认值，输出到管道或文件时不截断。
+pub fn row_limit(options: &Options, default: usize) -> Option<usize> {
+    match options.max_rows {
+        Some(0) => None,
+        Some(n) => Some(n),
+        None if pager::stdout_is_tty() => Some(default),
+        None => None,
     }
 }
 
@@ -42,6 +151,72 @@ pub fn get_fields(data: &[HashMap<String, serde_json::Value>]) -> Vec<String> {
     fields
 }
 
+/// 按 API 定义中的顺序获取字段，定义中没有的字段按名称排在最后
+pub fn ordered_fields(data: &[HashMap<String, serde_json::Value>], api: Option<&ApiDefinition>) -> Vec<String> {
+    let present = get_fields(data);
+    let mut fields: Vec<String> = api
+        .map(|api| {
+            api.output_fields
+                .iter()
+                .filter(|f| present.contains(&f.name))
+                .map(|f| f.name.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    for field in present {
+        if !fields.contains(&field) {
+            fields.push(field);
+        }
+    }
+
+    fields
+}
+
+/// 按 `--select` 指定的顺序排列字段
+pub fn select_order(fields: Vec<String>, options: &Options) -> Vec<String> {
+    match &options.select {
+        Some(select) => select.iter().filter(|f| fields.contains(f)).cloned().collect(),
+        None => fields,
+    }
+}
+
+/// 生成表头标签
+///
+/// 按 `--header` 使用字段说明替换字段名，定义中没有说明的字段保留原名。
+pub fn header_labels(fields: &[String], api: Option<&ApiDefinition>, mode: HeaderMode) -> Vec<String> {
+    fields
+        .iter()
+        .map(|field| {
+            let description = api
+                .and_then(|api| api.output_fields.iter().find(|f| &f.name == field))
+                .map(|f| header_description(&f.description))
+                .filter(|d| !d.is_empty());
+
+            match (mode, description) {
+                (HeaderMode::Desc, Some(desc)) => desc,
+                (HeaderMode::Both, Some(desc)) => format!("{} ({})", desc, field),
+                _ => field.clone(),
+            }
+        })
+        .collect()
+}
+
+/// 精简字段说明用作表头：去掉 【】 内的补充说明并合并空白
+fn header_description(description: &str) -> String {
+    let mut result = String::new();
+    let mut depth: usize = 0;
+    for c in description.chars() {
+        match c {
+            '【' => depth += 1,
+            '】' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => result.push(c),
+            _ => {}
+        }
+    }
+    result.split_whitespace().collect::<Vec<_>>().join("")
+}
+
 /// 格式化 JSON 值为字符串
 pub fn format_value(value: &serde_json::Value) -> String {
     match value {
@@ -50,7 +225,8 @@ pub fn format_value(value: &serde_json::Value) -> String {
         serde_json::Value::Number(n) => {
             if let Some(f) = n.as_f64() {
                 if f.fract() == 0.0 {
-                    format!("{}", n.as_i64().unwrap_or(0))
+                    // 浮点型的整数值（如 1234.0）没有 i64 表示
+                    n.as_i64().map(|i| i.to_string()).unwrap_or_else(|| format!("{:.0}", f))
                 } else {
                     format!("{}", f)
                 }
@@ -64,6 +240,42 @@ pub fn format_value(value: &serde_json::Value) -> String {
     }
 }
 
+/// 计算字符串的终端显示宽度（中日韩等宽字符计为 2）
+pub fn display_width(value: &str) -> usize {
+    UnicodeWidthStr::width(value)
+}
+
+/// 按显示宽度截断字符串
+///
+/// 超出 `max_width` 时在字素边界截断并追加 `...`，`max_width` 为 0 表示不截断。
+pub fn truncate_to_width(value: &str, max_width: usize) -> String {
+    if max_width == 0 || display_width(value) <= max_width {
+        return value.to_string();
+    }
+
+    let ellipsis = if max_width > 3 { "..." } else { "" };
+    let budget = max_width - ellipsis.len();
+
+    let mut result = String::new();
+    let mut width = 0;
+    for grapheme in value.graphemes(true) {
+        let w = display_width(grapheme);
+        if width + w > budget {
+            break;
+        }
+        result.push_str(grapheme);
+        width += w;
+    }
+    result.push_str(ellipsis);
+    result
+}
+
+/// 按显示宽度右侧补齐空格
+pub fn pad_to_width(value: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(value));
+    format!("{}{}", value, " ".repeat(padding))
+}
+
 /// 转义 CSV 值
 pub fn escape_csv_value(value: &str) -> String {
     if value.contains(',') || value.contains('"') || value.contains('\n') {
@@ -95,14 +307,93 @@ mod tests {
     }
 
     #[test]
+    fn test_select_rows() {
+        let data = [1, 2, 3, 4, 5];
+        assert_eq!(select_rows(&data, None, None), &[1, 2, 3, 4, 5]);
+        assert_eq!(select_rows(&data, Some(2), None), &[1, 2]);
+        assert_eq!(select_rows(&data, None, Some(2)), &[4, 5]);
+        assert_eq!(select_rows(&data, Some(4), Some(2)), &[3, 4]);
+        assert_eq!(select_rows(&data, Some(10), Some(10)), &[1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_row_limit() {
+        let options = Options {
+            max_rows: Some(0),
+            ..Options::default()
+        };
+        assert_eq!(row_limit(&options, 20), None);
+
+        let options = Options {
+            max_rows: Some(5),
+            ..Options::default()
+        };
+        assert_eq!(row_limit(&options, 20), Some(5));
+    }
+
+    #[test]
+    fn test_header_labels() {
+        let api = ApiDefinition::for_test(
+            "daily",
+            &[
+                ("pct_chg", "float", "涨跌幅 【基于除权后的昨收计算的涨跌幅】"),
+                ("amount", "float", "成交额 （千元）"),
+            ],
+        );
+        let fields = vec!["amount".to_string(), "pct_chg".to_string(), "extra".to_string()];
+
+        assert_eq!(header_labels(&fields, Some(&api), HeaderMode::Name), fields);
+        assert_eq!(
+            header_labels(&fields, Some(&api), HeaderMode::Desc),
+            vec!["成交额（千元）", "涨跌幅", "extra"]
+        );
+        assert_eq!(
+            header_labels(&fields, Some(&api), HeaderMode::Both),
+            vec!["成交额（千元） (amount)", "涨跌幅 (pct_chg)", "extra"]
+        );
+        assert_eq!(header_labels(&fields, None, HeaderMode::Desc), fields);
+    }
+
+    #[test]
+    #[allow(clippy::approx_constant)] // 3.14 只是示例小数，不是 π
     fn test_format_value() {
         assert_eq!(format_value(&json!(null)), "");
         assert_eq!(format_value(&json!(true)), "true");
         assert_eq!(format_value(&json!(42)), "42");
+        assert_eq!(format_value(&json!(1234.0)), "1234");
         assert_eq!(format_value(&json!(3.14)), "3.14");
         assert_eq!(format_value(&json!("hello")), "hello");
     }
 
+    #[test]
+    fn test_display_width() {
+        assert_eq!(display_width("abc"), 3);
+        assert_eq!(display_width("平安银行"), 8);
+        assert_eq!(display_width("万科A"), 5);
+    }
+
+    #[test]
+    fn test_truncate_to_width() {
+        assert_eq!(truncate_to_width("short", 30), "short");
+        assert_eq!(truncate_to_width("abcdefghij", 8), "abcde...");
+        assert_eq!(truncate_to_width("平安银行股份有限公司", 11), "平安银行...");
+        assert_eq!(truncate_to_width("平安银行股份有限公司", 12), "平安银行...");
+        assert_eq!(truncate_to_width("平安银行股份有限公司", 0), "平安银行股份有限公司");
+        assert_eq!(truncate_to_width("平安银行", 3), "平");
+
+        // 组合字符不会被拆开
+        let truncated = truncate_to_width("e\u{301}e\u{301}e\u{301}e\u{301}e\u{301}", 4);
+        assert!(truncated.starts_with("e\u{301}"));
+        assert!(display_width(&truncated) <= 4);
+    }
+
+    #[test]
+    fn test_pad_to_width() {
+        assert_eq!(pad_to_width("ab", 4), "ab  ");
+        assert_eq!(pad_to_width("平安", 6), "平安  ");
+        assert_eq!(pad_to_width("平安银行", 4), "平安银行");
+    }
+
     #[test]
     fn test_escape_csv_value() {
         assert_eq!(escape_csv_value("simple"), "simple");
@@ -0,0 +1,88 @@
+//! 终端分页输出
+//!
+//! 当标准输出为终端且内容超过一屏时，通过 `$PAGER`（默认 `less -RS`）输出。
+
+use crate::error::{TushareError, TResult};
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// 默认分页程序（-R 保留涨跌着色）
+const DEFAULT_PAGER: &str = "less -RS";
+
+/// 标准输出是否为终端
+pub fn stdout_is_tty() -> bool {
+    std::io::stdout().is_terminal()
+}
+
+/// 获取终端尺寸（列数, 行数）
+pub fn terminal_size() -> Option<(usize, usize)> {
+    crossterm::terminal::size()
+        .ok()
+        .filter(|(cols, rows)| *cols > 0 && *rows > 0)
+        .map(|(cols, rows)| (cols as usize, rows as usize))
+}
+
+/// 输出文本，超过一屏时使用分页程序
+///
+/// `use_pager` 为 false、标准输出不是终端或分页程序启动失败时直接输出。
+pub fn print_paged(text: &str, use_pager: bool) -> TResult<()> {
+    if use_pager && stdout_is_tty() && exceeds_screen(text) {
+        if let Some(pager) = pager_command() {
+            if run_pager(&pager, text).is_ok() {
+                return Ok(());
+            }
+        }
+    }
+
+    let stdout = std::io::stdout();
+    let mut handle = stdout.lock();
+    writeln!(handle, "{}", text)
+        .map_err(|e| TushareError::OutputError(format!("写入输出失败: {}", e)))
+}
+
+/// 内容是否超过一屏
+fn exceeds_screen(text: &str) -> bool {
+    match terminal_size() {
+        Some((_, rows)) => text.lines().count() >= rows,
+        None => false,
+    }
+}
+
+/// 获取分页程序命令，`PAGER` 设为空字符串时禁用分页
+fn pager_command() -> Option<String> {
+    match std::env::var("PAGER") {
+        Ok(pager) if pager.trim().is_empty() => None,
+        Ok(pager) => Some(pager),
+        Err(_) => Some(DEFAULT_PAGER.to_string()),
+    }
+}
+
+/// 启动分页程序并写入内容
+fn run_pager(pager: &str, text: &str) -> std::io::Result<()> {
+    let mut parts = pager.split_whitespace();
+    let program = parts.next().unwrap_or("less");
+    let mut child = Command::new(program)
+        .args(parts)
+        .stdin(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // 用户提前退出分页程序时会出现 BrokenPipe，忽略即可
+        let _ = writeln!(stdin, "{}", text);
+    }
+    child.wait()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_print_paged_without_tty() {
+        // 测试环境下标准输出不是终端，应直接输出
+        let result = print_paged("line 1\nline 2", true);
+        assert!(result.is_ok());
+    }
+}
@@ -0,0 +1,139 @@
+//! 纵向记录格式化输出
+//!
+//! 每个字段输出为一行 `字段 | 值 | 说明`，适合 `stock_company`、`fina_indicator`
+//! 等列数很多的结果。字段说明取自 API 定义中的 `ApiOutputField.description`。
+
+use crate::api::ApiDefinition;
+use crate::cli::args::Options;
+use crate::output::display::DisplayFormatter;
+use crate::output::pager::print_paged;
+use crate::output::{ordered_fields, row_limit, select_order};
+use crate::error::TResult;
+use comfy_table::{presets::UTF8_FULL, ContentArrangement, Table};
+use std::collections::HashMap;
+
+/// 单行结果超过该列数时，终端中的表格输出自动切换为记录格式
+pub const AUTO_RECORD_COLUMNS: usize = 10;
+
+/// 终端中默认显示的最大记录数，与表格输出一致
+const DEFAULT_MAX_ROWS: usize = 20;
+
+/// 以纵向记录格式输出数据
+pub fn output_record(
+    data: &[HashMap<String, serde_json::Value>],
+    options: &Options,
+    api: Option<&ApiDefinition>,
+) -> TResult<()> {
+    if data.is_empty() {
+        println!("(无数据)");
+        return Ok(());
+    }
+
+    let max_rows = row_limit(options, DEFAULT_MAX_ROWS);
+    print_paged(&render_record(data, options, api, max_rows), !options.no_pager)
+}
+
+/// 渲染纵向记录
+pub fn render_record(
+    data: &[HashMap<String, serde_json::Value>],
+    options: &Options,
+    api: Option<&ApiDefinition>,
+    max_rows: Option<usize>,
+) -> String {
+    let fields = select_order(ordered_fields(data, api), options);
+    let formatter = DisplayFormatter::new(options, api);
+    let descriptions: HashMap<&str, &str> = api
+        .map(|api| {
+            api.output_fields
+                .iter()
+                .map(|f| (f.name.as_str(), f.description.as_str()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let display_count = max_rows.unwrap_or(data.len()).min(data.len());
+    let mut blocks = Vec::with_capacity(display_count + 1);
+    for (i, row) in data[..display_count].iter().enumerate() {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec!["字段", "值", "说明"]);
+
+        for field in &fields {
+            let value = row.get(field).map(|v| formatter.format(field, v)).unwrap_or_default();
+            let description = descriptions.get(field.as_str()).copied().unwrap_or_default();
+            table.add_row(vec![field.as_str(), value.as_str(), description]);
+        }
+
+        if data.len() > 1 {
+            blocks.push(format!("第 {} 行 / 共 {} 行\n{}", i + 1, data.len(), table));
+        } else {
+            blocks.push(table.to_string());
+        }
+    }
+
+    if data.len() > display_count {
+        blocks.push(format!(
+            "... (共 {} 行，仅显示前 {} 行，使用 --max-rows 0 显示全部)",
+            data.len(),
+            display_count
+        ));
+    }
+
+    blocks.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn company_api() -> ApiDefinition {
+        ApiDefinition::for_test("stock_company", &[("ts_code", "str", "股票代码"), ("chairman", "str", "法人代表")])
+    }
+
+    #[test]
+    fn test_render_record() {
+        let data = vec![vec![
+            ("ts_code".to_string(), json!("000001.SZ")),
+            ("chairman".to_string(), json!("谢永林")),
+            ("extra".to_string(), json!(1)),
+        ]
+        .into_iter()
+        .collect()];
+
+        let rendered = render_record(&data, &Options::default(), Some(&company_api()), None);
+        assert!(rendered.contains("法人代表"));
+        assert!(rendered.contains("谢永林"));
+
+        // 定义中的字段按定义顺序，其余字段排在最后
+        let ts_code = rendered.find("ts_code").unwrap();
+        let chairman = rendered.find("chairman").unwrap();
+        let extra = rendered.find("extra").unwrap();
+        assert!(ts_code < chairman && chairman < extra);
+    }
+
+    #[test]
+    fn test_render_record_multiple_rows() {
+        let data = vec![
+            vec![("ts_code".to_string(), json!("000001.SZ"))].into_iter().collect(),
+            vec![("ts_code".to_string(), json!("000002.SZ"))].into_iter().collect(),
+        ];
+
+        let rendered = render_record(&data, &Options::default(), None, None);
+        assert!(rendered.contains("第 2 行 / 共 2 行"));
+    }
+
+    #[test]
+    fn test_render_record_row_limit() {
+        let data: Vec<HashMap<String, serde_json::Value>> = (0..30)
+            .map(|i| vec![("id".to_string(), json!(i))].into_iter().collect())
+            .collect();
+
+        let rendered = render_record(&data, &Options::default(), None, Some(2));
+        assert!(rendered.contains("第 2 行 / 共 30 行"));
+        assert!(!rendered.contains("第 3 行"));
+        assert!(rendered.contains("共 30 行，仅显示前 2 行"));
+    }
+}
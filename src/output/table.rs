@@ -1,51 +1,84 @@
 //! 表格格式化输出
 
-use crate::output::{format_value, get_fields};
+use crate::api::ApiDefinition;
+use crate::cli::args::Options;
+use crate::output::display::DisplayFormatter;
+use crate::output::pager::print_paged;
+use crate::output::{ordered_fields, select_order, header_labels, row_limit, truncate_to_width};
 use crate::error::TResult;
-use comfy_table::{presets::UTF8_FULL, Table};
+use comfy_table::{presets::UTF8_FULL, Cell, Color, Table};
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
+/// 终端中默认显示的最大行数
+const DEFAULT_MAX_ROWS: usize = 20;
+
 /// 以表格格式输出数据
-pub fn output_table(data: &[HashMap<String, serde_json::Value>]) -> TResult<()> {
+///
+/// 列宽由 comfy_table 按显示宽度计算，超过 `max_width` 的单元格在字素边界截断。
+/// 在终端中涨跌类字段按 A 股习惯着色（红涨绿跌）。
+pub fn output_table(
+    data: &[HashMap<String, serde_json::Value>],
+    options: &Options,
+    api: Option<&ApiDefinition>,
+) -> TResult<()> {
     if data.is_empty() {
         println!("(无数据)");
         return Ok(());
     }
 
-    let fields = get_fields(data);
+    let max_rows = row_limit(options, DEFAULT_MAX_ROWS);
+    print_paged(&render_table(data, options, api, max_rows), !options.no_pager)
+}
+
+/// 渲染表格
+pub fn render_table(
+    data: &[HashMap<String, serde_json::Value>],
+    options: &Options,
+    api: Option<&ApiDefinition>,
+    max_rows: Option<usize>,
+) -> String {
+    let fields = select_order(ordered_fields(data, api), options);
+    let formatter = DisplayFormatter::new(options, api);
+    let colored = !options.no_color && std::env::var_os("NO_COLOR").is_none();
 
     // 限制显示行数
-    let max_rows = 20;
-    let display_data: Vec<_> = data.iter().take(max_rows).cloned().collect();
+    let display_count = max_rows.unwrap_or(data.len()).min(data.len());
 
     // 创建表格
     let mut table = Table::new();
     table
         .load_preset(UTF8_FULL)
-        .set_header(fields.clone());
+        .set_header(header_labels(&fields, api, options.header));
 
     // 添加数据行
-    for row in &display_data {
-        let mut values = Vec::new();
+    for row in &data[..display_count] {
+        let mut cells = Vec::new();
         for field in &fields {
-            let value = row.get(field).map(format_value).unwrap_or_default();
-            values.push(value);
+            let (text, sign) = match row.get(field) {
+                Some(value) => (formatter.format(field, value), formatter.change_sign(field, value)),
+                None => (String::new(), None),
+            };
+            let cell = Cell::new(truncate_to_width(&text, options.max_width));
+            cells.push(match sign {
+                Some(Ordering::Greater) if colored => cell.fg(Color::Red),
+                Some(Ordering::Less) if colored => cell.fg(Color::Green),
+                _ => cell,
+            });
         }
-        table.add_row(values);
+        table.add_row(cells);
     }
 
     // 如果数据被截断，添加提示
-    if data.len() > max_rows {
+    if data.len() > display_count {
         table.add_row(vec![format!(
-            "... (共 {} 行，仅显示前 {} 行)",
+            "... (共 {} 行，仅显示前 {} 行，使用 --max-rows 0 显示全部)",
             data.len(),
-            max_rows
+            display_count
         )]);
     }
 
-    println!("{}", table);
-
-    Ok(())
+    table.to_string()
 }
 
 #[cfg(test)]
@@ -70,14 +103,45 @@ mod tests {
             .collect(),
         ];
 
-        let result = output_table(&data);
+        let result = output_table(&data, &Options::default(), None);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_output_table_cjk() {
+        let data = vec![vec![
+            ("name".to_string(), json!("平安银行股份有限公司平安银行股份有限公司")),
+            ("area".to_string(), json!("深圳")),
+        ]
+        .into_iter()
+        .collect()];
+
+        let options = Options {
+            max_width: 7,
+            ..Options::default()
+        };
+        let result = output_table(&data, &options, None);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_render_table_row_limit() {
+        let data: Vec<HashMap<String, serde_json::Value>> = (0..30)
+            .map(|i| vec![("id".to_string(), json!(i))].into_iter().collect())
+            .collect();
+
+        let rendered = render_table(&data, &Options::default(), None, Some(20));
+        assert!(rendered.contains("共 30 行，仅显示前 20 行"));
+
+        let rendered = render_table(&data, &Options::default(), None, None);
+        assert!(rendered.contains("29"));
+        assert!(!rendered.contains("仅显示"));
+    }
+
     #[test]
     fn test_output_empty_table() {
         let data: Vec<HashMap<String, serde_json::Value>> = Vec::new();
-        let result = output_table(&data);
+        let result = output_table(&data, &Options::default(), None);
         assert!(result.is_ok());
     }
 }
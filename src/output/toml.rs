@@ -0,0 +1,135 @@
+//! TOML 格式化输出
+//!
+//! 输出为表数组（`[[<接口名>]]`），键按 API 定义顺序排列。TOML 没有空值，
+//! 空字段会被省略；字符串一律加引号，带前导零的代码读回时仍为字符串。
+
+use crate::api::ApiDefinition;
+use crate::cli::args::Options;
+use crate::output::{ordered_fields, select_order};
+use crate::error::TResult;
+use std::collections::HashMap;
+use std::io::Write;
+
+/// 未知接口时使用的表名
+const DEFAULT_TABLE_NAME: &str = "data";
+
+/// 以 TOML 格式输出数据
+pub fn output_toml(
+    data: &[HashMap<String, serde_json::Value>],
+    options: &Options,
+    api: Option<&ApiDefinition>,
+) -> TResult<()> {
+    let stdout = std::io::stdout();
+    let mut handle = stdout.lock();
+
+    write!(handle, "{}", render_toml(data, options, api))
+        .map_err(|e| crate::error::TushareError::OutputError(format!("写入输出失败: {}", e)))?;
+
+    Ok(())
+}
+
+/// 渲染 TOML 文本
+pub fn render_toml(
+    data: &[HashMap<String, serde_json::Value>],
+    options: &Options,
+    api: Option<&ApiDefinition>,
+) -> String {
+    let fields = select_order(ordered_fields(data, api), options);
+    let table = toml_key(api.map(|a| a.name.as_str()).unwrap_or(DEFAULT_TABLE_NAME));
+    let mut out = String::new();
+
+    for (i, row) in data.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        out.push_str(&format!("[[{}]]\n", table));
+        for field in &fields {
+            if let Some(value) = row.get(field).and_then(toml_value) {
+                out.push_str(&format!("{} = {}\n", toml_key(field), value));
+            }
+        }
+    }
+
+    out
+}
+
+/// 格式化 TOML 键，非裸键时加引号
+fn toml_key(key: &str) -> String {
+    let bare = !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare {
+        key.to_string()
+    } else {
+        quote(key)
+    }
+}
+
+/// 格式化 TOML 值，空值返回 None
+fn toml_value(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        serde_json::Value::Number(n) => {
+            if n.is_f64() {
+                // TOML 浮点数必须带小数点或指数
+                let text = n.to_string();
+                if text.contains(['.', 'e', 'E']) {
+                    Some(text)
+                } else {
+                    Some(format!("{}.0", text))
+                }
+            } else {
+                Some(n.to_string())
+            }
+        }
+        serde_json::Value::String(s) => Some(quote(s)),
+        serde_json::Value::Array(items) => {
+            let values: Vec<String> = items.iter().filter_map(toml_value).collect();
+            Some(format!("[{}]", values.join(", ")))
+        }
+        other => Some(quote(&other.to_string())),
+    }
+}
+
+/// 基本字符串（JSON 转义与 TOML 基本字符串兼容）
+fn quote(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_else(|_| format!("\"{}\"", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_toml() {
+        let data = vec![
+            vec![
+                ("ts_code".to_string(), json!("000001.SZ")),
+                ("symbol".to_string(), json!("000001")),
+                ("close".to_string(), json!(10.0)),
+                ("delist_date".to_string(), json!(null)),
+            ]
+            .into_iter()
+            .collect(),
+            vec![("ts_code".to_string(), json!("000002.SZ"))].into_iter().collect(),
+        ];
+
+        let toml = render_toml(&data, &Options::default(), None);
+        assert_eq!(
+            toml,
+            "[[data]]\nclose = 10.0\nsymbol = \"000001\"\nts_code = \"000001.SZ\"\n\n[[data]]\nts_code = \"000002.SZ\"\n"
+        );
+    }
+
+    #[test]
+    fn test_toml_key() {
+        assert_eq!(toml_key("ts_code"), "ts_code");
+        assert_eq!(toml_key("名称"), "\"名称\"");
+        assert_eq!(toml_key("a.b"), "\"a.b\"");
+    }
+
+    #[test]
+    fn test_render_empty_toml() {
+        assert_eq!(render_toml(&[], &Options::default(), None), "");
+    }
+}
@@ -0,0 +1,125 @@
+//! YAML 格式化输出
+//!
+//! 输出为映射列表，键按 API 定义顺序排列。字符串一律使用双引号，
+//! 保证 `000001` 这类带前导零的代码在读回时仍为字符串。
+
+use crate::api::ApiDefinition;
+use crate::cli::args::Options;
+use crate::output::{ordered_fields, select_order};
+use crate::error::TResult;
+use std::collections::HashMap;
+use std::io::Write;
+
+/// 以 YAML 格式输出数据
+pub fn output_yaml(
+    data: &[HashMap<String, serde_json::Value>],
+    options: &Options,
+    api: Option<&ApiDefinition>,
+) -> TResult<()> {
+    let stdout = std::io::stdout();
+    let mut handle = stdout.lock();
+
+    write!(handle, "{}", render_yaml(data, options, api))
+        .map_err(|e| crate::error::TushareError::OutputError(format!("写入输出失败: {}", e)))?;
+
+    Ok(())
+}
+
+/// 渲染 YAML 文本
+pub fn render_yaml(
+    data: &[HashMap<String, serde_json::Value>],
+    options: &Options,
+    api: Option<&ApiDefinition>,
+) -> String {
+    if data.is_empty() {
+        return "[]\n".to_string();
+    }
+
+    let fields = select_order(ordered_fields(data, api), options);
+    let mut out = String::new();
+
+    for row in data {
+        let mut first = true;
+        for field in &fields {
+            let Some(value) = row.get(field) else {
+                continue;
+            };
+            out.push_str(if first { "- " } else { "  " });
+            out.push_str(&yaml_key(field));
+            out.push_str(": ");
+            out.push_str(&yaml_value(value));
+            out.push('\n');
+            first = false;
+        }
+        if first {
+            out.push_str("- {}\n");
+        }
+    }
+
+    out
+}
+
+/// 格式化 YAML 键，非简单标识符时加引号
+fn yaml_key(key: &str) -> String {
+    let plain = !key.is_empty()
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !key.starts_with(|c: char| c.is_ascii_digit());
+    if plain {
+        key.to_string()
+    } else {
+        quote(key)
+    }
+}
+
+/// 格式化 YAML 标量值
+fn yaml_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => "null".to_string(),
+        serde_json::Value::Bool(b) => b.to_string(),
+        serde_json::Value::Number(n) => n.to_string(),
+        serde_json::Value::String(s) => quote(s),
+        // 嵌套结构使用 JSON 流式写法（YAML 1.2 兼容）
+        other => other.to_string(),
+    }
+}
+
+/// 双引号字符串（JSON 转义与 YAML 双引号标量兼容）
+fn quote(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_else(|_| format!("\"{}\"", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_yaml() {
+        let data = vec![vec![
+            ("ts_code".to_string(), json!("000001.SZ")),
+            ("symbol".to_string(), json!("000001")),
+            ("close".to_string(), json!(10.5)),
+            ("vol".to_string(), json!(1000)),
+            ("delist_date".to_string(), json!(null)),
+        ]
+        .into_iter()
+        .collect()];
+
+        let yaml = render_yaml(&data, &Options::default(), None);
+        assert_eq!(
+            yaml,
+            "- close: 10.5\n  delist_date: null\n  symbol: \"000001\"\n  ts_code: \"000001.SZ\"\n  vol: 1000\n"
+        );
+    }
+
+    #[test]
+    fn test_render_yaml_escape() {
+        let data = vec![vec![("名称".to_string(), json!("say \"hi\"\n"))].into_iter().collect()];
+        assert_eq!(render_yaml(&data, &Options::default(), None), "- \"名称\": \"say \\\"hi\\\"\\n\"\n");
+    }
+
+    #[test]
+    fn test_render_empty_yaml() {
+        assert_eq!(render_yaml(&[], &Options::default(), None), "[]\n");
+    }
+}
@@ -0,0 +1,370 @@
+//! 分组聚合
+//!
+//! `--group-by industry --agg "sum(amount),avg(pct_chg),count()"` 按分组字段
+//! 汇总结果，支持 sum/avg/min/max/count/first/last/median，可用 `as` 指定列名。
+
+use crate::api::{ApiDefinition, ApiOutputField};
+use crate::error::{TushareError, TResult};
+use crate::query::{round_to, value_as_f64, FieldKind};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// 聚合结果保留的小数位数（消除浮点累加误差）
+const RESULT_DECIMALS: i32 = 6;
+
+/// 聚合函数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggFunc {
+    Sum,
+    Avg,
+    Min,
+    Max,
+    Count,
+    First,
+    Last,
+    Median,
+}
+
+impl AggFunc {
+    /// 从函数名解析
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "sum" => Some(AggFunc::Sum),
+            "avg" | "mean" => Some(AggFunc::Avg),
+            "min" => Some(AggFunc::Min),
+            "max" => Some(AggFunc::Max),
+            "count" => Some(AggFunc::Count),
+            "first" => Some(AggFunc::First),
+            "last" => Some(AggFunc::Last),
+            "median" => Some(AggFunc::Median),
+            _ => None,
+        }
+    }
+
+    /// 函数名
+    pub fn as_str(&self) -> &str {
+        match self {
+            AggFunc::Sum => "sum",
+            AggFunc::Avg => "avg",
+            AggFunc::Min => "min",
+            AggFunc::Max => "max",
+            AggFunc::Count => "count",
+            AggFunc::First => "first",
+            AggFunc::Last => "last",
+            AggFunc::Median => "median",
+        }
+    }
+
+    /// 函数的中文说明
+    fn description(&self) -> &str {
+        match self {
+            AggFunc::Sum => "合计",
+            AggFunc::Avg => "平均",
+            AggFunc::Min => "最小",
+            AggFunc::Max => "最大",
+            AggFunc::Count => "计数",
+            AggFunc::First => "首个",
+            AggFunc::Last => "末个",
+            AggFunc::Median => "中位数",
+        }
+    }
+}
+
+/// 单个聚合项
+#[derive(Debug, Clone, PartialEq)]
+pub struct Aggregation {
+    /// 聚合函数
+    pub func: AggFunc,
+    /// 源字段（`count()` 为 None）
+    pub field: Option<String>,
+    /// 输出列名
+    pub alias: String,
+}
+
+/// 解析聚合设置，如 `sum(amount),avg(pct_chg) as avg_chg,count()`
+pub fn parse_aggregations(spec: &str) -> TResult<Vec<Aggregation>> {
+    let mut aggregations = Vec::new();
+    for item in split_top_level(spec) {
+        let item = item.trim();
+        if item.is_empty() {
+            continue;
+        }
+        let error = || {
+            TushareError::ParseError(format!(
+                "无效的聚合项: {} (格式为 函数(字段) [as 列名]，函数可选 sum|avg|min|max|count|first|last|median)",
+                item
+            ))
+        };
+
+        let open = item.find('(').ok_or_else(error)?;
+        let close = item.rfind(')').filter(|c| *c > open).ok_or_else(error)?;
+        let func = AggFunc::from_str(item[..open].trim()).ok_or_else(error)?;
+        let field = item[open + 1..close].trim();
+        let field = match field {
+            "" | "*" if func == AggFunc::Count => None,
+            "" | "*" => return Err(error()),
+            f => Some(f.to_string()),
+        };
+
+        let rest = item[close + 1..].trim();
+        let alias = if rest.is_empty() {
+            match &field {
+                Some(f) => format!("{}_{}", func.as_str(), f),
+                None => func.as_str().to_string(),
+            }
+        } else {
+            let alias = rest
+                .strip_prefix("as ")
+                .or_else(|| rest.strip_prefix("AS "))
+                .map(|a| a.trim())
+                .filter(|a| !a.is_empty() && !a.contains(char::is_whitespace))
+                .ok_or_else(error)?;
+            alias.to_string()
+        };
+
+        aggregations.push(Aggregation { func, field, alias });
+    }
+
+    if aggregations.is_empty() {
+        return Err(TushareError::ParseError(format!("无效的聚合设置: {}", spec)));
+    }
+    Ok(aggregations)
+}
+
+/// 按逗号分割，忽略括号内的逗号
+pub(crate) fn split_top_level(spec: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in spec.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&spec[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&spec[start..]);
+    parts
+}
+
+/// 分组聚合，分组按首次出现的顺序排列
+pub fn aggregate(
+    data: &[HashMap<String, serde_json::Value>],
+    group_by: &[String],
+    aggregations: &[Aggregation],
+    kinds: &HashMap<String, FieldKind>,
+) -> Vec<HashMap<String, serde_json::Value>> {
+    let mut order: Vec<Vec<String>> = Vec::new();
+    let mut groups: HashMap<Vec<String>, Vec<&HashMap<String, serde_json::Value>>> = HashMap::new();
+    for row in data {
+        let key: Vec<String> = group_by
+            .iter()
+            .map(|f| row.get(f).map(crate::output::format_value).unwrap_or_default())
+            .collect();
+        groups
+            .entry(key.clone())
+            .or_insert_with(|| {
+                order.push(key);
+                Vec::new()
+            })
+            .push(row);
+    }
+
+    order
+        .iter()
+        .map(|key| {
+            let rows = &groups[key];
+            let mut out = HashMap::new();
+            for field in group_by {
+                let value = rows[0].get(field).cloned().unwrap_or(serde_json::Value::Null);
+                out.insert(field.clone(), value);
+            }
+            for agg in aggregations {
+                out.insert(agg.alias.clone(), compute(agg, rows, kinds));
+            }
+            out
+        })
+        .collect()
+}
+
+/// 计算单个聚合项
+fn compute(
+    agg: &Aggregation,
+    rows: &[&HashMap<String, serde_json::Value>],
+    kinds: &HashMap<String, FieldKind>,
+) -> serde_json::Value {
+    let field = match &agg.field {
+        Some(f) => f,
+        None => return serde_json::json!(rows.len()),
+    };
+    let values: Vec<&serde_json::Value> = rows
+        .iter()
+        .filter_map(|row| row.get(field))
+        .filter(|v| !v.is_null())
+        .collect();
+
+    match agg.func {
+        AggFunc::Count => serde_json::json!(values.len()),
+        AggFunc::First => values.first().map(|v| (*v).clone()).unwrap_or_default(),
+        AggFunc::Last => values.last().map(|v| (*v).clone()).unwrap_or_default(),
+        AggFunc::Min | AggFunc::Max => {
+            let numeric = kinds.get(field).map(|k| *k == FieldKind::Number).unwrap_or_else(|| values.iter().all(|v| v.is_number()));
+            let compare = |a: &&&serde_json::Value, b: &&&serde_json::Value| -> Ordering {
+                if numeric {
+                    if let (Some(x), Some(y)) = (value_as_f64(a), value_as_f64(b)) {
+                        return x.partial_cmp(&y).unwrap_or(Ordering::Equal);
+                    }
+                }
+                crate::output::format_value(a).cmp(&crate::output::format_value(b))
+            };
+            let found = if agg.func == AggFunc::Min {
+                values.iter().min_by(compare)
+            } else {
+                values.iter().max_by(compare)
+            };
+            found.map(|v| (*v).clone()).unwrap_or_default()
+        }
+        AggFunc::Sum | AggFunc::Avg | AggFunc::Median => {
+            let mut numbers: Vec<f64> = values.iter().filter_map(|v| value_as_f64(v)).collect();
+            if numbers.is_empty() {
+                return serde_json::Value::Null;
+            }
+            let result = match agg.func {
+                // 整数求和保持整数，溢出时改用浮点数
+                AggFunc::Sum if values.iter().all(|v| v.is_i64()) => {
+                    match values.iter().filter_map(|v| v.as_i64()).try_fold(0i64, i64::checked_add) {
+                        Some(sum) => return serde_json::json!(sum),
+                        None => numbers.iter().sum(),
+                    }
+                }
+                AggFunc::Sum => numbers.iter().sum(),
+                AggFunc::Avg => numbers.iter().sum::<f64>() / numbers.len() as f64,
+                _ => {
+                    numbers.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+                    let mid = numbers.len() / 2;
+                    if numbers.len() % 2 == 0 {
+                        (numbers[mid - 1] + numbers[mid]) / 2.0
+                    } else {
+                        numbers[mid]
+                    }
+                }
+            };
+            serde_json::json!(round_to(result, RESULT_DECIMALS))
+        }
+    }
+}
+
+/// 生成聚合结果的字段定义：分组字段沿用原定义，聚合列沿用源字段的单位说明
+pub fn aggregate_definition(
+    api: Option<&ApiDefinition>,
+    group_by: &[String],
+    aggregations: &[Aggregation],
+) -> ApiDefinition {
+    let source = |name: &str| api.and_then(|api| api.output_fields.iter().find(|f| f.name == name));
+
+    let mut output_fields: Vec<ApiOutputField> = group_by
+        .iter()
+        .map(|f| {
+            source(f).cloned().unwrap_or_else(|| ApiOutputField::new(f, "", ""))
+        })
+        .collect();
+
+    for agg in aggregations {
+        let field = agg.field.as_deref().and_then(source);
+        let (field_type, description) = match (agg.func, field) {
+            (AggFunc::Count, _) => ("int".to_string(), agg.func.description().to_string()),
+            (_, Some(f)) => (f.field_type.clone(), format!("{}{}", agg.func.description(), f.description)),
+            (_, None) => (String::new(), String::new()),
+        };
+        output_fields.push(ApiOutputField::new(&agg.alias, &field_type, &description));
+    }
+
+    ApiDefinition::derived(api, output_fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn data() -> Vec<HashMap<String, serde_json::Value>> {
+        [("银行", 100.0, Some(1.0), 1), ("证券", 50.0, Some(-2.0), 2), ("银行", 300.0, Some(3.0), 3), ("银行", 200.0, None, 4)]
+            .iter()
+            .map(|(industry, amount, pct, n)| {
+                vec![
+                    ("industry".to_string(), json!(industry)),
+                    ("amount".to_string(), json!(amount)),
+                    ("pct_chg".to_string(), json!(pct)),
+                    ("n".to_string(), json!(n)),
+                ]
+                .into_iter()
+                .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_aggregations() {
+        let aggs = parse_aggregations("sum(amount), avg(pct_chg) as chg, count()").unwrap();
+        assert_eq!(aggs.len(), 3);
+        assert_eq!(aggs[0].alias, "sum_amount");
+        assert_eq!(aggs[1], Aggregation { func: AggFunc::Avg, field: Some("pct_chg".to_string()), alias: "chg".to_string() });
+        assert_eq!(aggs[2], Aggregation { func: AggFunc::Count, field: None, alias: "count".to_string() });
+
+        assert!(parse_aggregations("total(amount)").is_err());
+        assert!(parse_aggregations("sum()").is_err());
+        assert!(parse_aggregations("sum(amount").is_err());
+        assert!(parse_aggregations("sum(amount) total").is_err());
+        assert!(parse_aggregations("").is_err());
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let aggs = parse_aggregations("sum(amount),avg(pct_chg),count(),count(pct_chg),median(amount),first(n),last(n),max(n),sum(n)").unwrap();
+        let rows = aggregate(&data(), &["industry".to_string()], &aggs, &HashMap::new());
+        assert_eq!(rows.len(), 2);
+
+        let bank = &rows[0];
+        assert_eq!(bank["industry"], json!("银行"));
+        assert_eq!(bank["sum_amount"], json!(600.0));
+        assert_eq!(bank["avg_pct_chg"], json!(2.0));
+        assert_eq!(bank["count"], json!(3));
+        assert_eq!(bank["count_pct_chg"], json!(2));
+        assert_eq!(bank["median_amount"], json!(200.0));
+        assert_eq!(bank["first_n"], json!(1));
+        assert_eq!(bank["last_n"], json!(4));
+        assert_eq!(bank["max_n"], json!(4));
+        assert_eq!(bank["sum_n"], json!(8));
+
+        assert_eq!(rows[1]["industry"], json!("证券"));
+    }
+
+    #[test]
+    fn test_aggregate_without_groups() {
+        let aggs = parse_aggregations("sum(amount)").unwrap();
+        let rows = aggregate(&data(), &[], &aggs, &HashMap::new());
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["sum_amount"], json!(650.0));
+
+        // 整数求和溢出时改用浮点数
+        let data: Vec<HashMap<String, serde_json::Value>> =
+            vec![HashMap::from([("n".to_string(), json!(i64::MAX))]), HashMap::from([("n".to_string(), json!(i64::MAX))])];
+        let rows = aggregate(&data, &[], &parse_aggregations("sum(n)").unwrap(), &HashMap::new());
+        assert_eq!(rows[0]["sum_n"].as_f64(), Some(i64::MAX as f64 * 2.0));
+    }
+
+    #[test]
+    fn test_aggregate_definition() {
+        let api = ApiDefinition::for_test("daily", &[("amount", "float", "成交额 （千元）")]);
+        let aggs = parse_aggregations("sum(amount),count()").unwrap();
+        let def = aggregate_definition(Some(&api), &["industry".to_string()], &aggs);
+        let names: Vec<&str> = def.output_fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["industry", "sum_amount", "count"]);
+        assert_eq!(def.output_fields[1].description, "合计成交额 （千元）");
+    }
+}
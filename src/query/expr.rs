@@ -0,0 +1,590 @@
+//! `--where` 过滤表达式
+//!
+//! 支持比较（`== != > >= < <=`）、`in (...)`、`is [not] null`，
+//! 以及 `and`/`or`/`not` 组合和括号，例如 `pct_chg > 9.5 and industry == '银行'`。
+
+use crate::error::{TushareError, TResult};
+use crate::output::{display_width, format_value};
+use crate::query::FieldKind;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// 比较运算符
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl CompareOp {
+    /// 比较结果是否满足运算符
+    fn matches(self, ordering: Ordering) -> bool {
+        match self {
+            CompareOp::Eq => ordering == Ordering::Equal,
+            CompareOp::Ne => ordering != Ordering::Equal,
+            CompareOp::Gt => ordering == Ordering::Greater,
+            CompareOp::Ge => ordering != Ordering::Less,
+            CompareOp::Lt => ordering == Ordering::Less,
+            CompareOp::Le => ordering != Ordering::Greater,
+        }
+    }
+}
+
+/// 字面量（数字保留原文，用于与字符串字段比较，如 `symbol == 000001`）
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Number(f64, String),
+    Str(String),
+    Bool(bool),
+}
+
+impl Literal {
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Literal::Number(n, _) => Some(*n),
+            Literal::Str(s) => s.trim().parse().ok(),
+            Literal::Bool(_) => None,
+        }
+    }
+
+    fn as_text(&self) -> String {
+        match self {
+            Literal::Number(_, raw) => raw.clone(),
+            Literal::Str(s) => s.clone(),
+            Literal::Bool(b) => b.to_string(),
+        }
+    }
+}
+
+/// 比较操作数
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    Field(String),
+    Literal(Literal),
+}
+
+/// 过滤表达式
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Operand, CompareOp, Operand),
+    In { field: String, values: Vec<Literal>, negated: bool },
+    IsNull { field: String, negated: bool },
+}
+
+impl Expr {
+    /// 解析表达式
+    pub fn parse(input: &str) -> TResult<Expr> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { input, tokens, pos: 0 };
+        let expr = parser.parse_or()?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            return Err(parser.error(token.offset, &format!("多余的内容 `{}`", token.text)));
+        }
+        Ok(expr)
+    }
+
+    /// 表达式中引用的字段
+    pub fn fields(&self) -> Vec<&str> {
+        let mut fields = Vec::new();
+        self.collect_fields(&mut fields);
+        fields
+    }
+
+    fn collect_fields<'a>(&'a self, fields: &mut Vec<&'a str>) {
+        match self {
+            Expr::And(a, b) | Expr::Or(a, b) => {
+                a.collect_fields(fields);
+                b.collect_fields(fields);
+            }
+            Expr::Not(e) => e.collect_fields(fields),
+            Expr::Compare(left, _, right) => {
+                for operand in [left, right] {
+                    if let Operand::Field(f) = operand {
+                        fields.push(f);
+                    }
+                }
+            }
+            Expr::In { field, .. } | Expr::IsNull { field, .. } => fields.push(field),
+        }
+    }
+
+    /// 对一行数据求值，空值参与的比较均不成立
+    pub fn eval(&self, row: &HashMap<String, serde_json::Value>, kinds: &HashMap<String, FieldKind>) -> bool {
+        match self {
+            Expr::And(a, b) => a.eval(row, kinds) && b.eval(row, kinds),
+            Expr::Or(a, b) => a.eval(row, kinds) || b.eval(row, kinds),
+            Expr::Not(e) => !e.eval(row, kinds),
+            Expr::IsNull { field, negated } => {
+                let is_null = row.get(field).map(|v| v.is_null()).unwrap_or(true);
+                is_null != *negated
+            }
+            Expr::In { field, values, negated } => {
+                let value = match row.get(field) {
+                    Some(v) if !v.is_null() => v,
+                    _ => return false,
+                };
+                let kind = field_kind(field, value, kinds);
+                let found = values.iter().any(|literal| {
+                    compare(value, &Resolved::Literal(literal), kind) == Some(Ordering::Equal)
+                });
+                found != *negated
+            }
+            Expr::Compare(left, op, right) => {
+                let (left, right) = match (resolve(left, row), resolve(right, row)) {
+                    (Some(l), Some(r)) => (l, r),
+                    _ => return false,
+                };
+                // 比较方式由字段类型决定，字段在左侧时以左侧为准
+                let kind = match (&left, &right) {
+                    (Resolved::Value(f, v), _) => field_kind(f, v, kinds),
+                    (_, Resolved::Value(f, v)) => field_kind(f, v, kinds),
+                    _ => FieldKind::Text,
+                };
+                let ordering = match (&left, &right) {
+                    (Resolved::Value(_, v), other) => compare(v, other, kind),
+                    (Resolved::Literal(l), Resolved::Value(_, v)) => compare(v, &Resolved::Literal(l), kind).map(Ordering::reverse),
+                    (Resolved::Literal(a), Resolved::Literal(b)) => Some(a.as_text().cmp(&b.as_text())),
+                };
+                ordering.map(|o| op.matches(o)).unwrap_or(false)
+            }
+        }
+    }
+}
+
+/// 求值时的操作数
+enum Resolved<'a> {
+    Value(&'a str, &'a serde_json::Value),
+    Literal(&'a Literal),
+}
+
+fn resolve<'a>(operand: &'a Operand, row: &'a HashMap<String, serde_json::Value>) -> Option<Resolved<'a>> {
+    match operand {
+        Operand::Field(f) => row.get(f).filter(|v| !v.is_null()).map(|v| Resolved::Value(f, v)),
+        Operand::Literal(l) => Some(Resolved::Literal(l)),
+    }
+}
+
+/// 字段类型：定义中有类型时使用定义，否则按值推断
+fn field_kind(field: &str, value: &serde_json::Value, kinds: &HashMap<String, FieldKind>) -> FieldKind {
+    kinds.get(field).copied().unwrap_or(if value.is_number() {
+        FieldKind::Number
+    } else {
+        FieldKind::Text
+    })
+}
+
+/// 按字段类型比较值
+fn compare(value: &serde_json::Value, other: &Resolved, kind: FieldKind) -> Option<Ordering> {
+    match kind {
+        FieldKind::Number => {
+            let left = crate::query::value_as_f64(value)?;
+            let right = match other {
+                Resolved::Value(_, v) => crate::query::value_as_f64(v)?,
+                Resolved::Literal(l) => l.as_f64()?,
+            };
+            left.partial_cmp(&right)
+        }
+        FieldKind::Text => {
+            let right = match other {
+                Resolved::Value(_, v) => format_value(v),
+                Resolved::Literal(l) => l.as_text(),
+            };
+            Some(format_value(value).cmp(&right))
+        }
+    }
+}
+
+/// 词法单元
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    kind: TokenKind,
+    text: String,
+    /// 在输入中的字节偏移
+    offset: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Ident,
+    Number(f64),
+    Str(String),
+    Op(CompareOp),
+    LParen,
+    RParen,
+    Comma,
+    And,
+    Or,
+    Not,
+}
+
+/// 词法分析
+fn tokenize(input: &str) -> TResult<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let chars: Vec<(usize, char)> = input.char_indices().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (offset, c) = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        let kind = match c {
+            '(' => {
+                i += 1;
+                TokenKind::LParen
+            }
+            ')' => {
+                i += 1;
+                TokenKind::RParen
+            }
+            ',' => {
+                i += 1;
+                TokenKind::Comma
+            }
+            '\'' | '"' => {
+                i += 1;
+                let mut value = String::new();
+                loop {
+                    match chars.get(i) {
+                        Some((_, ch)) if *ch == c => {
+                            i += 1;
+                            break;
+                        }
+                        Some((_, '\\')) if chars.get(i + 1).is_some() => {
+                            value.push(chars[i + 1].1);
+                            i += 2;
+                        }
+                        Some((_, ch)) => {
+                            value.push(*ch);
+                            i += 1;
+                        }
+                        None => return Err(syntax_error(input, offset, "字符串缺少结束引号")),
+                    }
+                }
+                TokenKind::Str(value)
+            }
+            '=' | '!' | '<' | '>' | '&' | '|' => {
+                let next = chars.get(i + 1).map(|(_, c)| *c);
+                let (kind, len) = match (c, next) {
+                    ('=', Some('=')) => (TokenKind::Op(CompareOp::Eq), 2),
+                    ('=', _) => (TokenKind::Op(CompareOp::Eq), 1),
+                    ('!', Some('=')) => (TokenKind::Op(CompareOp::Ne), 2),
+                    ('!', _) => (TokenKind::Not, 1),
+                    ('<', Some('>')) => (TokenKind::Op(CompareOp::Ne), 2),
+                    ('<', Some('=')) => (TokenKind::Op(CompareOp::Le), 2),
+                    ('<', _) => (TokenKind::Op(CompareOp::Lt), 1),
+                    ('>', Some('=')) => (TokenKind::Op(CompareOp::Ge), 2),
+                    ('>', _) => (TokenKind::Op(CompareOp::Gt), 1),
+                    ('&', Some('&')) => (TokenKind::And, 2),
+                    ('|', Some('|')) => (TokenKind::Or, 2),
+                    _ => return Err(syntax_error(input, offset, &format!("无法识别的字符 `{}`", c))),
+                };
+                i += len;
+                kind
+            }
+            c if c.is_ascii_digit() || c == '.' || (c == '-' && chars.get(i + 1).map(|(_, n)| n.is_ascii_digit() || *n == '.').unwrap_or(false)) => {
+                i += 1;
+                while i < chars.len() && (chars[i].1.is_ascii_digit() || chars[i].1 == '.') {
+                    i += 1;
+                }
+                // 数字开头的代码（如 600000.SH）视为字符串
+                if i < chars.len() && (chars[i].1.is_alphanumeric() || chars[i].1 == '_') {
+                    while i < chars.len() && (chars[i].1.is_alphanumeric() || chars[i].1 == '_' || chars[i].1 == '.') {
+                        i += 1;
+                    }
+                    tokens.push(Token {
+                        kind: TokenKind::Str(slice(input, &chars, start, i).to_string()),
+                        text: slice(input, &chars, start, i).to_string(),
+                        offset,
+                    });
+                    continue;
+                }
+                let text = slice(input, &chars, start, i);
+                let number = text
+                    .parse()
+                    .map_err(|_| syntax_error(input, offset, &format!("无效的数字 `{}`", text)))?;
+                TokenKind::Number(number)
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                while i < chars.len() && (chars[i].1.is_alphanumeric() || chars[i].1 == '_' || chars[i].1 == '.') {
+                    i += 1;
+                }
+                match slice(input, &chars, start, i).to_lowercase().as_str() {
+                    "and" => TokenKind::And,
+                    "or" => TokenKind::Or,
+                    "not" => TokenKind::Not,
+                    _ => TokenKind::Ident,
+                }
+            }
+            _ => return Err(syntax_error(input, offset, &format!("无法识别的字符 `{}`", c))),
+        };
+
+        tokens.push(Token {
+            kind,
+            text: slice(input, &chars, start, i).to_string(),
+            offset,
+        });
+    }
+
+    Ok(tokens)
+}
+
+/// 按字符下标截取输入
+fn slice<'a>(input: &'a str, chars: &[(usize, char)], start: usize, end: usize) -> &'a str {
+    let from = chars[start].0;
+    let to = chars.get(end).map(|(o, _)| *o).unwrap_or(input.len());
+    &input[from..to]
+}
+
+/// 生成带位置标记的语法错误
+fn syntax_error(input: &str, offset: usize, message: &str) -> TushareError {
+    TushareError::ParseError(format!(
+        "--where 表达式错误: {}\n  {}\n  {}^",
+        message,
+        input,
+        " ".repeat(display_width(&input[..offset]))
+    ))
+}
+
+/// 递归下降语法分析
+struct Parser<'a> {
+    input: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, offset: usize, message: &str) -> TushareError {
+        syntax_error(self.input, offset, message)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> TResult<Token> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| self.error(self.input.len(), "表达式不完整"))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    /// 当前词法单元是否为指定关键字（不区分大小写）
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        self.peek()
+            .map(|t| t.kind == TokenKind::Ident && t.text.eq_ignore_ascii_case(keyword))
+            .unwrap_or(false)
+    }
+
+    fn parse_or(&mut self) -> TResult<Expr> {
+        let mut left = self.parse_and()?;
+        while matches!(self.peek().map(|t| &t.kind), Some(TokenKind::Or)) {
+            self.pos += 1;
+            left = Expr::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> TResult<Expr> {
+        let mut left = self.parse_not()?;
+        while matches!(self.peek().map(|t| &t.kind), Some(TokenKind::And)) {
+            self.pos += 1;
+            left = Expr::And(Box::new(left), Box::new(self.parse_not()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> TResult<Expr> {
+        if matches!(self.peek().map(|t| &t.kind), Some(TokenKind::Not)) {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> TResult<Expr> {
+        if matches!(self.peek().map(|t| &t.kind), Some(TokenKind::LParen)) {
+            self.pos += 1;
+            let expr = self.parse_or()?;
+            let token = self.next()?;
+            if token.kind != TokenKind::RParen {
+                return Err(self.error(token.offset, "缺少右括号 `)`"));
+            }
+            return Ok(expr);
+        }
+
+        let left = self.parse_operand()?;
+
+        // field is [not] null
+        if self.peek_keyword("is") {
+            self.pos += 1;
+            let negated = matches!(self.peek().map(|t| &t.kind), Some(TokenKind::Not));
+            if negated {
+                self.pos += 1;
+            }
+            let token = self.next()?;
+            if !(token.kind == TokenKind::Ident && token.text.eq_ignore_ascii_case("null")) {
+                return Err(self.error(token.offset, "`is` 后应为 `null` 或 `not null`"));
+            }
+            return Ok(Expr::IsNull { field: self.field_name(left)?, negated });
+        }
+
+        // field [not] in (...)
+        let negated = matches!(self.peek().map(|t| &t.kind), Some(TokenKind::Not));
+        if negated {
+            self.pos += 1;
+        }
+        if self.peek_keyword("in") {
+            self.pos += 1;
+            let field = self.field_name(left)?;
+            return Ok(Expr::In { field, values: self.parse_list()?, negated });
+        }
+        if negated {
+            let offset = self.peek().map(|t| t.offset).unwrap_or(self.input.len());
+            return Err(self.error(offset, "`not` 后应为 `in`"));
+        }
+
+        let token = self.next()?;
+        let op = match token.kind {
+            TokenKind::Op(op) => op,
+            _ => return Err(self.error(token.offset, &format!("应为比较运算符，实际为 `{}`", token.text))),
+        };
+        let right = self.parse_operand()?;
+        Ok(Expr::Compare(left, op, right))
+    }
+
+    fn parse_operand(&mut self) -> TResult<Operand> {
+        let token = self.next()?;
+        match token.kind {
+            TokenKind::Ident => match token.text.to_lowercase().as_str() {
+                "true" => Ok(Operand::Literal(Literal::Bool(true))),
+                "false" => Ok(Operand::Literal(Literal::Bool(false))),
+                _ => Ok(Operand::Field(token.text)),
+            },
+            TokenKind::Number(n) => Ok(Operand::Literal(Literal::Number(n, token.text))),
+            TokenKind::Str(s) => Ok(Operand::Literal(Literal::Str(s))),
+            _ => Err(self.error(token.offset, &format!("应为字段名或值，实际为 `{}`", token.text))),
+        }
+    }
+
+    fn parse_list(&mut self) -> TResult<Vec<Literal>> {
+        let token = self.next()?;
+        if token.kind != TokenKind::LParen {
+            return Err(self.error(token.offset, "`in` 后应为 `(`"));
+        }
+        let mut values = Vec::new();
+        loop {
+            let token = self.next()?;
+            match token.kind {
+                TokenKind::Number(n) => values.push(Literal::Number(n, token.text)),
+                TokenKind::Str(s) => values.push(Literal::Str(s)),
+                TokenKind::Ident => values.push(Literal::Str(token.text)),
+                _ => return Err(self.error(token.offset, &format!("应为列表值，实际为 `{}`", token.text))),
+            }
+            let token = self.next()?;
+            match token.kind {
+                TokenKind::Comma => continue,
+                TokenKind::RParen => break,
+                _ => return Err(self.error(token.offset, "应为 `,` 或 `)`")),
+            }
+        }
+        Ok(values)
+    }
+
+    fn field_name(&self, operand: Operand) -> TResult<String> {
+        match operand {
+            Operand::Field(f) => Ok(f),
+            Operand::Literal(l) => Err(TushareError::ParseError(format!(
+                "--where 表达式错误: `{}` 不是字段名",
+                l.as_text()
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn row() -> HashMap<String, serde_json::Value> {
+        vec![
+            ("ts_code".to_string(), json!("000001.SZ")),
+            ("symbol".to_string(), json!("000001")),
+            ("industry".to_string(), json!("银行")),
+            ("pct_chg".to_string(), json!(10.01)),
+            ("open".to_string(), json!(10.0)),
+            ("close".to_string(), json!(11.0)),
+            ("delist_date".to_string(), json!(null)),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn eval(expr: &str) -> bool {
+        let kinds = HashMap::from([("symbol".to_string(), FieldKind::Text)]);
+        Expr::parse(expr).unwrap().eval(&row(), &kinds)
+    }
+
+    #[test]
+    fn test_compare() {
+        assert!(eval("pct_chg > 9.5 and industry == '银行'"));
+        assert!(!eval("pct_chg > 9.5 and industry == \"证券\""));
+        assert!(eval("pct_chg < 0 or close > open"));
+        assert!(eval("not (pct_chg <= 10)"));
+        assert!(eval("pct_chg != -1 && industry <> '证券'"));
+    }
+
+    #[test]
+    fn test_text_field_keeps_leading_zeros() {
+        assert!(eval("symbol == 000001"));
+        assert!(!eval("symbol == 1"));
+    }
+
+    #[test]
+    fn test_in_and_null() {
+        assert!(eval("industry in ('银行', '证券')"));
+        assert!(eval("ts_code not in (600000.SH)"));
+        assert!(eval("ts_code == 000001.SZ"));
+        assert!(eval("delist_date is null"));
+        assert!(eval("close is not null"));
+        // 空值参与的比较不成立
+        assert!(!eval("delist_date > 0"));
+        assert!(!eval("delist_date != 0"));
+    }
+
+    #[test]
+    fn test_fields() {
+        let expr = Expr::parse("a > 1 and (b == 'x' or c in (1, 2))").unwrap();
+        assert_eq!(expr.fields(), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Expr::parse("pct_chg >").unwrap_err().to_string();
+        assert!(err.contains("表达式不完整"));
+
+        let err = Expr::parse("pct_chg 9.5").unwrap_err().to_string();
+        assert!(err.contains("应为比较运算符"));
+        assert!(err.contains("\n          ^"));
+
+        assert!(Expr::parse("industry == '银行").is_err());
+        assert!(Expr::parse("(a > 1").is_err());
+        assert!(Expr::parse("a > 1 b").is_err());
+        assert!(Expr::parse("a in 1").is_err());
+        assert!(Expr::parse("a # 1").is_err());
+    }
+}
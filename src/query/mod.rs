@@ -0,0 +1,363 @@
+//! 结果后处理模块
+//!
+//! 在输出前对返回的数据依次执行 `--where` 过滤、`--group-by/--agg` 分组聚合、
+//! `--sort` 排序和 `--select` 投影，弥补部分接口不支持服务端筛选的不足。
+//! 比较和排序按 API 定义中的字段类型进行。
+
+pub mod expr;
+pub mod aggregate;
+
+pub use expr::Expr;
+pub use aggregate::{aggregate, parse_aggregations, AggFunc, Aggregation};
+
+use crate::api::ApiDefinition;
+use crate::cli::args::Options;
+use crate::client::Row;
+use crate::error::{TushareError, TResult};
+use crate::output::{format_value, get_fields};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// 字段比较类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    /// 数值（float/int）
+    Number,
+    /// 文本（str/datetime，如代码、日期）
+    Text,
+}
+
+/// 排序键
+#[derive(Debug, Clone, PartialEq)]
+pub struct SortKey {
+    /// 字段名
+    pub field: String,
+    /// 是否降序
+    pub descending: bool,
+}
+
+/// 解析排序设置，如 `-amount,ts_code`（`-` 表示降序）
+pub fn parse_sort(spec: &str) -> TResult<Vec<SortKey>> {
+    let keys: Vec<SortKey> = spec
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| match s.strip_prefix('-') {
+            Some(field) => SortKey { field: field.trim().to_string(), descending: true },
+            None => SortKey { field: s.trim_start_matches('+').trim().to_string(), descending: false },
+        })
+        .collect();
+
+    if keys.is_empty() || keys.iter().any(|k| k.field.is_empty()) {
+        return Err(TushareError::ParseError(format!("无效的排序设置: {} (例如 -amount,ts_code)", spec)));
+    }
+    Ok(keys)
+}
+
+/// 根据 API 定义获取字段比较类型，未知类型的字段按值推断
+pub fn field_kinds(api: Option<&ApiDefinition>) -> HashMap<String, FieldKind> {
+    api.map(|api| {
+        api.output_fields
+            .iter()
+            .filter_map(|f| {
+                let kind = match f.field_type.as_str() {
+                    "float" | "int" | "number" => FieldKind::Number,
+                    "str" | "datetime" | "date" => FieldKind::Text,
+                    _ => return None,
+                };
+                Some((f.name.clone(), kind))
+            })
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// 读取数值（兼容字符串形式的数字）
+pub fn value_as_f64(value: &serde_json::Value) -> Option<f64> {
+    match value {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// 字段的文本值，缺失时为空字符串
+pub fn field_text(row: &Row, field: &str) -> String {
+    row.get(field).map(format_value).unwrap_or_default()
+}
+
+/// 保留指定小数位
+pub fn round_to(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+/// 转为 JSON 数值，空值或无法表示（NaN、无穷）时为 null
+pub fn number_value(value: Option<f64>) -> serde_json::Value {
+    value
+        .and_then(serde_json::Number::from_f64)
+        .map(serde_json::Value::Number)
+        .unwrap_or(serde_json::Value::Null)
+}
+
+/// 后处理结果
+#[derive(Debug, Clone, Default)]
+pub struct QueryResult {
+    /// 处理后的数据
+    pub rows: Vec<HashMap<String, serde_json::Value>>,
+    /// 聚合后的字段顺序（分组字段在前，聚合列按设置顺序）
+    pub columns: Option<Vec<String>>,
+    /// 聚合后的字段定义，沿用源字段的类型和说明
+    pub api: Option<ApiDefinition>,
+}
+
+/// 是否设置了任何后处理
+pub fn is_active(options: &Options) -> bool {
+    options.where_clause.is_some()
+        || options.group_by.is_some()
+        || options.agg.is_some()
+        || options.sort.is_some()
+        || options.select.is_some()
+}
+
+/// 依次执行过滤、分组聚合、排序和投影
+pub fn apply(
+    data: &[HashMap<String, serde_json::Value>],
+    options: &Options,
+    api: Option<&ApiDefinition>,
+) -> TResult<QueryResult> {
+    let mut kinds = field_kinds(api);
+    let mut known = known_fields(data, api);
+
+    let mut rows: Vec<HashMap<String, serde_json::Value>> = match &options.where_clause {
+        Some(clause) => {
+            let expr = Expr::parse(clause)?;
+            check_fields(expr.fields(), &known, "--where")?;
+            data.iter().filter(|row| expr.eval(row, &kinds)).cloned().collect()
+        }
+        None => data.to_vec(),
+    };
+
+    // 分组聚合，之后的排序和投影作用于聚合结果
+    let mut result = QueryResult::default();
+    if options.group_by.is_some() || options.agg.is_some() {
+        let group_by = options.group_by.clone().unwrap_or_default();
+        let aggregations = parse_aggregations(options.agg.as_deref().unwrap_or("count()"))?;
+        check_fields(group_by.iter().map(|s| s.as_str()).collect(), &known, "--group-by")?;
+        check_fields(aggregations.iter().filter_map(|a| a.field.as_deref()).collect(), &known, "--agg")?;
+
+        rows = aggregate(&rows, &group_by, &aggregations, &kinds);
+        let definition = aggregate::aggregate_definition(api, &group_by, &aggregations);
+        known = definition.output_fields.iter().map(|f| f.name.clone()).collect();
+        kinds = field_kinds(Some(&definition));
+        result.columns = Some(known.clone());
+        result.api = Some(definition);
+    }
+
+    if let Some(spec) = &options.sort {
+        let keys = parse_sort(spec)?;
+        check_fields(keys.iter().map(|k| k.field.as_str()).collect(), &known, "--sort")?;
+        sort_rows(&mut rows, &keys, &kinds);
+    }
+
+    if let Some(select) = &options.select {
+        check_fields(select.iter().map(|s| s.as_str()).collect(), &known, "--select")?;
+        for row in &mut rows {
+            row.retain(|k, _| select.contains(k));
+        }
+    }
+
+    result.rows = rows;
+    Ok(result)
+}
+
+/// 数据和定义中的全部字段
+fn known_fields(data: &[HashMap<String, serde_json::Value>], api: Option<&ApiDefinition>) -> Vec<String> {
+    let mut fields = get_fields(data);
+    if let Some(api) = api {
+        for f in &api.output_fields {
+            if !fields.contains(&f.name) {
+                fields.push(f.name.clone());
+            }
+        }
+    }
+    fields
+}
+
+/// 检查引用的字段是否存在（无数据且无定义时不检查）
+fn check_fields(fields: Vec<&str>, known: &[String], option: &str) -> TResult<()> {
+    if known.is_empty() {
+        return Ok(());
+    }
+    match fields.into_iter().find(|f| !known.iter().any(|k| k == f)) {
+        Some(field) => Err(TushareError::ValidationError(format!(
+            "{} 中的字段 {} 不存在 (可用字段: {})",
+            option,
+            field,
+            known.join(", ")
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// 按排序键稳定排序，空值始终排在最后
+pub fn sort_rows(
+    rows: &mut [HashMap<String, serde_json::Value>],
+    keys: &[SortKey],
+    kinds: &HashMap<String, FieldKind>,
+) {
+    rows.sort_by(|a, b| {
+        for key in keys {
+            let left = a.get(&key.field).filter(|v| !v.is_null());
+            let right = b.get(&key.field).filter(|v| !v.is_null());
+            let ordering = match (left, right) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(l), Some(r)) => {
+                    let ordering = compare_values(&key.field, l, r, kinds);
+                    if key.descending {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    }
+                }
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+        Ordering::Equal
+    });
+}
+
+/// 按字段类型比较两个值
+fn compare_values(
+    field: &str,
+    left: &serde_json::Value,
+    right: &serde_json::Value,
+    kinds: &HashMap<String, FieldKind>,
+) -> Ordering {
+    let numeric = match kinds.get(field) {
+        Some(kind) => *kind == FieldKind::Number,
+        None => left.is_number() && right.is_number(),
+    };
+    if numeric {
+        if let (Some(l), Some(r)) = (value_as_f64(left), value_as_f64(right)) {
+            return l.partial_cmp(&r).unwrap_or(Ordering::Equal);
+        }
+    }
+    format_value(left).cmp(&format_value(right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn api() -> ApiDefinition {
+        ApiDefinition::for_test("daily", &[("ts_code", "str", ""), ("amount", "float", ""), ("pct_chg", "float", "")])
+    }
+
+    fn data() -> Vec<HashMap<String, serde_json::Value>> {
+        [("000001.SZ", json!(100.0), 10.0), ("600000.SH", json!("250.5"), -1.2), ("000002.SZ", json!(null), 9.9), ("300750.SZ", json!(99.0), 2.0)]
+            .iter()
+            .map(|(code, amount, pct)| {
+                vec![
+                    ("ts_code".to_string(), json!(code)),
+                    ("amount".to_string(), amount.clone()),
+                    ("pct_chg".to_string(), json!(pct)),
+                ]
+                .into_iter()
+                .collect()
+            })
+            .collect()
+    }
+
+    fn codes(rows: &[HashMap<String, serde_json::Value>]) -> Vec<&str> {
+        rows.iter().map(|r| r["ts_code"].as_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_parse_sort() {
+        assert_eq!(
+            parse_sort("-amount, ts_code").unwrap(),
+            vec![
+                SortKey { field: "amount".to_string(), descending: true },
+                SortKey { field: "ts_code".to_string(), descending: false },
+            ]
+        );
+        assert!(parse_sort("").is_err());
+        assert!(parse_sort("-").is_err());
+    }
+
+    #[test]
+    fn test_row_helpers() {
+        let row = &data()[0];
+        assert_eq!(field_text(row, "ts_code"), "000001.SZ");
+        assert_eq!(field_text(row, "missing"), "");
+        assert_eq!(round_to(1.23456, 2), 1.23);
+        assert_eq!(number_value(Some(1.5)), serde_json::json!(1.5));
+        assert_eq!(number_value(Some(f64::NAN)), serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_sort_typed_with_nulls_last() {
+        let options = Options { sort: Some("-amount".to_string()), ..Options::default() };
+        let rows = apply(&data(), &options, Some(&api())).unwrap().rows;
+        // 字符串形式的数字按 float 类型比较，空值排在最后
+        assert_eq!(codes(&rows), vec!["600000.SH", "000001.SZ", "300750.SZ", "000002.SZ"]);
+    }
+
+    #[test]
+    fn test_where_sort_select() {
+        let options = Options {
+            where_clause: Some("pct_chg > 0".to_string()),
+            sort: Some("ts_code".to_string()),
+            select: Some(vec!["ts_code".to_string(), "pct_chg".to_string()]),
+            ..Options::default()
+        };
+        let rows = apply(&data(), &options, Some(&api())).unwrap().rows;
+        assert_eq!(codes(&rows), vec!["000001.SZ", "000002.SZ", "300750.SZ"]);
+        assert!(rows.iter().all(|r| r.len() == 2 && !r.contains_key("amount")));
+    }
+
+    #[test]
+    fn test_group_by_then_sort() {
+        let options = Options {
+            where_clause: Some("amount is not null".to_string()),
+            group_by: Some(vec!["market".to_string()]),
+            agg: Some("sum(amount),count()".to_string()),
+            sort: Some("-sum_amount".to_string()),
+            ..Options::default()
+        };
+        let data: Vec<HashMap<String, serde_json::Value>> = data()
+            .into_iter()
+            .map(|mut row| {
+                let market = if row["ts_code"].as_str().unwrap().ends_with(".SH") { "SH" } else { "SZ" };
+                row.insert("market".to_string(), json!(market));
+                row
+            })
+            .collect();
+
+        let result = apply(&data, &options, Some(&api())).unwrap();
+        assert_eq!(result.columns, Some(vec!["market".to_string(), "sum_amount".to_string(), "count".to_string()]));
+        assert_eq!(result.rows[0]["market"], json!("SH"));
+        assert_eq!(result.rows[0]["sum_amount"], json!(250.5));
+        assert_eq!(result.rows[1]["sum_amount"], json!(199.0));
+        assert_eq!(result.rows[1]["count"], json!(2));
+
+        let options = Options { sort: Some("amount".to_string()), ..options };
+        assert!(apply(&data, &options, Some(&api())).is_err());
+    }
+
+    #[test]
+    fn test_unknown_field() {
+        let options = Options { where_clause: Some("close > 1".to_string()), ..Options::default() };
+        let err = apply(&data(), &options, Some(&api())).unwrap_err().to_string();
+        assert!(err.contains("--where 中的字段 close 不存在"));
+
+        let options = Options { select: Some(vec!["vol".to_string()]), ..Options::default() };
+        assert!(apply(&data(), &options, None).is_err());
+    }
+}
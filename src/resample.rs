@@ -0,0 +1,421 @@
+//! K 线重采样
+//!
+//! `--resample 2h|30min|D|W|M|Q|Y` 将 K 线结果按 `ts_code` 分组合并为更大的周期：
+//! 开盘取第一根、收盘取最后一根、最高/最低取极值、成交量/成交额求和。
+//!
+//! 分钟线（含 `trade_time` 字段，如 `stk_mins`）按 A 股交易时段
+//! 09:30–11:30、13:00–15:00 共 240 分钟切分，午休不计入周期，K 线时间为周期结束时间；
+//! 日线（含 `trade_date` 字段）按自然周（周一至周日）、月、季、年合并，日期为周期内
+//! 最后一个交易日，与 `weekly`/`monthly` 接口一致。
+
+use crate::api::{ApiDefinition, ApiOutputField};
+use crate::client::Row;
+use crate::error::{TushareError, TResult};
+use crate::output::format_value;
+use crate::query::{field_text, number_value, round_to, value_as_f64};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use std::collections::HashMap;
+
+/// 每个交易日的交易分钟数
+const SESSION_MINUTES: u32 = 240;
+
+/// 上午交易分钟数
+const MORNING_MINUTES: u32 = 120;
+
+/// 输出的分钟线时间格式
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// 成交量、涨跌等结果保留的小数位数
+const RESULT_DECIMALS: i32 = 4;
+
+/// 重采样周期
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    /// N 分钟（按交易时段计算）
+    Minutes(u32),
+    /// 日
+    Day,
+    /// 周
+    Week,
+    /// 月
+    Month,
+    /// 季
+    Quarter,
+    /// 年
+    Year,
+}
+
+impl Frequency {
+    /// 从字符串解析周期，如 `30min`、`2h`、`D`、`W`、`M`、`Q`、`Y`
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let lower = s.to_lowercase();
+        let minutes = if let Some(n) = lower.strip_suffix("min") {
+            n.parse::<u32>().ok()
+        } else if let Some(n) = lower.strip_suffix('h') {
+            n.parse::<u32>().ok().and_then(|n| n.checked_mul(60))
+        } else {
+            None
+        };
+        if let Some(minutes) = minutes {
+            return (minutes > 0 && minutes <= SESSION_MINUTES).then_some(Frequency::Minutes(minutes));
+        }
+
+        match lower.as_str() {
+            "d" => Some(Frequency::Day),
+            "w" => Some(Frequency::Week),
+            "m" => Some(Frequency::Month),
+            "q" => Some(Frequency::Quarter),
+            "y" => Some(Frequency::Year),
+            _ => None,
+        }
+    }
+
+    /// 按日期划分的周期标识
+    fn period_of(&self, date: NaiveDate) -> (i32, u32) {
+        match self {
+            Frequency::Minutes(_) | Frequency::Day => (date.year(), date.ordinal()),
+            Frequency::Week => {
+                let week = date.iso_week();
+                (week.year(), week.week())
+            }
+            Frequency::Month => (date.year(), date.month()),
+            Frequency::Quarter => (date.year(), (date.month() - 1) / 3),
+            Frequency::Year => (date.year(), 0),
+        }
+    }
+}
+
+/// 一根 K 线的时间
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum BarTime {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+impl BarTime {
+    fn date(&self) -> NaiveDate {
+        match self {
+            BarTime::Date(d) => *d,
+            BarTime::DateTime(t) => t.date(),
+        }
+    }
+}
+
+/// 重采样，结果按证券首次出现的顺序分组，组内最新的 K 线在前
+pub fn resample(
+    data: &[Row],
+    freq: Frequency,
+) -> TResult<Vec<Row>> {
+    let Some(first) = data.first() else {
+        return Ok(Vec::new());
+    };
+    let intraday = first.contains_key("trade_time");
+    if !intraday && !first.contains_key("trade_date") {
+        return Err(TushareError::ValidationError(
+            "重采样需要 trade_time（分钟线）或 trade_date（日线）字段".to_string(),
+        ));
+    }
+    if let (Frequency::Minutes(_), false) = (freq, intraday) {
+        return Err(TushareError::ValidationError(
+            "按分钟重采样需要分钟线数据（如 stk_mins，含 trade_time 字段）".to_string(),
+        ));
+    }
+    for field in ["open", "high", "low", "close"] {
+        if !first.contains_key(field) {
+            return Err(TushareError::ValidationError(format!("重采样需要 {} 字段", field)));
+        }
+    }
+
+    // 按证券分组，组内按时间升序
+    let mut codes: Vec<String> = Vec::new();
+    let mut groups: HashMap<String, Vec<(BarTime, &Row)>> = HashMap::new();
+    for row in data {
+        let code = row.get("ts_code").map(format_value).unwrap_or_default();
+        let time = parse_time(row, intraday)?;
+        if !groups.contains_key(&code) {
+            codes.push(code.clone());
+        }
+        groups.entry(code).or_default().push((time, row));
+    }
+
+    let mut result = Vec::new();
+    for code in codes {
+        let mut bars = groups.remove(&code).unwrap_or_default();
+        bars.sort_by_key(|(time, _)| *time);
+
+        let mut merged: Vec<Row> = Vec::new();
+        let mut current: Option<(Bucket, Vec<&Row>)> = None;
+        for (time, row) in bars {
+            let bucket = Bucket::of(time, freq);
+            match &mut current {
+                Some((b, rows)) if b.key == bucket.key => {
+                    b.label = bucket.label;
+                    rows.push(row);
+                }
+                _ => {
+                    if let Some((b, rows)) = current.take() {
+                        merged.push(merge_rows(&rows, b.label));
+                    }
+                    current = Some((bucket, vec![row]));
+                }
+            }
+        }
+        if let Some((b, rows)) = current {
+            merged.push(merge_rows(&rows, b.label));
+        }
+
+        merged.reverse();
+        result.extend(merged);
+    }
+
+    Ok(result)
+}
+
+/// 重采样后的字段定义：分钟线合并为日及以上周期时 `trade_time` 换为 `trade_date`
+pub fn resample_definition(api: Option<&ApiDefinition>, intraday: bool, freq: Frequency) -> Option<ApiDefinition> {
+    let mut output_fields = api?.output_fields.clone();
+    if intraday && !matches!(freq, Frequency::Minutes(_)) {
+        for field in &mut output_fields {
+            if field.name == "trade_time" {
+                *field = ApiOutputField::new("trade_date", "str", "交易日期");
+            }
+        }
+    }
+    Some(ApiDefinition::derived(api, output_fields))
+}
+
+/// 周期分组：`key` 用于判断是否同一周期，`label` 为输出的时间
+#[derive(Debug, Clone, PartialEq)]
+struct Bucket {
+    key: (i32, u32, u32),
+    label: Label,
+}
+
+/// 合并后 K 线的时间
+#[derive(Debug, Clone, PartialEq)]
+enum Label {
+    /// 日期（YYYYMMDD），写入 trade_date
+    Date(String),
+    /// 时间，写入 trade_time
+    Time(String),
+}
+
+impl Bucket {
+    fn of(time: BarTime, freq: Frequency) -> Self {
+        let date = time.date();
+        let (year, period) = freq.period_of(date);
+        match (freq, time) {
+            (Frequency::Minutes(minutes), BarTime::DateTime(t)) => {
+                let index = session_minute(t.time()).max(1).div_ceil(minutes);
+                let end = (index * minutes).min(SESSION_MINUTES);
+                let label = date.and_time(session_clock(end));
+                Bucket {
+                    key: (year, period, index),
+                    label: Label::Time(label.format(TIME_FORMAT).to_string()),
+                }
+            }
+            _ => Bucket {
+                key: (year, period, 0),
+                label: Label::Date(date.format("%Y%m%d").to_string()),
+            },
+        }
+    }
+}
+
+/// 距开盘的交易分钟数（不计午休），开盘前为 0，收盘后为 240
+fn session_minute(time: NaiveTime) -> u32 {
+    let minute = time.hour() * 60 + time.minute();
+    let (open, noon, afternoon) = (9 * 60 + 30, 11 * 60 + 30, 13 * 60);
+    if minute <= open {
+        0
+    } else if minute <= noon {
+        minute - open
+    } else if minute <= afternoon {
+        MORNING_MINUTES
+    } else {
+        (MORNING_MINUTES + minute - afternoon).min(SESSION_MINUTES)
+    }
+}
+
+/// 交易分钟数对应的时钟时间
+fn session_clock(elapsed: u32) -> NaiveTime {
+    let minute = if elapsed <= MORNING_MINUTES {
+        9 * 60 + 30 + elapsed
+    } else {
+        13 * 60 + elapsed - MORNING_MINUTES
+    };
+    NaiveTime::from_hms_opt(minute / 60, minute % 60, 0).unwrap_or_default()
+}
+
+fn parse_time(row: &Row, intraday: bool) -> TResult<BarTime> {
+    let field = if intraday { "trade_time" } else { "trade_date" };
+    let text = field_text(row, field);
+    let parsed = if intraday {
+        ["%Y-%m-%d %H:%M:%S", "%Y%m%d %H:%M:%S", "%Y-%m-%d %H:%M"]
+            .iter()
+            .find_map(|f| NaiveDateTime::parse_from_str(&text, f).ok())
+            .map(BarTime::DateTime)
+    } else {
+        NaiveDate::parse_from_str(&text, "%Y%m%d").ok().map(BarTime::Date)
+    };
+    parsed.ok_or_else(|| TushareError::ValidationError(format!("无法解析 {}: {}", field, text)))
+}
+
+/// 合并同一周期的 K 线（按时间升序）
+fn merge_rows(rows: &[&Row], label: Label) -> Row {
+    let first = rows[0];
+    let last = rows[rows.len() - 1];
+    let prices = |field: &'static str| rows.iter().filter_map(move |r| r.get(field).and_then(value_as_f64));
+
+    let mut bar = HashMap::new();
+    if let Some(code) = first.get("ts_code") {
+        bar.insert("ts_code".to_string(), code.clone());
+    }
+    match label {
+        Label::Date(date) => bar.insert("trade_date".to_string(), serde_json::json!(date)),
+        Label::Time(time) => bar.insert("trade_time".to_string(), serde_json::json!(time)),
+    };
+
+    bar.insert("open".to_string(), first["open"].clone());
+    bar.insert("high".to_string(), number(prices("high").reduce(f64::max)));
+    bar.insert("low".to_string(), number(prices("low").reduce(f64::min)));
+    bar.insert("close".to_string(), last["close"].clone());
+
+    for field in ["vol", "amount"] {
+        if first.contains_key(field) {
+            bar.insert(field.to_string(), number(Some(prices(field).sum())));
+        }
+    }
+
+    if let Some(pre_close) = first.get("pre_close") {
+        bar.insert("pre_close".to_string(), pre_close.clone());
+        let close = last.get("close").and_then(value_as_f64);
+        let change = close.zip(value_as_f64(pre_close)).map(|(c, p)| c - p);
+        let pct_chg = change.zip(value_as_f64(pre_close)).and_then(|(c, p)| (p != 0.0).then(|| c / p * 100.0));
+        bar.insert("change".to_string(), number(change));
+        bar.insert("pct_chg".to_string(), number(pct_chg));
+    }
+
+    bar
+}
+
+/// 保留指定小数位的数值，空值为 null
+fn number(value: Option<f64>) -> serde_json::Value {
+    number_value(value.map(|v| round_to(v, RESULT_DECIMALS)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::TushareResponse;
+    use serde_json::json;
+
+    fn daily() -> Vec<Row> {
+        serde_json::from_str::<TushareResponse>(include_str!("../tests/fixtures/indicators_000001_daily.json"))
+            .unwrap()
+            .to_objects()
+    }
+
+    fn minute(time: &str, open: f64, high: f64, low: f64, close: f64, vol: f64) -> Row {
+        [
+            ("ts_code", json!("600000.SH")),
+            ("trade_time", json!(time)),
+            ("open", json!(open)),
+            ("high", json!(high)),
+            ("low", json!(low)),
+            ("close", json!(close)),
+            ("vol", json!(vol)),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect()
+    }
+
+    fn minutes() -> Vec<Row> {
+        vec![
+            minute("2024-01-02 15:00:00", 7.05, 7.06, 7.04, 7.05, 300.0),
+            minute("2024-01-02 13:01:00", 7.02, 7.04, 7.01, 7.03, 200.0),
+            minute("2024-01-02 11:30:00", 7.00, 7.02, 6.99, 7.01, 150.0),
+            minute("2024-01-02 10:30:00", 6.98, 7.00, 6.97, 6.99, 120.0),
+            minute("2024-01-02 10:31:00", 6.99, 7.01, 6.98, 7.00, 110.0),
+            minute("2024-01-02 09:30:00", 6.95, 6.95, 6.95, 6.95, 500.0),
+            minute("2024-01-02 09:31:00", 6.95, 6.97, 6.94, 6.96, 100.0),
+        ]
+    }
+
+    #[test]
+    fn test_parse_frequency() {
+        assert_eq!(Frequency::from_str("30min"), Some(Frequency::Minutes(30)));
+        assert_eq!(Frequency::from_str("2H"), Some(Frequency::Minutes(120)));
+        assert_eq!(Frequency::from_str("W"), Some(Frequency::Week));
+        assert_eq!(Frequency::from_str("m"), Some(Frequency::Month));
+        assert_eq!(Frequency::from_str("q"), Some(Frequency::Quarter));
+        assert_eq!(Frequency::from_str("5h"), None);
+        assert_eq!(Frequency::from_str("0min"), None);
+        assert_eq!(Frequency::from_str("week"), None);
+    }
+
+    #[test]
+    fn test_session_boundaries() {
+        let bars = resample(&minutes(), Frequency::Minutes(60)).unwrap();
+        let times: Vec<&str> = bars.iter().map(|b| b["trade_time"].as_str().unwrap()).collect();
+        // 09:30 的集合竞价并入第一根，13:01 属于午后第一个小时
+        assert_eq!(times, ["2024-01-02 15:00:00", "2024-01-02 14:00:00", "2024-01-02 11:30:00", "2024-01-02 10:30:00"]);
+
+        let first_hour = &bars[3];
+        assert_eq!(first_hour["open"], json!(6.95));
+        assert_eq!(first_hour["close"], json!(6.99));
+        assert_eq!(first_hour["high"], json!(7.0));
+        assert_eq!(first_hour["low"], json!(6.94));
+        assert_eq!(first_hour["vol"], json!(720.0));
+
+        // 2 小时 K 线正好是上午和下午两个交易时段
+        let bars = resample(&minutes(), Frequency::Minutes(120)).unwrap();
+        let times: Vec<&str> = bars.iter().map(|b| b["trade_time"].as_str().unwrap()).collect();
+        assert_eq!(times, ["2024-01-02 15:00:00", "2024-01-02 11:30:00"]);
+        assert_eq!(bars[1]["vol"], json!(980.0));
+        assert_eq!(bars[0]["open"], json!(7.02));
+    }
+
+    #[test]
+    fn test_minutes_to_daily() {
+        let bars = resample(&minutes(), Frequency::Day).unwrap();
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0]["trade_date"], json!("20240102"));
+        assert!(!bars[0].contains_key("trade_time"));
+        assert_eq!(bars[0]["close"], json!(7.05));
+        assert_eq!(bars[0]["vol"], json!(1480.0));
+    }
+
+    #[test]
+    fn test_daily_to_weekly() {
+        let bars = resample(&daily(), Frequency::Week).unwrap();
+        // 2024-01-02 至 2024-02-26 共 9 周，最新的在前
+        assert_eq!(bars.len(), 9);
+        assert_eq!(bars[0]["trade_date"], json!("20240226"));
+        assert_eq!(bars[8]["trade_date"], json!("20240105"));
+
+        // 第一周 20240102-20240105，周线日期为最后一个交易日
+        let week = &bars[8];
+        assert_eq!(week["open"], json!(9.95));
+        assert_eq!(week["high"], json!(10.08));
+        assert_eq!(week["low"], json!(8.89));
+        assert_eq!(week["close"], json!(9.16));
+        assert_eq!(week["pre_close"], json!(10.0));
+        assert_eq!(week["change"], json!(-0.84));
+        assert_eq!(week["pct_chg"], json!(-8.4));
+        assert_eq!(week["vol"], json!(3490010.11));
+    }
+
+    #[test]
+    fn test_daily_to_quarter_and_errors() {
+        let bars = resample(&daily(), Frequency::Quarter).unwrap();
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0]["close"], json!(7.24));
+
+        assert!(resample(&daily(), Frequency::Minutes(60)).is_err());
+        assert!(resample(&[], Frequency::Week).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,22 @@
+//! 本地存储模块
+//!
+//! 负责将 API 返回的数据持久化到本地，并记录同步状态。
+
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStore;
+
+/// 同步状态
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncState {
+    /// API 名称
+    pub api_name: String,
+    /// 最后同步的交易日期
+    pub last_date: String,
+    /// 最近一次同步写入的行数
+    pub rows: usize,
+    /// 最近一次同步时间
+    pub updated_at: String,
+}
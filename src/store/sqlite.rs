@@ -0,0 +1,455 @@
+//! SQLite 存储实现
+
+use crate::error::TResult;
+use crate::store::SyncState;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// 同步状态表名
+const SYNC_STATE_TABLE: &str = "_sync_state";
+
+pub use crate::client::Row;
+
+/// SQLite 本地存储
+///
+/// 每个 API 对应一张同名数据表，同步状态记录在 `_sync_state` 表中。
+pub struct SqliteStore {
+    conn: Connection,
+}
+
+impl SqliteStore {
+    /// 打开（或创建）存储文件
+    pub fn open(path: &Path) -> TResult<Self> {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        Self::init(Connection::open(path)?)
+    }
+
+    /// 创建内存存储（用于测试）
+    pub fn open_in_memory() -> TResult<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    /// 初始化同步状态表
+    fn init(conn: Connection) -> TResult<Self> {
+        conn.execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS {} (
+                api_name TEXT PRIMARY KEY,
+                last_date TEXT NOT NULL,
+                rows INTEGER NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            SYNC_STATE_TABLE
+        ))?;
+        Ok(Self { conn })
+    }
+
+    /// 检查数据表是否存在
+    pub fn table_exists(&self, table: &str) -> TResult<bool> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+            params![table],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// 获取数据表中某列的最大值（以文本形式返回）
+    pub fn max_value(&self, table: &str, column: &str) -> TResult<Option<String>> {
+        if !self.table_exists(table)? || !self.columns(table)?.contains(column) {
+            return Ok(None);
+        }
+
+        let sql = format!(
+            "SELECT CAST(MAX({}) AS TEXT) FROM {}",
+            quote_ident(column),
+            quote_ident(table)
+        );
+        let value: Option<String> = self.conn.query_row(&sql, [], |row| row.get(0))?;
+        Ok(value)
+    }
+
+    /// 写入数据行
+    ///
+    /// 表不存在时自动创建，缺少的列自动补齐。`field_types` 为字段名到
+    /// API 定义中字段类型（str/int/float）的映射。指定 `replace_key` 时，
+    /// 会先删除该列取值与本批数据相同的旧行，保证重复同步不产生重复数据。
+    pub fn write_rows(
+        &mut self,
+        table: &str,
+        fields: &[String],
+        items: &[Vec<serde_json::Value>],
+        field_types: &HashMap<String, String>,
+        replace_key: Option<&str>,
+    ) -> TResult<usize> {
+        if fields.is_empty() {
+            return Ok(0);
+        }
+
+        self.ensure_table(table, fields, field_types)?;
+
+        let tx = self.conn.transaction()?;
+
+        if let Some(key) = replace_key {
+            if let Some(idx) = fields.iter().position(|f| f == key) {
+                let keys: HashSet<String> = items
+                    .iter()
+                    .filter_map(|item| item.get(idx))
+                    .map(value_to_key)
+                    .collect();
+                let sql = format!(
+                    "DELETE FROM {} WHERE CAST({} AS TEXT) = ?1",
+                    quote_ident(table),
+                    quote_ident(key)
+                );
+                for k in &keys {
+                    tx.execute(&sql, params![k])?;
+                }
+            }
+        }
+
+        let columns: Vec<String> = fields.iter().map(|f| quote_ident(f)).collect();
+        let placeholders: Vec<String> = (1..=fields.len()).map(|i| format!("?{}", i)).collect();
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            quote_ident(table),
+            columns.join(", "),
+            placeholders.join(", ")
+        );
+
+        {
+            let mut stmt = tx.prepare(&sql)?;
+            for item in items {
+                let values: Vec<rusqlite::types::Value> = (0..fields.len())
+                    .map(|i| item.get(i).map(to_sql_value).unwrap_or(rusqlite::types::Value::Null))
+                    .collect();
+                stmt.execute(rusqlite::params_from_iter(values))?;
+            }
+        }
+
+        tx.commit()?;
+
+        Ok(items.len())
+    }
+
+    /// 获取 API 的同步状态
+    pub fn sync_state(&self, api_name: &str) -> TResult<Option<SyncState>> {
+        let state = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT api_name, last_date, rows, updated_at FROM {} WHERE api_name = ?1",
+                    SYNC_STATE_TABLE
+                ),
+                params![api_name],
+                |row| {
+                    Ok(SyncState {
+                        api_name: row.get(0)?,
+                        last_date: row.get(1)?,
+                        rows: row.get::<_, i64>(2)? as usize,
+                        updated_at: row.get(3)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(state)
+    }
+
+    /// 记录 API 的同步状态
+    pub fn record_sync(&self, api_name: &str, last_date: &str, rows: usize) -> TResult<()> {
+        let updated_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        self.conn.execute(
+            &format!(
+                "INSERT INTO {} (api_name, last_date, rows, updated_at) VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(api_name) DO UPDATE SET
+                    last_date = excluded.last_date,
+                    rows = excluded.rows,
+                    updated_at = excluded.updated_at",
+                SYNC_STATE_TABLE
+            ),
+            params![api_name, last_date, rows as i64, updated_at],
+        )?;
+        Ok(())
+    }
+
+    /// 执行只读查询，返回结果列名（按查询顺序）和数据行
+    ///
+    /// 重名的列（如自连接的 `SELECT a.*, b.*`）依次重命名为 `close_2`、`close_3`……
+    pub fn query(&self, sql: &str) -> TResult<(Vec<String>, Vec<Row>)> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let columns = unique_columns(stmt.column_names());
+
+        let mut rows = Vec::new();
+        let mut cursor = stmt.query([])?;
+        while let Some(row) = cursor.next()? {
+            let mut item = HashMap::new();
+            for (i, column) in columns.iter().enumerate() {
+                item.insert(column.clone(), from_sql_value(row.get_ref(i)?));
+            }
+            rows.push(item);
+        }
+
+        Ok((columns, rows))
+    }
+
+    /// 获取数据表的列名
+    fn columns(&self, table: &str) -> TResult<HashSet<String>> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("PRAGMA table_info({})", quote_ident(table)))?;
+        let columns = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .collect::<Result<HashSet<_>, _>>()?;
+        Ok(columns)
+    }
+
+    /// 确保数据表存在且包含所有字段
+    fn ensure_table(
+        &self,
+        table: &str,
+        fields: &[String],
+        field_types: &HashMap<String, String>,
+    ) -> TResult<()> {
+        let column_def = |f: &String| {
+            let field_type = field_types.get(f).map(|s| s.as_str()).unwrap_or("str");
+            format!("{} {}", quote_ident(f), sql_type(field_type))
+        };
+
+        if !self.table_exists(table)? {
+            let defs: Vec<String> = fields.iter().map(column_def).collect();
+            self.conn.execute_batch(&format!(
+                "CREATE TABLE {} ({})",
+                quote_ident(table),
+                defs.join(", ")
+            ))?;
+            return Ok(());
+        }
+
+        let existing = self.columns(table)?;
+        for field in fields.iter().filter(|f| !existing.contains(*f)) {
+            self.conn.execute_batch(&format!(
+                "ALTER TABLE {} ADD COLUMN {}",
+                quote_ident(table),
+                column_def(field)
+            ))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// 将 API 字段类型映射为 SQLite 列类型
+fn sql_type(field_type: &str) -> &'static str {
+    match field_type {
+        "float" => "REAL",
+        "int" => "INTEGER",
+        _ => "TEXT",
+    }
+}
+
+/// 为重名的列追加序号，保证每列在行中都有独立的键
+fn unique_columns(names: Vec<&str>) -> Vec<String> {
+    let mut seen: HashSet<String> = names.iter().map(|n| n.to_string()).collect();
+    let mut used = HashSet::new();
+    names
+        .into_iter()
+        .map(|name| {
+            if used.insert(name.to_string()) {
+                return name.to_string();
+            }
+            let mut n = 2;
+            let unique = loop {
+                let candidate = format!("{}_{}", name, n);
+                if !seen.contains(&candidate) {
+                    break candidate;
+                }
+                n += 1;
+            };
+            seen.insert(unique.clone());
+            used.insert(unique.clone());
+            unique
+        })
+        .collect()
+}
+
+/// 转义 SQL 标识符
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// 将 JSON 值转换为用于比较的文本键
+fn value_to_key(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// 将 JSON 值转换为 SQLite 值
+fn to_sql_value(value: &serde_json::Value) -> rusqlite::types::Value {
+    use rusqlite::types::Value;
+
+    match value {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Integer(*b as i64),
+        serde_json::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::Integer(i)
+            } else {
+                Value::Real(n.as_f64().unwrap_or(0.0))
+            }
+        }
+        serde_json::Value::String(s) => Value::Text(s.clone()),
+        other => Value::Text(other.to_string()),
+    }
+}
+
+/// 将 SQLite 值转换为 JSON 值
+fn from_sql_value(value: rusqlite::types::ValueRef) -> serde_json::Value {
+    use rusqlite::types::ValueRef;
+
+    match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(i) => serde_json::json!(i),
+        ValueRef::Real(f) => serde_json::Number::from_f64(f)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        ValueRef::Text(t) => serde_json::Value::String(String::from_utf8_lossy(t).into_owned()),
+        ValueRef::Blob(b) => serde_json::Value::String(format!("[{} 字节]", b.len())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn daily_fields() -> Vec<String> {
+        vec!["ts_code".to_string(), "trade_date".to_string(), "close".to_string()]
+    }
+
+    #[test]
+    fn test_write_and_max_value() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        assert_eq!(store.max_value("daily", "trade_date").unwrap(), None);
+
+        let items = vec![
+            vec![json!("000001.SZ"), json!("20240102"), json!(9.39)],
+            vec![json!("000001.SZ"), json!("20240103"), json!(9.19)],
+        ];
+        let written = store
+            .write_rows("daily", &daily_fields(), &items, &HashMap::new(), Some("trade_date"))
+            .unwrap();
+
+        assert_eq!(written, 2);
+        assert!(store.table_exists("daily").unwrap());
+        assert_eq!(
+            store.max_value("daily", "trade_date").unwrap(),
+            Some("20240103".to_string())
+        );
+    }
+
+    #[test]
+    fn test_write_replaces_same_key() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        let items = vec![vec![json!("000001.SZ"), json!("20240102"), json!(9.39)]];
+
+        store
+            .write_rows("daily", &daily_fields(), &items, &HashMap::new(), Some("trade_date"))
+            .unwrap();
+        store
+            .write_rows("daily", &daily_fields(), &items, &HashMap::new(), Some("trade_date"))
+            .unwrap();
+
+        let count: i64 = store
+            .conn
+            .query_row("SELECT COUNT(*) FROM daily", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_query() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        let mut field_types = HashMap::new();
+        field_types.insert("close".to_string(), "float".to_string());
+        let items = vec![
+            vec![json!("000001.SZ"), json!("20240102"), json!(9.0)],
+            vec![json!("000001.SZ"), json!("20240103"), json!(10.0)],
+        ];
+        store
+            .write_rows("daily", &daily_fields(), &items, &field_types, None)
+            .unwrap();
+
+        let (columns, rows) = store
+            .query("SELECT ts_code, avg(close) AS avg_close, count(*) AS n FROM daily GROUP BY ts_code")
+            .unwrap();
+        assert_eq!(columns, vec!["ts_code", "avg_close", "n"]);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["ts_code"], json!("000001.SZ"));
+        assert_eq!(rows[0]["avg_close"], json!(9.5));
+        assert_eq!(rows[0]["n"], json!(2));
+
+        assert!(store.query("SELECT * FROM missing").is_err());
+    }
+
+    #[test]
+    fn test_query_duplicate_columns() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        let mut field_types = HashMap::new();
+        field_types.insert("close".to_string(), "float".to_string());
+        let items = vec![
+            vec![json!("000001.SZ"), json!("20240102"), json!(9.0)],
+            vec![json!("000001.SZ"), json!("20240103"), json!(10.0)],
+        ];
+        store
+            .write_rows("daily", &daily_fields(), &items, &field_types, None)
+            .unwrap();
+
+        let (columns, rows) = store
+            .query(
+                "SELECT a.close, b.close, 1 AS close_2 FROM daily a JOIN daily b \
+                 ON a.ts_code = b.ts_code AND a.trade_date < b.trade_date",
+            )
+            .unwrap();
+        assert_eq!(columns, vec!["close", "close_3", "close_2"]);
+        assert_eq!(rows[0]["close"], json!(9.0));
+        assert_eq!(rows[0]["close_3"], json!(10.0));
+        assert_eq!(rows[0]["close_2"], json!(1));
+    }
+
+    #[test]
+    fn test_write_adds_missing_columns() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        let items = vec![vec![json!("000001.SZ"), json!("20240102"), json!(9.39)]];
+        store
+            .write_rows("daily", &daily_fields(), &items, &HashMap::new(), None)
+            .unwrap();
+
+        let mut fields = daily_fields();
+        fields.push("vol".to_string());
+        let items = vec![vec![json!("000001.SZ"), json!("20240103"), json!(9.19), json!(1000)]];
+        store
+            .write_rows("daily", &fields, &items, &HashMap::new(), None)
+            .unwrap();
+
+        assert!(store.columns("daily").unwrap().contains("vol"));
+    }
+
+    #[test]
+    fn test_sync_state() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        assert!(store.sync_state("daily").unwrap().is_none());
+
+        store.record_sync("daily", "20240102", 5000).unwrap();
+        store.record_sync("daily", "20240103", 5001).unwrap();
+
+        let state = store.sync_state("daily").unwrap().unwrap();
+        assert_eq!(state.last_date, "20240103");
+        assert_eq!(state.rows, 5001);
+    }
+}
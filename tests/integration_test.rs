@@ -1,6 +1,6 @@
 //! 集成测试
 
-use tushare::cli::{parse_args, handle_command};
+use tushare::cli::parse_args;
 use tushare::cli::args::{OutputFormat, ParamValue};
 
 #[test]